@@ -46,7 +46,20 @@ function generateSummary(country) {
   return parts.join('; ') + '.';
 }
 
+// mledoze/countries has no bounding boxes, so they are maintained by hand
+// and carried over from the previous output
+async function loadExistingBboxes() {
+  try {
+    const existing = JSON.parse(await fs.readFile(OUTPUT_PATH, 'utf8'));
+    return new Map(existing.filter(c => c.bbox).map(c => [c.slug, c.bbox]));
+  } catch {
+    return new Map();
+  }
+}
+
 async function main() {
+  const bboxes = await loadExistingBboxes();
+
   console.log('Fetching country data from mledoze/countries...');
   const rawData = await fetchJSON(SOURCE_URL);
   
@@ -57,6 +70,8 @@ async function main() {
     .filter(c => c.cca2 && c.name?.common) // Must have ISO2 and name
     .map(country => ({
       iso2: country.cca2,
+      iso3: country.cca3,
+      iso_numeric: country.ccn3 || '',
       slug: country.cca2.toLowerCase(),
       name: country.name.common,
       region: country.region || 'Unknown',
@@ -67,7 +82,25 @@ async function main() {
         ...(country.altSpellings || [])
       ].filter((name, i, arr) => 
         name !== country.name.common && arr.indexOf(name) === i
-      ).slice(0, 3) // Keep max 3 aliases
+      ).slice(0, 3), // Keep max 3 aliases
+      demonyms: [
+        country.demonyms?.eng?.m,
+        country.demonyms?.eng?.f,
+      ].filter((name, i, arr) => name && arr.indexOf(name) === i),
+      capital: country.capital?.[0] || null,
+      centroid: country.latlng?.length === 2
+        ? { lat: country.latlng[0], lon: country.latlng[1] }
+        : null,
+      bbox: bboxes.get(country.cca2.toLowerCase()) || null,
+      currencies: Object.entries(country.currencies || {}).map(([code, c]) => ({
+        code,
+        name: c.name,
+        symbol: c.symbol || '',
+      })),
+      languages: Object.entries(country.languages || {}).map(([code, name]) => ({
+        code,
+        name,
+      })),
     }))
     .sort((a, b) => a.name.localeCompare(b.name)); // Sort alphabetically
   
@@ -18,9 +18,17 @@ async function main() {
     name: country.name,
     iso2: country.iso2 || country.slug.toUpperCase(),
     iso3: country.iso3 || country.iso2?.toUpperCase() || country.slug.toUpperCase().slice(0, 3),
+    iso_numeric: country.iso_numeric || '',
     summary: country.summary || `Country: ${country.name}`,
     region: country.region || 'Unknown',
     subregion: country.subregion || 'Unknown',
+    aliases: country.aliases || [],
+    demonyms: country.demonyms || [],
+    capital: country.capital || null,
+    centroid: country.centroid || null,
+    bbox: country.bbox || null,
+    currencies: country.currencies || [],
+    languages: country.languages || [],
   }));
   
   console.log('Sample entries:');
@@ -0,0 +1,396 @@
+use super::entities::PlaceResolver;
+use super::groups::CountryGroups;
+use super::metadata::{find_country, get_countries_metadata};
+use super::note_index::load_note_index;
+use super::topics::load_topics_manifest;
+use mapanote_lib::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Places without a region (e.g. custom entities with no parent) are counted here
+const NO_REGION: &str = "Other";
+
+/// Length of the periods in the created/edited series
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsPeriod {
+    #[default]
+    Month, // "2025-03"
+    Quarter, // "2025-Q1"
+    Year,    // "2025"
+}
+
+impl AnalyticsPeriod {
+    /// `(year, index)` of the period containing a `YYYY-MM...` date
+    fn of(self, date: &str) -> Option<(i32, u32)> {
+        let year = date.get(..4)?.parse().ok()?;
+        let month: u32 = date.get(5..7)?.parse().ok()?;
+        if !(1..=12).contains(&month) {
+            return None;
+        }
+
+        Some(match self {
+            AnalyticsPeriod::Month => (year, month),
+            AnalyticsPeriod::Quarter => (year, (month - 1) / 3 + 1),
+            AnalyticsPeriod::Year => (year, 1),
+        })
+    }
+
+    fn per_year(self) -> u32 {
+        match self {
+            AnalyticsPeriod::Month => 12,
+            AnalyticsPeriod::Quarter => 4,
+            AnalyticsPeriod::Year => 1,
+        }
+    }
+
+    fn label(self, (year, index): (i32, u32)) -> String {
+        match self {
+            AnalyticsPeriod::Month => format!("{}-{:02}", year, index),
+            AnalyticsPeriod::Quarter => format!("{}-Q{}", year, index),
+            AnalyticsPeriod::Year => year.to_string(),
+        }
+    }
+
+    /// Every period from the first to the last key, so charts get no gaps
+    fn fill<T: Default + Clone>(self, counts: &BTreeMap<(i32, u32), T>) -> Vec<(String, T)> {
+        let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) else {
+            return Vec::new();
+        };
+
+        let mut series = Vec::new();
+        let mut current = first;
+        while current <= last {
+            let value = counts.get(&current).cloned().unwrap_or_default();
+            series.push((self.label(current), value));
+
+            current = if current.1 == self.per_year() {
+                (current.0 + 1, 1)
+            } else {
+                (current.0, current.1 + 1)
+            };
+        }
+
+        series
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegionCount {
+    pub region: String,
+    pub notes: usize,
+    pub countries_covered: usize,
+    pub countries_total: usize, // Embedded countries in the region
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubregionCount {
+    pub region: String,
+    pub subregion: String,
+    pub notes: usize,
+    pub countries_covered: usize,
+    pub countries_total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub notes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopicCount {
+    pub topic_id: String,
+    pub title: String,
+    pub color: Option<String>,
+    pub notes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MonthCount {
+    pub month: String, // "YYYY-MM", from the note date
+    pub notes: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PeriodActivity {
+    pub period: String,
+    pub created: usize,
+    pub edited: usize, // Notes last edited in the period after being created
+}
+
+#[derive(Debug, Serialize)]
+pub struct Coverage {
+    pub countries_covered: usize,
+    pub countries_total: usize,
+    pub share: f64, // 0.0 to 1.0
+}
+
+/// Note counts for charts. A topic note counts once for every region and
+/// subregion it targets (directly or through a group), so those counts can
+/// add up to more than `total_notes`.
+#[derive(Debug, Serialize)]
+pub struct VaultAnalytics {
+    pub total_notes: usize,
+    pub regions: Vec<RegionCount>,
+    pub subregions: Vec<SubregionCount>,
+    pub tags: Vec<TagCount>,
+    pub topics: Vec<TopicCount>,
+    pub months: Vec<MonthCount>,
+    pub activity: Vec<PeriodActivity>,
+    pub coverage: Coverage,
+}
+
+#[derive(Default)]
+struct PlaceCounts {
+    notes: usize,
+    covered: HashSet<String>,
+    total: usize,
+}
+
+fn by_notes<T>(items: &mut [T], notes: impl Fn(&T) -> usize, name: impl Fn(&T) -> &str) {
+    items.sort_by(|a, b| notes(b).cmp(&notes(a)).then_with(|| name(a).cmp(name(b))));
+}
+
+pub fn vault_analytics(
+    vault_root: &Path,
+    period: AnalyticsPeriod,
+) -> Result<VaultAnalytics, String> {
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let resolver = PlaceResolver::load(&vault_path)?;
+    let groups = CountryGroups::load(Some(&vault_path));
+    let topics_manifest = load_topics_manifest(&vault_path)?;
+    let entries = load_note_index(vault_root)?;
+
+    let mut regions: BTreeMap<String, PlaceCounts> = BTreeMap::new();
+    let mut subregions: BTreeMap<(String, String), PlaceCounts> = BTreeMap::new();
+    for country in get_countries_metadata() {
+        regions.entry(country.region.clone()).or_default().total += 1;
+        subregions
+            .entry((country.region.clone(), country.subregion.clone()))
+            .or_default()
+            .total += 1;
+    }
+
+    let mut tags: HashMap<String, usize> = HashMap::new();
+    let mut topic_notes: HashMap<String, usize> = HashMap::new();
+    let mut months: BTreeMap<(i32, u32), usize> = BTreeMap::new();
+    let mut activity: BTreeMap<(i32, u32), PeriodActivity> = BTreeMap::new();
+    let mut covered: HashSet<String> = HashSet::new();
+
+    for entry in &entries {
+        let places = if entry.source_type == "country" {
+            vec![entry.owner_id.clone()]
+        } else {
+            *topic_notes.entry(entry.owner_id.clone()).or_default() += 1;
+            groups.expand_targets(&entry.country_targets)
+        };
+
+        // Count the note once per region and subregion it touches
+        let mut note_regions = HashSet::new();
+        let mut note_subregions = HashSet::new();
+        for slug in &places {
+            let Some(place) = resolver.resolve(slug) else {
+                continue;
+            };
+            let region = if place.region.is_empty() {
+                NO_REGION.to_string()
+            } else {
+                place.region
+            };
+            let is_country = find_country(slug).is_some();
+            if is_country {
+                covered.insert(slug.clone());
+            }
+
+            let counts = regions.entry(region.clone()).or_default();
+            if note_regions.insert(region.clone()) {
+                counts.notes += 1;
+            }
+            if is_country {
+                counts.covered.insert(slug.clone());
+            }
+
+            let key = (region, place.subregion);
+            let counts = subregions.entry(key.clone()).or_default();
+            if note_subregions.insert(key) {
+                counts.notes += 1;
+            }
+            if is_country {
+                counts.covered.insert(slug.clone());
+            }
+        }
+
+        for tag in &entry.tags {
+            *tags.entry(tag.clone()).or_default() += 1;
+        }
+
+        if let Some(month) = AnalyticsPeriod::Month.of(&entry.date) {
+            *months.entry(month).or_default() += 1;
+        }
+
+        if let Some(key) = period.of(&entry.created_at) {
+            activity.entry(key).or_default().created += 1;
+        }
+        if entry.updated_at > entry.created_at {
+            if let Some(key) = period.of(&entry.updated_at) {
+                activity.entry(key).or_default().edited += 1;
+            }
+        }
+    }
+
+    let mut regions: Vec<RegionCount> = regions
+        .into_iter()
+        .map(|(region, counts)| RegionCount {
+            region,
+            notes: counts.notes,
+            countries_covered: counts.covered.len(),
+            countries_total: counts.total,
+        })
+        .collect();
+    by_notes(&mut regions, |r| r.notes, |r| &r.region);
+
+    let mut subregions: Vec<SubregionCount> = subregions
+        .into_iter()
+        .map(|((region, subregion), counts)| SubregionCount {
+            region,
+            subregion,
+            notes: counts.notes,
+            countries_covered: counts.covered.len(),
+            countries_total: counts.total,
+        })
+        .collect();
+    by_notes(&mut subregions, |s| s.notes, |s| &s.subregion);
+
+    let mut tags: Vec<TagCount> = tags
+        .into_iter()
+        .map(|(tag, notes)| TagCount { tag, notes })
+        .collect();
+    by_notes(&mut tags, |t| t.notes, |t| &t.tag);
+
+    let mut topics: Vec<TopicCount> = topics_manifest
+        .topics
+        .iter()
+        .map(|topic| TopicCount {
+            topic_id: topic.id.clone(),
+            title: topic.title.clone(),
+            color: topic.color.clone(),
+            notes: topic_notes.get(&topic.id).copied().unwrap_or(0),
+        })
+        .collect();
+    by_notes(&mut topics, |t| t.notes, |t| &t.title);
+
+    let countries_total = get_countries_metadata().len();
+
+    Ok(VaultAnalytics {
+        total_notes: entries.len(),
+        regions,
+        subregions,
+        tags,
+        topics,
+        months: AnalyticsPeriod::Month
+            .fill(&months)
+            .into_iter()
+            .map(|(month, notes)| MonthCount { month, notes })
+            .collect(),
+        activity: period
+            .fill(&activity)
+            .into_iter()
+            .map(|(period, counts)| PeriodActivity { period, ..counts })
+            .collect(),
+        coverage: Coverage {
+            countries_covered: covered.len(),
+            countries_total,
+            share: if countries_total == 0 {
+                0.0
+            } else {
+                covered.len() as f64 / countries_total as f64
+            },
+        },
+    })
+}
+
+/// Note counts by region, subregion, tag, topic and month, plus created and
+/// edited notes per `period` (monthly by default) and country coverage
+#[tauri::command]
+pub fn get_vault_analytics(
+    period: Option<AnalyticsPeriod>,
+    state: State<AppState>,
+) -> Result<VaultAnalytics, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    vault_analytics(&PathBuf::from(vault_path), period.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+    use std::fs;
+
+    #[test]
+    fn test_counts_regions_months_and_coverage() {
+        let vault = TestVault::new();
+        let finland = vault.join("notes").join("fi");
+        let topic = vault.join("topics").join("T1");
+        fs::create_dir_all(&finland).unwrap();
+        fs::create_dir_all(&topic).unwrap();
+        fs::write(
+            vault.join("topics.json"),
+            r#"{"version": "1.0", "topics": [{"id": "T1", "title": "Energy", "summary": null, "color": null, "pinned": false, "created_at": "", "updated_at": ""}], "relations": []}"#,
+        )
+        .unwrap();
+        fs::write(
+            finland.join("01A.md"),
+            "---\nid: 01A\ndate: 2025-01-10\ntitle: Grid\ntags: [energy]\ncreated_at: 2025-01-10T08:00:00.000Z\nupdated_at: 2025-03-02T08:00:00.000Z\n---\n\nBody",
+        )
+        .unwrap();
+        fs::write(
+            topic.join("01B.md"),
+            "---\nid: 01B\ndate: 2025-03-01\ntitle: Nordic power\ntags: [energy, grid]\ntopic_id: T1\ncountry_targets: [group:nordic]\ncreated_at: 2025-03-01T08:00:00.000Z\n---\n\nBody",
+        )
+        .unwrap();
+
+        let analytics = vault_analytics(&vault, AnalyticsPeriod::Month).unwrap();
+        assert_eq!(analytics.total_notes, 2);
+
+        // Both notes are in Northern Europe; the topic note counts once
+        let northern = analytics
+            .subregions
+            .iter()
+            .find(|s| s.subregion == "Northern Europe")
+            .unwrap();
+        assert_eq!(northern.notes, 2);
+        assert!(northern.countries_covered >= 4);
+        assert_eq!(analytics.regions[0].region, "Europe");
+        assert_eq!(analytics.regions[0].notes, 2);
+
+        assert_eq!(analytics.tags[0].tag, "energy");
+        assert_eq!(analytics.tags[0].notes, 2);
+        assert_eq!(analytics.topics[0].notes, 1);
+
+        // February has no notes but is still in the series
+        let months: Vec<(&str, usize)> = analytics
+            .months
+            .iter()
+            .map(|m| (m.month.as_str(), m.notes))
+            .collect();
+        assert_eq!(months, vec![("2025-01", 1), ("2025-02", 0), ("2025-03", 1)]);
+
+        let march = analytics.activity.last().unwrap();
+        assert_eq!((march.created, march.edited), (1, 1));
+
+        let quarters = vault_analytics(&vault, AnalyticsPeriod::Quarter).unwrap();
+        assert_eq!(quarters.activity.len(), 1);
+        assert_eq!(quarters.activity[0].period, "2025-Q1");
+
+        assert_eq!(
+            analytics.coverage.countries_covered,
+            northern.countries_covered
+        );
+        assert!(analytics.coverage.share > 0.0);
+    }
+}
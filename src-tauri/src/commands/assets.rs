@@ -0,0 +1,580 @@
+use super::images::THUMBNAILS_DIR;
+use super::journal::JournalEvent;
+use super::notes::{collect_note_files, parse_note, touch_note, NoteSource};
+use mapanote_lib::AppState;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetReference {
+    pub note_id: String,
+    pub note_title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetInfo {
+    pub owner_type: String, // "country" or "topic"
+    pub owner_id: String,   // Country slug or topic ID
+    pub path: String,       // Relative path used in markdown, e.g. "assets/x.png"
+    pub size: u64,
+    pub thumbnail: Option<String>,
+    pub referenced_by: Vec<AssetReference>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GarbageReport {
+    pub orphans: Vec<AssetInfo>,
+    pub removed: usize,
+    pub bytes_freed: u64,
+}
+
+/// Folder holding the notes (and assets) of a country or topic
+pub fn owner_dir(vault_root: &Path, owner_type: &str, owner_id: &str) -> Result<PathBuf, String> {
+    let base = match owner_type {
+        "country" => "notes",
+        "topic" => "topics",
+        _ => return Err(format!("Unknown asset owner type: {}", owner_type)),
+    };
+    validate_file_name(owner_id)?;
+
+    Ok(vault_root.join(base).join(owner_id))
+}
+
+/// Reject names that would point outside the assets folder
+pub fn validate_file_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\', ':', '\0']) {
+        return Err(format!("Invalid file name: {}", name));
+    }
+    Ok(())
+}
+
+/// Characters percent-encoded when an asset name is written into a link
+const ASSET_NAME_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'\'')
+    .add(b'(')
+    .add(b')')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b']')
+    .add(b'`');
+
+/// `src`/`href` attributes in inline HTML (`<img src="assets/x.png">`)
+fn html_target_regex() -> Regex {
+    Regex::new(r#"(?i)\b(?:src|href)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap()
+}
+
+/// Places a note points at an asset: `](assets/..)`, `](<assets/..>)`,
+/// `[id]: assets/..` definitions and `src`/`href` attributes. Each
+/// alternative captures the text before the path and the path itself.
+fn asset_target_regex() -> Regex {
+    Regex::new(concat!(
+        r"(\]\(\s*<|\]:[ \t]*<)assets/([^>\n]+)",
+        r"|(\]\(\s*|\]:[ \t]*)assets/([^\s)<>]+)",
+        r#"|(\b(?i:src|href)\s*=\s*")assets/([^"]+)"#,
+        r"|(\b(?i:src|href)\s*=\s*')assets/([^']+)",
+        r"|(\b(?i:src|href)\s*=\s*)assets/([^\s>]+)",
+    ))
+    .unwrap()
+}
+
+/// Split a link target into its path and any `?query` or `#fragment`
+fn split_target(target: &str) -> (&str, &str) {
+    target.split_at(target.find(['?', '#']).unwrap_or(target.len()))
+}
+
+/// The asset file name a link target points at, if it is in `assets/`
+pub fn asset_name(target: &str) -> Option<String> {
+    let target = target.trim().trim_start_matches("./");
+    let (path, _) = split_target(target.strip_prefix("assets/")?);
+    let name = percent_decode_str(path).decode_utf8().ok()?;
+
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Write an asset file name the way it should appear in a link
+fn encode_asset_name(name: &str) -> String {
+    utf8_percent_encode(name, ASSET_NAME_ENCODE_SET).to_string()
+}
+
+/// Asset file names referenced from a note body: images and links (inline,
+/// `<..>` and reference-style), reference definitions and inline HTML
+/// `src`/`href` attributes, percent-decoded
+pub fn asset_references(content: &str) -> Vec<String> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_FOOTNOTES);
+
+    let html_targets = html_target_regex();
+    let mut parser = Parser::new_ext(content, options);
+    let mut targets = Vec::new();
+
+    for event in parser.by_ref() {
+        match event {
+            Event::Start(Tag::Image { dest_url, .. } | Tag::Link { dest_url, .. }) => {
+                targets.push(dest_url.to_string());
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                for caps in html_targets.captures_iter(&html) {
+                    if let Some(target) = caps.get(1).or(caps.get(2)).or(caps.get(3)) {
+                        targets.push(target.as_str().to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Definitions count even when no link uses them yet
+    targets.extend(
+        parser
+            .reference_definitions()
+            .iter()
+            .map(|(_, definition)| definition.dest.to_string()),
+    );
+
+    let mut names: Vec<String> = Vec::new();
+    for name in targets.iter().filter_map(|target| asset_name(target)) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Point every reference to asset `from` at `to` (percent-encoded)
+fn rewrite_asset_references(content: &str, from: &str, to: &str) -> String {
+    let encoded = encode_asset_name(to);
+
+    asset_target_regex()
+        .replace_all(content, |caps: &Captures| {
+            let (prefix, target) = (1..caps.len())
+                .step_by(2)
+                .find_map(|i| Some((caps.get(i)?.as_str(), caps.get(i + 1)?.as_str())))
+                .unwrap_or_default();
+            let (path, suffix) = split_target(target);
+
+            if asset_name(&format!("assets/{}", path)).as_deref() == Some(from) {
+                format!("{}assets/{}{}", prefix, encoded, suffix)
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .to_string()
+}
+
+/// Thumbnail belonging to an asset (thumbnails share the asset's file stem)
+fn find_thumbnail(assets_dir: &Path, file_name: &str) -> Option<PathBuf> {
+    let stem = Path::new(file_name).file_stem()?.to_str()?;
+
+    fs::read_dir(assets_dir.join(THUMBNAILS_DIR))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.file_stem().and_then(|s| s.to_str()) == Some(stem))
+}
+
+/// Delete an asset and its thumbnail, returning the bytes freed
+pub fn remove_asset(assets_dir: &Path, file_name: &str) -> Result<u64, String> {
+    validate_file_name(file_name)?;
+
+    let mut freed = 0;
+    let asset_path = assets_dir.join(file_name);
+
+    if let Some(thumbnail) = find_thumbnail(assets_dir, file_name) {
+        freed += fs::metadata(&thumbnail).map(|m| m.len()).unwrap_or(0);
+        fs::remove_file(&thumbnail).map_err(|e| format!("Failed to delete thumbnail: {}", e))?;
+    }
+
+    if asset_path.exists() {
+        freed += fs::metadata(&asset_path).map(|m| m.len()).unwrap_or(0);
+        fs::remove_file(&asset_path).map_err(|e| format!("Failed to delete asset: {}", e))?;
+    }
+
+    Ok(freed)
+}
+
+/// Every asset in the vault with the notes that reference it
+fn scan_assets(vault_root: &Path) -> Result<Vec<AssetInfo>, String> {
+    // (owner_type, owner_id, file name) -> referencing notes
+    let mut references: HashMap<(String, String, String), Vec<AssetReference>> = HashMap::new();
+
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+
+        let note = match parse_note(&content) {
+            Some(note) => note,
+            None => continue,
+        };
+
+        let (owner_type, owner_id) = match &file.source {
+            NoteSource::Country(slug) => ("country", slug.clone()),
+            NoteSource::Topic(topic_id) => ("topic", topic_id.clone()),
+        };
+
+        for name in asset_references(&note.content) {
+            let refs = references
+                .entry((owner_type.to_string(), owner_id.clone(), name))
+                .or_default();
+
+            if !refs.iter().any(|r| r.note_id == note.id) {
+                refs.push(AssetReference {
+                    note_id: note.id.clone(),
+                    note_title: note.title.clone(),
+                });
+            }
+        }
+    }
+
+    let mut assets = Vec::new();
+
+    for (owner_type, base) in [("country", "notes"), ("topic", "topics")] {
+        let base_dir = vault_root.join(base);
+        if !base_dir.exists() {
+            continue;
+        }
+
+        for owner_entry in fs::read_dir(&base_dir)
+            .map_err(|e| format!("Failed to read {} directory: {}", base, e))?
+            .flatten()
+        {
+            let assets_dir = owner_entry.path().join("assets");
+            if !assets_dir.is_dir() {
+                continue;
+            }
+
+            let owner_id = owner_entry.file_name().to_string_lossy().to_string();
+
+            for entry in fs::read_dir(&assets_dir)
+                .map_err(|e| format!("Failed to read assets directory: {}", e))?
+                .flatten()
+            {
+                let path = entry.path();
+                if !path.is_file() {
+                    continue; // Skips the thumbnails folder
+                }
+
+                let name = entry.file_name().to_string_lossy().to_string();
+                let thumbnail = find_thumbnail(&assets_dir, &name).and_then(|thumb| {
+                    thumb
+                        .file_name()
+                        .map(|n| format!("assets/{}/{}", THUMBNAILS_DIR, n.to_string_lossy()))
+                });
+
+                assets.push(AssetInfo {
+                    owner_type: owner_type.to_string(),
+                    owner_id: owner_id.clone(),
+                    path: format!("assets/{}", name),
+                    size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                    thumbnail,
+                    referenced_by: references
+                        .remove(&(owner_type.to_string(), owner_id.clone(), name))
+                        .unwrap_or_default(),
+                });
+            }
+        }
+    }
+
+    assets.sort_by(|a, b| {
+        (&a.owner_type, &a.owner_id, &a.path).cmp(&(&b.owner_type, &b.owner_id, &b.path))
+    });
+
+    Ok(assets)
+}
+
+/// List every asset in the vault with its size and referencing notes
+#[tauri::command]
+pub fn list_assets(state: State<AppState>) -> Result<Vec<AssetInfo>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    scan_assets(&PathBuf::from(vault_path))
+}
+
+/// Rename an asset and its thumbnail and rewrite the references to it in
+/// its owner's notes. Returns the number of notes updated.
+fn rename_asset_file(
+    vault_root: &Path,
+    owner_type: &str,
+    owner_id: &str,
+    from: &str,
+    to: &str,
+) -> Result<usize, String> {
+    let from = from.trim_start_matches("assets/");
+    let to = to.trim_start_matches("assets/").trim();
+    validate_file_name(from)?;
+    validate_file_name(to)?;
+
+    let notes_dir = owner_dir(vault_root, owner_type, owner_id)?;
+    let assets_dir = notes_dir.join("assets");
+
+    let from_path = assets_dir.join(from);
+    let to_path = assets_dir.join(to);
+
+    if !from_path.is_file() {
+        return Err(format!("Asset not found: {}", from));
+    }
+    if to_path.exists() {
+        return Err(format!("An asset named {} already exists", to));
+    }
+
+    fs::rename(&from_path, &to_path).map_err(|e| format!("Failed to rename asset: {}", e))?;
+
+    // Keep the thumbnail paired with the renamed asset
+    if let Some(thumbnail) = find_thumbnail(&assets_dir, from) {
+        let stem = Path::new(to).file_stem().unwrap_or_default();
+        let mut new_name = stem.to_os_string();
+        if let Some(extension) = thumbnail.extension() {
+            new_name.push(".");
+            new_name.push(extension);
+        }
+        fs::rename(&thumbnail, thumbnail.with_file_name(new_name))
+            .map_err(|e| format!("Failed to rename thumbnail: {}", e))?;
+    }
+
+    let mut notes_updated = 0;
+
+    for entry in fs::read_dir(&notes_dir)
+        .map_err(|e| format!("Failed to read notes directory: {}", e))?
+        .flatten()
+    {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            continue;
+        }
+
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
+        let updated = rewrite_asset_references(&content, from, to);
+
+        if updated != content {
+            fs::write(&path, touch_note(&updated))
+                .map_err(|e| format!("Failed to write note: {}", e))?;
+            notes_updated += 1;
+        }
+    }
+
+    JournalEvent::new("asset.renamed")
+        .entities(&[from, to])
+        .owner(owner_type, owner_id)
+        .record(vault_root);
+
+    Ok(notes_updated)
+}
+
+/// Find assets no note references, deleting them unless `dry_run` is set
+fn collect_orphans(vault_root: &Path, dry_run: bool) -> Result<GarbageReport, String> {
+    let orphans: Vec<AssetInfo> = scan_assets(vault_root)?
+        .into_iter()
+        .filter(|asset| asset.referenced_by.is_empty())
+        .collect();
+
+    let mut removed = 0;
+    let mut bytes_freed = 0;
+
+    if !dry_run {
+        for asset in &orphans {
+            let assets_dir =
+                owner_dir(vault_root, &asset.owner_type, &asset.owner_id)?.join("assets");
+            let name = asset.path.trim_start_matches("assets/");
+
+            bytes_freed += remove_asset(&assets_dir, name)?;
+            removed += 1;
+        }
+
+        if removed > 0 {
+            let paths: Vec<String> = orphans
+                .iter()
+                .map(|asset| format!("{}/{}/{}", asset.owner_type, asset.owner_id, asset.path))
+                .collect();
+            JournalEvent::new("asset.collected")
+                .entities(&paths)
+                .record(vault_root);
+        }
+    }
+
+    Ok(GarbageReport {
+        orphans,
+        removed,
+        bytes_freed,
+    })
+}
+
+/// Rename an asset and rewrite the references to it in its owner's notes.
+/// Returns the number of notes updated.
+#[tauri::command]
+pub fn rename_asset(
+    owner_type: String,
+    owner_id: String,
+    from: String,
+    to: String,
+    state: State<AppState>,
+) -> Result<usize, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    rename_asset_file(Path::new(vault_path), &owner_type, &owner_id, &from, &to)
+}
+
+/// Find assets no note references. Only reports them unless `dry_run` is
+/// explicitly `false`, in which case they are deleted.
+#[tauri::command]
+pub fn collect_garbage(
+    dry_run: Option<bool>,
+    state: State<AppState>,
+) -> Result<GarbageReport, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    collect_orphans(Path::new(vault_path), dry_run.unwrap_or(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::resolve_asset_path;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_asset_references() {
+        let content = "Map: ![Border](assets/ab12.png)\n[Report](assets/report.pdf \"PDF\")\n[Site](https://example.com)";
+
+        assert_eq!(asset_references(content), vec!["ab12.png", "report.pdf"]);
+
+        let content = "![Spaced](<assets/a b.png>) ![Encoded](assets/c%20d.png#zoom)\n\n\
+                       <img src=\"assets/e.png\" width=\"40\"> [chart][c]\n\n\
+                       [c]: assets/chart.svg\n[unused]: ./assets/old.pdf\n";
+        let mut names = asset_references(content);
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["a b.png", "c d.png", "chart.svg", "e.png", "old.pdf"]
+        );
+    }
+
+    #[test]
+    fn test_rename_to_spaced_name_survives_garbage_collection() {
+        let vault = TestVault::new();
+        vault.write("notes/fi/assets/map.png", b"png");
+        vault.write("notes/fi/assets/thumbs/map.jpg", b"jpg");
+        vault.write(
+            "notes/fi/01J.md",
+            "---\nid: 01J\ntitle: Note\ndate: 2025-10-07\ntags: []\n---\n\n\
+             ![Map](assets/map.png \"Border\") <img src='assets/map.png'>\n\n\
+             [m]: <assets/map.png>\n",
+        );
+
+        let updated = rename_asset_file(&vault, "country", "fi", "map.png", "border map.png");
+        assert_eq!(updated, Ok(1));
+        assert!(vault.join("notes/fi/assets/border map.png").exists());
+        assert!(vault.join("notes/fi/assets/thumbs/border map.jpg").exists());
+
+        let content = fs::read_to_string(vault.join("notes/fi/01J.md")).unwrap();
+        assert!(content.contains("![Map](assets/border%20map.png \"Border\")"));
+        assert!(content.contains("<img src='assets/border%20map.png'>"));
+        assert!(content.contains("[m]: <assets/border%20map.png>"));
+
+        // The renamed asset is still referenced, so nothing is an orphan
+        let report = collect_orphans(&vault, true).unwrap();
+        assert!(report.orphans.is_empty());
+
+        vault.write("notes/fi/assets/stray.png", b"x");
+        let report = collect_orphans(&vault, false).unwrap();
+        assert_eq!(report.removed, 1);
+        assert!(vault.join("notes/fi/assets/border map.png").exists());
+        assert!(!vault.join("notes/fi/assets/stray.png").exists());
+    }
+
+    #[test]
+    fn test_renamed_asset_resolves_through_protocol() {
+        let vault = TestVault::new();
+        vault.write("notes/fi/assets/map.png", b"png");
+        vault.write(
+            "notes/fi/01J.md",
+            "---\nid: 01J\ntitle: Note\ndate: 2025-10-07\ntags: []\n---\n\n![Map](assets/map.png)",
+        );
+        rename_asset_file(&vault, "country", "fi", "map.png", "border map #2.png").unwrap();
+
+        // The renderer decodes the target, then the webview encodes it once
+        let content = fs::read_to_string(vault.join("notes/fi/01J.md")).unwrap();
+        let names = asset_references(&content);
+        assert_eq!(names, vec!["border map #2.png"]);
+        let request = format!(
+            "country/fi/assets/{}",
+            utf8_percent_encode(&names[0], percent_encoding::NON_ALPHANUMERIC)
+        );
+        let resolved = resolve_asset_path(&vault, &request).unwrap();
+        assert!(resolved.ends_with("border map #2.png"));
+
+        // Encoding the note's already-encoded target again doesn't resolve
+        let target = content
+            .split("](assets/")
+            .nth(1)
+            .unwrap()
+            .trim_end_matches(')');
+        let double = format!(
+            "country/fi/assets/{}",
+            utf8_percent_encode(target, percent_encoding::NON_ALPHANUMERIC)
+        );
+        assert!(resolve_asset_path(&vault, &double).is_none());
+    }
+
+    #[test]
+    fn test_scan_assets_tracks_references() {
+        let vault = TestVault::new();
+        let country_dir = vault.join("notes").join("fi");
+        fs::create_dir_all(country_dir.join("assets").join(THUMBNAILS_DIR)).unwrap();
+
+        fs::write(country_dir.join("assets").join("used.png"), b"a").unwrap();
+        fs::write(country_dir.join("assets").join("orphan.png"), b"bb").unwrap();
+        fs::write(
+            country_dir
+                .join("assets")
+                .join(THUMBNAILS_DIR)
+                .join("orphan.jpg"),
+            b"c",
+        )
+        .unwrap();
+        fs::write(
+            country_dir.join("01J.md"),
+            "---\nid: 01J\ntitle: Note\ndate: 2025-10-07\ntags: []\n---\n\n![x](assets/used.png)",
+        )
+        .unwrap();
+
+        let assets = scan_assets(&vault).unwrap();
+        assert_eq!(assets.len(), 2);
+
+        let orphan = assets
+            .iter()
+            .find(|a| a.path == "assets/orphan.png")
+            .unwrap();
+        assert!(orphan.referenced_by.is_empty());
+        assert_eq!(
+            orphan.thumbnail.as_deref(),
+            Some("assets/thumbs/orphan.jpg")
+        );
+
+        let used = assets.iter().find(|a| a.path == "assets/used.png").unwrap();
+        assert_eq!(used.referenced_by[0].note_id, "01J");
+
+        assert_eq!(
+            remove_asset(&country_dir.join("assets"), "orphan.png").unwrap(),
+            3
+        );
+        assert!(remove_asset(&country_dir.join("assets"), "../01J.md").is_err());
+    }
+}
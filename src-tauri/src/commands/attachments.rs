@@ -0,0 +1,229 @@
+use super::assets::{owner_dir, validate_file_name};
+use super::extraction::{extract_attachment_text, remove_extracted_text};
+use super::journal::JournalEvent;
+use super::notes::{format_attachments, parse_note, set_frontmatter_field, touch_note};
+use crate::protocol::mime_type_for;
+use mapanote_lib::models::{Attachment, Note};
+use mapanote_lib::AppState;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Folder holding a note's attachments: `<owner>/attachments/<note_id>/`
+pub fn attachments_dir(owner_dir: &Path, note_id: &str) -> PathBuf {
+    owner_dir.join("attachments").join(note_id)
+}
+
+/// Remove a note's attachments folder (used when the note is deleted)
+pub fn remove_note_attachments(owner_dir: &Path, note_id: &str) -> Result<(), String> {
+    let dir = attachments_dir(owner_dir, note_id);
+
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete attachments: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Pick a file name that doesn't clash with an existing attachment
+/// ("report.pdf" -> "report (2).pdf")
+fn unique_name(dir: &Path, name: &str) -> String {
+    if !dir.join(name).exists() {
+        return name.to_string();
+    }
+
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| format!(".{}", e))
+        .unwrap_or_default();
+
+    (2..)
+        .map(|i| format!("{} ({}){}", stem, i, extension))
+        .find(|candidate| !dir.join(candidate).exists())
+        .unwrap()
+}
+
+/// Read a note file and its parsed frontmatter
+fn read_note(owner_dir: &Path, note_id: &str) -> Result<(PathBuf, String, Note), String> {
+    validate_file_name(note_id)?;
+
+    let note_path = owner_dir.join(format!("{}.md", note_id));
+    if !note_path.exists() {
+        return Err(format!("Note {} not found", note_id));
+    }
+
+    let content =
+        fs::read_to_string(&note_path).map_err(|e| format!("Failed to read note: {}", e))?;
+    let note = parse_note(&content).ok_or("Failed to parse note")?;
+
+    Ok((note_path, content, note))
+}
+
+fn write_attachments(
+    note_path: &Path,
+    content: &str,
+    attachments: &[Attachment],
+) -> Result<(), String> {
+    let updated = set_frontmatter_field(content, "attachments", &format_attachments(attachments));
+    let updated = touch_note(&updated);
+    fs::write(note_path, updated).map_err(|e| format!("Failed to write note: {}", e))
+}
+
+/// Copy a file into a note's attachments and record it in the frontmatter
+#[tauri::command]
+pub fn add_attachment(
+    owner_type: String,
+    owner_id: String,
+    note_id: String,
+    source_path: String,
+    state: State<AppState>,
+) -> Result<Attachment, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let owner_dir = owner_dir(&PathBuf::from(vault_path), &owner_type, &owner_id)?;
+    let (note_path, content, note) = read_note(&owner_dir, &note_id)?;
+
+    let source = PathBuf::from(&source_path);
+    if !source.is_file() {
+        return Err(format!("File not found: {}", source_path));
+    }
+
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or("Invalid file name")?;
+    validate_file_name(file_name)?;
+
+    let dir = attachments_dir(&owner_dir, &note_id);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
+
+    let name = unique_name(&dir, file_name);
+    let size = fs::copy(&source, dir.join(&name))
+        .map_err(|e| format!("Failed to copy attachment: {}", e))?;
+
+    let attachment = Attachment {
+        mime: mime_type_for(Path::new(&name)).to_string(),
+        name,
+        size,
+        added: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+    };
+
+    let mut attachments = note.attachments;
+    attachments.push(attachment.clone());
+    write_attachments(&note_path, &content, &attachments)?;
+
+    // Index PDF and plain-text attachments for search; a document we can't
+    // read is still attached, and is retried once the file changes
+    if let Err(e) = extract_attachment_text(&dir, &attachment) {
+        eprintln!("Skipping text extraction for {}: {}", attachment.name, e);
+    }
+
+    JournalEvent::new("attachment.added")
+        .entities(&[&note_id, &attachment.name])
+        .owner(&owner_type, &owner_id)
+        .record(Path::new(vault_path));
+
+    Ok(attachment)
+}
+
+/// List a note's attachments
+#[tauri::command]
+pub fn list_attachments(
+    owner_type: String,
+    owner_id: String,
+    note_id: String,
+    state: State<AppState>,
+) -> Result<Vec<Attachment>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    let owner_dir = owner_dir(&PathBuf::from(vault_path), &owner_type, &owner_id)?;
+    let (_, _, note) = read_note(&owner_dir, &note_id)?;
+
+    Ok(note.attachments)
+}
+
+/// Absolute path of an attachment, for opening it in the system viewer
+#[tauri::command]
+pub fn get_attachment_path(
+    owner_type: String,
+    owner_id: String,
+    note_id: String,
+    name: String,
+    state: State<AppState>,
+) -> Result<String, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    validate_file_name(&name)?;
+    validate_file_name(&note_id)?;
+
+    let owner_dir = owner_dir(&PathBuf::from(vault_path), &owner_type, &owner_id)?;
+    let path = attachments_dir(&owner_dir, &note_id).join(&name);
+
+    if !path.is_file() {
+        return Err(format!("Attachment not found: {}", name));
+    }
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Delete an attachment and drop it from the note's frontmatter
+#[tauri::command]
+pub fn remove_attachment(
+    owner_type: String,
+    owner_id: String,
+    note_id: String,
+    name: String,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    validate_file_name(&name)?;
+
+    let owner_dir = owner_dir(&PathBuf::from(vault_path), &owner_type, &owner_id)?;
+    let (note_path, content, note) = read_note(&owner_dir, &note_id)?;
+
+    let dir = attachments_dir(&owner_dir, &note_id);
+    let path = dir.join(&name);
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete attachment: {}", e))?;
+    }
+    remove_extracted_text(&dir, &name);
+
+    let attachments: Vec<Attachment> = note
+        .attachments
+        .into_iter()
+        .filter(|a| a.name != name)
+        .collect();
+    write_attachments(&note_path, &content, &attachments)?;
+
+    JournalEvent::new("attachment.removed")
+        .entities(&[&note_id, &name])
+        .owner(&owner_type, &owner_id)
+        .record(Path::new(vault_path));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_name() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("report.pdf"), b"1").unwrap();
+        fs::write(dir.join("report (2).pdf"), b"2").unwrap();
+
+        assert_eq!(unique_name(dir, "data.csv"), "data.csv");
+        assert_eq!(unique_name(dir, "report.pdf"), "report (3).pdf");
+    }
+}
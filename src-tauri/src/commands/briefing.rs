@@ -0,0 +1,1150 @@
+use super::assets::{asset_name, validate_file_name};
+use super::entities::PlaceResolver;
+use super::gis_export::{country_stats, StatsFilter};
+use super::groups::CountryGroups;
+use super::notes::{collect_note_files, parse_note, NoteSource};
+use super::templates::{
+    country_context, note_context, note_countries, topic_context, ExportTemplates,
+};
+use super::topics::load_topics_manifest;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, Rgb, RgbImage};
+use mapanote_lib::models::{CountryMetadata, Note};
+use mapanote_lib::AppState;
+use printpdf::{
+    BuiltinFont, Color, ColorBits, ColorSpace, Image, ImageFilter, ImageTransform, ImageXObject,
+    IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point, Px,
+};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+// A4, in millimetres
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+/// Space kept free at the bottom of each page for the page number
+const FOOTER: f32 = 8.0;
+const PT_TO_MM: f32 = 0.3528;
+
+const MAX_IMAGE_HEIGHT: f32 = 110.0;
+/// Images are downscaled to this many pixels on their longest side
+const MAX_IMAGE_PIXELS: u32 = 1600;
+const JPEG_QUALITY: u8 = 85;
+
+/// Column where cover fact values start
+const FACT_INDENT: f32 = 38.0;
+
+/// Helvetica advance widths (1/1000 em) for ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Characters outside Latin-1 that the WinAnsi encoding of the built-in fonts still covers
+const WINANSI_EXTRAS: &str = "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ";
+
+#[derive(Debug, Serialize)]
+pub struct BriefingReport {
+    pub path: String,
+    pub pages: usize,
+    pub notes: usize,
+    pub images: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Regular,
+    Bold,
+    Italic,
+}
+
+/// Something placed on a page. `y` is measured from the top of the page.
+enum Element {
+    Text {
+        x: f32,
+        y: f32,
+        size: f32,
+        style: Style,
+        text: String,
+    },
+    Rule {
+        y: f32,
+    },
+    Image {
+        x: f32,
+        y: f32,
+        width: f32,
+        index: usize,
+    },
+}
+
+/// Lays text out top to bottom, starting a new page when one fills up
+struct Layout {
+    pages: Vec<Vec<Element>>,
+    cursor: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            cursor: MARGIN,
+        }
+    }
+
+    /// Index of the page currently being filled
+    fn page(&self) -> usize {
+        self.pages.len() - 1
+    }
+
+    fn break_page(&mut self) {
+        self.pages.push(Vec::new());
+        self.cursor = MARGIN;
+    }
+
+    /// Start a new page unless `height` still fits on the current one
+    fn ensure(&mut self, height: f32) {
+        let current_is_empty = self.pages.last().is_none_or(|page| page.is_empty());
+        if self.cursor + height > PAGE_HEIGHT - MARGIN - FOOTER && !current_is_empty {
+            self.break_page();
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        self.cursor += height;
+    }
+
+    fn push(&mut self, element: Element) {
+        self.pages.last_mut().unwrap().push(element);
+    }
+
+    fn line(&mut self, x: f32, text: String, size: f32, style: Style) {
+        let height = line_height(size);
+        self.ensure(height);
+        let y = self.cursor + size * PT_TO_MM;
+        self.push(Element::Text {
+            x,
+            y,
+            size,
+            style,
+            text,
+        });
+        self.cursor += height;
+    }
+
+    /// Wrapped text, indented by `indent` mm
+    fn paragraph(&mut self, text: &str, size: f32, style: Style, indent: f32) {
+        for line in wrap(text, size, style, CONTENT_WIDTH - indent) {
+            self.line(MARGIN + indent, line, size, style);
+        }
+    }
+
+    /// A bold label with its value wrapped in a column to the right
+    fn fact(&mut self, label: &str, value: &str) {
+        let size = 10.0;
+        let lines = wrap(value, size, Style::Regular, CONTENT_WIDTH - FACT_INDENT);
+        self.ensure(line_height(size));
+        self.push(Element::Text {
+            x: MARGIN,
+            y: self.cursor + size * PT_TO_MM,
+            size,
+            style: Style::Bold,
+            text: label.to_string(),
+        });
+        for line in lines {
+            self.line(MARGIN + FACT_INDENT, line, size, Style::Regular);
+        }
+    }
+
+    fn rule(&mut self) {
+        self.ensure(4.0);
+        self.gap(1.5);
+        let y = self.cursor;
+        self.push(Element::Rule { y });
+        self.gap(2.5);
+    }
+
+    /// Text block of a note body; images are placed by the caller
+    fn block(&mut self, block: &Block) {
+        match block {
+            Block::Heading(text) => {
+                self.gap(1.5);
+                self.paragraph(text, 11.0, Style::Bold, 0.0);
+            }
+            Block::Paragraph(text) => {
+                self.paragraph(text, 10.0, Style::Regular, 0.0);
+                self.gap(1.5);
+            }
+            Block::Item(text) => {
+                self.paragraph(&format!("•  {}", text), 10.0, Style::Regular, 4.0);
+            }
+            Block::Image(_) => {}
+        }
+    }
+
+    fn image(&mut self, index: usize, width: f32, height: f32) {
+        self.ensure(height + 2.0);
+        let y = self.cursor + 1.0;
+        self.push(Element::Image {
+            x: MARGIN,
+            y,
+            width,
+            index,
+        });
+        self.cursor = y + height + 3.0;
+    }
+}
+
+fn line_height(size: f32) -> f32 {
+    size * PT_TO_MM * 1.4
+}
+
+/// Approximate printed width in mm (Helvetica metrics, bold slightly wider)
+fn text_width(text: &str, size: f32, style: Style) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c as u32 {
+            32..=126 => HELVETICA_WIDTHS[c as usize - 32] as u32,
+            _ => 556,
+        })
+        .sum();
+    let factor = if style == Style::Bold { 1.06 } else { 1.0 };
+    units as f32 / 1000.0 * size * PT_TO_MM * factor
+}
+
+/// Greedy word wrap; words wider than a line are split
+fn wrap(text: &str, size: f32, style: Style, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for source_line in text.lines() {
+        let mut current = String::new();
+        for word in source_line.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if text_width(&candidate, size, style) <= width {
+                current = candidate;
+                continue;
+            }
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            for c in word.chars() {
+                current.push(c);
+                if text_width(&current, size, style) > width && current.chars().count() > 1 {
+                    current.pop();
+                    lines.push(std::mem::replace(&mut current, c.to_string()));
+                }
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+    }
+
+    lines
+}
+
+/// Cut `text` so it fits in `width`, marking the cut with an ellipsis
+fn truncate(text: &str, size: f32, style: Style, width: f32) -> String {
+    if text_width(text, size, style) <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.to_string();
+    while !cut.is_empty() && text_width(&format!("{}…", cut), size, style) > width {
+        cut.pop();
+    }
+    format!("{}…", cut.trim_end())
+}
+
+/// The built-in PDF fonts only cover WinAnsi; anything else prints as `?`
+fn winansi(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if (c as u32) < 0x80
+                || (0xA0..=0xFF).contains(&(c as u32))
+                || WINANSI_EXTRAS.contains(c)
+            {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect()
+}
+
+/// Block-level pieces of a note body
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    Item(String),
+    Image(String), // Link target, e.g. `assets/x.png`
+}
+
+fn markdown_blocks(content: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut text = String::new();
+    let mut item_depth = 0usize;
+    let mut in_image = false;
+
+    let flush = |text: &mut String, blocks: &mut Vec<Block>, item: bool| {
+        let trimmed = text.trim();
+        if !trimmed.is_empty() {
+            blocks.push(if item {
+                Block::Item(trimmed.to_string())
+            } else {
+                Block::Paragraph(trimmed.to_string())
+            });
+        }
+        text.clear();
+    };
+
+    for event in Parser::new(content) {
+        match event {
+            Event::Start(Tag::Image { dest_url, .. }) => {
+                flush(&mut text, &mut blocks, item_depth > 0);
+                blocks.push(Block::Image(dest_url.to_string()));
+                in_image = true;
+            }
+            Event::End(TagEnd::Image) => in_image = false,
+            _ if in_image => {}
+            Event::Start(Tag::Item) => {
+                flush(&mut text, &mut blocks, item_depth > 0);
+                item_depth += 1;
+            }
+            Event::End(TagEnd::Item) => {
+                flush(&mut text, &mut blocks, true);
+                item_depth -= 1;
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                let heading = text.trim().to_string();
+                if !heading.is_empty() {
+                    blocks.push(Block::Heading(heading));
+                }
+                text.clear();
+            }
+            Event::End(TagEnd::Paragraph) if item_depth > 0 => text.push(' '),
+            Event::End(TagEnd::Paragraph | TagEnd::CodeBlock | TagEnd::BlockQuote) => {
+                flush(&mut text, &mut blocks, false)
+            }
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak => text.push(' '),
+            Event::HardBreak => text.push('\n'),
+            _ => {}
+        }
+    }
+    flush(&mut text, &mut blocks, item_depth > 0);
+
+    blocks
+}
+
+/// Decode an asset, flatten transparency onto white and re-encode as JPEG
+fn load_image(path: &Path) -> Option<ImageXObject> {
+    let bytes = fs::read(path).ok()?;
+    let decoded = image::load_from_memory(&bytes).ok()?;
+    let decoded = if decoded.width().max(decoded.height()) > MAX_IMAGE_PIXELS {
+        decoded.thumbnail(MAX_IMAGE_PIXELS, MAX_IMAGE_PIXELS)
+    } else {
+        decoded
+    };
+
+    let rgba = decoded.to_rgba8();
+    let rgb = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend =
+            |channel: u8| ((channel as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    });
+
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+        .encode_image(&DynamicImage::ImageRgb8(rgb))
+        .ok()?;
+
+    Some(ImageXObject {
+        width: Px(rgba.width() as usize),
+        height: Px(rgba.height() as usize),
+        color_space: ColorSpace::Rgb,
+        bits_per_component: ColorBits::Bit8,
+        interpolate: true,
+        image_data: jpeg,
+        image_filter: Some(ImageFilter::DCT),
+        smask: None,
+        clipping_bbox: None,
+    })
+}
+
+/// A note as it appears in the briefing
+struct BriefingNote {
+    note: Note,
+    owner: NoteSource,
+    owner_dir: PathBuf,
+    topic: Option<String>, // Title of the topic it belongs to
+    targets: Vec<String>,  // Target country names
+}
+
+/// Table of contents entry
+struct Entry {
+    title: String,
+    level: usize,
+    page: usize, // Page index in the content layout
+}
+
+/// Builds the content pages, remembering where sections start
+struct Content {
+    layout: Layout,
+    entries: Vec<Entry>,
+    images: Vec<Option<ImageXObject>>,
+}
+
+impl Content {
+    fn section(&mut self, title: &str) {
+        if !self.layout.pages.last().unwrap().is_empty() {
+            self.layout.break_page();
+        }
+        self.entries.push(Entry {
+            title: title.to_string(),
+            level: 0,
+            page: self.layout.page(),
+        });
+        self.layout.paragraph(title, 18.0, Style::Bold, 0.0);
+        self.layout.gap(4.0);
+    }
+
+    fn note(&mut self, briefing_note: &BriefingNote) {
+        let note = &briefing_note.note;
+
+        // Keep the heading together with the start of the body
+        self.layout.ensure(30.0);
+        self.entries.push(Entry {
+            title: note.title.clone(),
+            level: 1,
+            page: self.layout.page(),
+        });
+        self.layout.paragraph(&note.title, 13.0, Style::Bold, 0.0);
+
+        let mut meta = vec![note.date.clone()];
+        if let Some(topic) = &briefing_note.topic {
+            meta.push(format!("Topic: {}", topic));
+        }
+        if !briefing_note.targets.is_empty() {
+            meta.push(format!("Countries: {}", briefing_note.targets.join(", ")));
+        }
+        self.layout
+            .paragraph(&meta.join("  ·  "), 9.0, Style::Italic, 0.0);
+        if !note.tags.is_empty() {
+            let tags: Vec<String> = note.tags.iter().map(|t| format!("#{}", t)).collect();
+            self.layout
+                .paragraph(&tags.join("  "), 9.0, Style::Italic, 0.0);
+        }
+        self.layout.gap(2.0);
+
+        for block in markdown_blocks(&note.content) {
+            match block {
+                Block::Image(target) => self.image(&briefing_note.owner_dir, &target),
+                block => self.layout.block(&block),
+            }
+        }
+
+        self.layout.rule();
+    }
+
+    /// Embed an image from the note's `assets/` folder; other links are skipped
+    fn image(&mut self, owner_dir: &Path, target: &str) {
+        let name = match asset_name(target) {
+            Some(name) if validate_file_name(&name).is_ok() => name,
+            _ => return,
+        };
+        let image = match load_image(&owner_dir.join("assets").join(&name)) {
+            Some(image) => image,
+            None => {
+                eprintln!("Skipping image {} in briefing: not a readable image", name);
+                return;
+            }
+        };
+
+        let aspect = image.height.0 as f32 / image.width.0 as f32;
+        let mut width = CONTENT_WIDTH;
+        if width * aspect > MAX_IMAGE_HEIGHT {
+            width = MAX_IMAGE_HEIGHT / aspect;
+        }
+
+        self.images.push(Some(image));
+        self.layout
+            .image(self.images.len() - 1, width, width * aspect);
+    }
+}
+
+fn country_facts(layout: &mut Layout, country: &CountryMetadata) {
+    let mut codes = vec![country.iso2.clone(), country.iso3.clone()];
+    if !country.iso_numeric.is_empty() {
+        codes.push(country.iso_numeric.clone());
+    }
+    layout.fact("ISO 3166", &codes.join(" / "));
+
+    let region = match country.subregion.as_str() {
+        "" => country.region.clone(),
+        subregion => format!("{} — {}", country.region, subregion),
+    };
+    layout.fact("Region", &region);
+
+    if let Some(capital) = &country.capital {
+        layout.fact("Capital", capital);
+    }
+
+    let known_as: Vec<&str> = country
+        .aliases
+        .iter()
+        .map(String::as_str)
+        .filter(|alias| *alias != country.iso2 && *alias != country.iso3)
+        .collect();
+    if !known_as.is_empty() {
+        layout.fact("Also known as", &known_as.join(", "));
+    }
+
+    if !country.currencies.is_empty() {
+        let currencies: Vec<String> = country
+            .currencies
+            .iter()
+            .map(|c| format!("{} ({})", c.name, c.code))
+            .collect();
+        layout.fact("Currencies", &currencies.join(", "));
+    }
+    if !country.languages.is_empty() {
+        let languages: Vec<&str> = country.languages.iter().map(|l| l.name.as_str()).collect();
+        layout.fact("Languages", &languages.join(", "));
+    }
+    if !country.demonyms.is_empty() {
+        layout.fact("Demonyms", &country.demonyms.join(", "));
+    }
+    if let Some(centroid) = &country.centroid {
+        layout.fact(
+            "Centroid",
+            &format!("{:.2}, {:.2}", centroid.lat, centroid.lon),
+        );
+    }
+}
+
+fn cover(title: &str, subtitle: &str, note_count: usize) -> Layout {
+    let mut layout = Layout::new();
+    layout.gap(40.0);
+    layout.paragraph(title, 28.0, Style::Bold, 0.0);
+    layout.paragraph(subtitle, 14.0, Style::Regular, 0.0);
+    layout.gap(2.0);
+    layout.paragraph(
+        &format!(
+            "Generated {}  ·  {} note{}",
+            chrono::Local::now().format("%Y-%m-%d"),
+            note_count,
+            if note_count == 1 { "" } else { "s" }
+        ),
+        10.0,
+        Style::Italic,
+        0.0,
+    );
+    layout.rule();
+    layout.gap(4.0);
+    layout
+}
+
+/// Newest first, like the note lists in the app
+fn sort_notes(notes: &mut [BriefingNote]) {
+    notes.sort_by(|a, b| {
+        b.note
+            .date
+            .cmp(&a.note.date)
+            .then_with(|| b.note.id.cmp(&a.note.id))
+    });
+}
+
+/// Every note in the vault with the folder it lives in
+fn vault_notes(vault_root: &Path) -> Result<Vec<(NoteSource, Note, PathBuf)>, String> {
+    let mut notes = Vec::new();
+
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+        if let Some(note) = parse_note(&content) {
+            let owner_dir = file.path.parent().unwrap_or(vault_root).to_path_buf();
+            notes.push((file.source, note, owner_dir));
+        }
+    }
+
+    Ok(notes)
+}
+
+/// Render a country, or a topic with its target countries, into a PDF at
+/// `destination`. A Markdown export `template` replaces note bodies and the
+/// cover summary.
+pub fn render_briefing(
+    vault_root: &Path,
+    owner_type: &str,
+    owner_id: &str,
+    destination: &Path,
+    template: Option<&str>,
+) -> Result<BriefingReport, String> {
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let resolver = PlaceResolver::load(&vault_path)?;
+    let groups = CountryGroups::load(Some(&vault_path));
+    let manifest = load_topics_manifest(&vault_path)?;
+    let templates = ExportTemplates::load_selected(vault_root, template, &["md"])?;
+    let find_topic = |topic_id: &str| manifest.topics.iter().find(|t| t.id == topic_id);
+    let topic_title = |topic_id: &str| {
+        find_topic(topic_id)
+            .map(|t| t.title.clone())
+            .unwrap_or_else(|| topic_id.to_string())
+    };
+    let apply_note_template = |notes: &mut [BriefingNote]| -> Result<(), String> {
+        let templates = match &templates {
+            Some(templates) => templates,
+            None => return Ok(()),
+        };
+        for briefing_note in notes {
+            let topic = match &briefing_note.owner {
+                NoteSource::Topic(topic_id) => find_topic(topic_id),
+                NoteSource::Country(_) => None,
+            };
+            let countries = note_countries(
+                &briefing_note.owner,
+                &briefing_note.note,
+                &groups,
+                &resolver,
+            );
+            let context =
+                note_context(&briefing_note.note, &briefing_note.owner, topic, &countries);
+            if let Some(body) = templates.render("note", &context)? {
+                briefing_note.note.content = body;
+            }
+        }
+        Ok(())
+    };
+
+    let mut content = Content {
+        layout: Layout::new(),
+        entries: Vec::new(),
+        images: Vec::new(),
+    };
+
+    let (title, mut cover, note_count) = match owner_type {
+        "country" => {
+            let country = resolver
+                .resolve(owner_id)
+                .ok_or(format!("Unknown country: {}", owner_id))?;
+
+            let mut country_notes = Vec::new();
+            let mut topic_notes = Vec::new();
+            for (source, note, owner_dir) in vault_notes(vault_root)? {
+                match source {
+                    NoteSource::Country(ref slug) if slug == owner_id => {
+                        country_notes.push(BriefingNote {
+                            note,
+                            owner: source,
+                            owner_dir,
+                            topic: None,
+                            targets: Vec::new(),
+                        })
+                    }
+                    NoteSource::Topic(ref topic_id)
+                        if groups
+                            .expand_targets(&note.country_targets)
+                            .iter()
+                            .any(|slug| slug == owner_id) =>
+                    {
+                        topic_notes.push(BriefingNote {
+                            note,
+                            topic: Some(topic_title(topic_id)),
+                            owner: source,
+                            owner_dir,
+                            targets: Vec::new(),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+            sort_notes(&mut country_notes);
+            sort_notes(&mut topic_notes);
+
+            let summary = match &templates {
+                Some(templates) => {
+                    let stats = country_stats(vault_root, &StatsFilter::default())?;
+                    let notes: Vec<&Note> = country_notes.iter().map(|n| &n.note).collect();
+                    let targeting: Vec<&Note> = topic_notes.iter().map(|n| &n.note).collect();
+                    templates.render(
+                        "country",
+                        &country_context(
+                            &country,
+                            &notes,
+                            &targeting,
+                            stats.iter().find(|row| row.slug == owner_id),
+                        ),
+                    )?
+                }
+                None => None,
+            };
+            apply_note_template(&mut country_notes)?;
+            apply_note_template(&mut topic_notes)?;
+
+            let note_count = country_notes.len() + topic_notes.len();
+            let mut cover = cover(&country.name, "Country briefing", note_count);
+            country_facts(&mut cover, &country);
+            match summary {
+                Some(summary) => {
+                    cover.gap(4.0);
+                    for block in markdown_blocks(&summary) {
+                        cover.block(&block);
+                    }
+                }
+                None if !country.summary.is_empty() => {
+                    cover.gap(4.0);
+                    cover.paragraph(&country.summary, 10.0, Style::Regular, 0.0);
+                }
+                None => {}
+            }
+
+            for (heading, notes) in [
+                ("Country notes", &country_notes),
+                ("Topic notes", &topic_notes),
+            ] {
+                if notes.is_empty() {
+                    continue;
+                }
+                content.section(heading);
+                for note in notes {
+                    content.note(note);
+                }
+            }
+
+            (country.name, cover, note_count)
+        }
+        "topic" => {
+            let topic = manifest
+                .topics
+                .iter()
+                .find(|t| t.id == owner_id)
+                .ok_or(format!("Topic not found: {}", owner_id))?;
+
+            let mut slugs: BTreeSet<String> = manifest
+                .relations
+                .iter()
+                .filter(|r| r.topic_id == owner_id)
+                .map(|r| r.country_slug.clone())
+                .collect();
+
+            let mut notes = Vec::new();
+            for (source, note, owner_dir) in vault_notes(vault_root)? {
+                if !matches!(&source, NoteSource::Topic(topic_id) if topic_id == owner_id) {
+                    continue;
+                }
+                let expanded = groups.expand_targets(&note.country_targets);
+                slugs.extend(expanded.iter().cloned());
+                notes.push(BriefingNote {
+                    targets: expanded.iter().map(|slug| resolver.name(slug)).collect(),
+                    note,
+                    owner: source,
+                    owner_dir,
+                    topic: None,
+                });
+            }
+            sort_notes(&mut notes);
+
+            let mut countries: Vec<CountryMetadata> = slugs
+                .iter()
+                .filter_map(|slug| resolver.resolve(slug))
+                .collect();
+            countries.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let summary = match &templates {
+                Some(templates) => {
+                    let topic_notes: Vec<&Note> = notes.iter().map(|n| &n.note).collect();
+                    templates.render("topic", &topic_context(topic, &countries, &topic_notes))?
+                }
+                None => None,
+            };
+            apply_note_template(&mut notes)?;
+
+            let mut cover = cover(&topic.title, "Topic briefing", notes.len());
+            match summary {
+                Some(summary) => {
+                    for block in markdown_blocks(&summary) {
+                        cover.block(&block);
+                    }
+                    cover.gap(4.0);
+                }
+                None => {
+                    if let Some(summary) = topic.summary.as_deref().filter(|s| !s.is_empty()) {
+                        cover.paragraph(summary, 11.0, Style::Regular, 0.0);
+                        cover.gap(4.0);
+                    }
+                }
+            }
+
+            let names: Vec<&str> = countries.iter().map(|c| c.name.as_str()).collect();
+            if !names.is_empty() {
+                cover.fact("Countries", &names.join(", "));
+            }
+
+            if !countries.is_empty() {
+                content.section("Target countries");
+                for country in &countries {
+                    content.layout.ensure(40.0);
+                    content.entries.push(Entry {
+                        title: country.name.clone(),
+                        level: 1,
+                        page: content.layout.page(),
+                    });
+                    content
+                        .layout
+                        .paragraph(&country.name, 13.0, Style::Bold, 0.0);
+                    country_facts(&mut content.layout, country);
+                    content.layout.rule();
+                }
+            }
+            if !notes.is_empty() {
+                content.section("Notes");
+                for note in &notes {
+                    content.note(note);
+                }
+            }
+
+            let note_count = notes.len();
+            (topic.title.clone(), cover, note_count)
+        }
+        other => return Err(format!("Invalid owner type: {}", other)),
+    };
+
+    if content.entries.is_empty() {
+        cover.gap(4.0);
+        cover.paragraph("No notes yet.", 10.0, Style::Italic, 0.0);
+    }
+
+    // The contents pages come after the cover and before the notes, so lay
+    // them out once to know how many there are; entries are one line each,
+    // so the page numbers filled in afterwards don't change that count
+    let toc = |offset: usize| {
+        let mut layout = Layout::new();
+        layout.paragraph("Contents", 18.0, Style::Bold, 0.0);
+        layout.gap(4.0);
+        for entry in &content.entries {
+            let (size, style, indent) = match entry.level {
+                0 => (11.0, Style::Bold, 0.0),
+                _ => (10.0, Style::Regular, 6.0),
+            };
+            let number = (entry.page + offset + 1).to_string();
+            let number_width = text_width(&number, size, style);
+            let text = truncate(
+                &winansi(&entry.title),
+                size,
+                style,
+                CONTENT_WIDTH - indent - number_width - 6.0,
+            );
+            if entry.level == 0 {
+                layout.gap(1.5);
+            }
+            layout.line(MARGIN + indent, text, size, style);
+            let y = layout.cursor - line_height(size) + size * PT_TO_MM;
+            layout.push(Element::Text {
+                x: PAGE_WIDTH - MARGIN - number_width,
+                y,
+                size,
+                style,
+                text: number,
+            });
+        }
+        layout
+    };
+    let cover_pages = cover.pages.len();
+    let toc_pages = toc(0).pages.len();
+    let content_offset = cover_pages + toc_pages;
+    let toc = toc(content_offset);
+
+    let mut bookmarks: Vec<(usize, String)> = vec![(cover_pages, "Contents".to_string())];
+    bookmarks.extend(
+        content
+            .entries
+            .iter()
+            .map(|entry| (content_offset + entry.page, entry.title.clone())),
+    );
+
+    let pages: Vec<Vec<Element>> = cover
+        .pages
+        .into_iter()
+        .chain(toc.pages)
+        .chain(content.layout.pages)
+        .collect();
+    let page_count = pages.len();
+
+    let (doc, first_page, first_layer) =
+        PdfDocument::new(winansi(&title), Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Briefing");
+    let font = |font: BuiltinFont| {
+        doc.add_builtin_font(font)
+            .map_err(|e| format!("Failed to load font: {}", e))
+    };
+    let regular = font(BuiltinFont::Helvetica)?;
+    let bold = font(BuiltinFont::HelveticaBold)?;
+    let italic = font(BuiltinFont::HelveticaOblique)?;
+    let font_for = |style: Style| -> &IndirectFontRef {
+        match style {
+            Style::Regular => &regular,
+            Style::Bold => &bold,
+            Style::Italic => &italic,
+        }
+    };
+
+    let mut page_indices = Vec::with_capacity(page_count);
+    let mut images = content.images;
+    let mut image_count = 0;
+
+    for (number, elements) in pages.into_iter().enumerate() {
+        let (page, layer) = if number == 0 {
+            (first_page, first_layer)
+        } else {
+            doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Briefing")
+        };
+        page_indices.push(page);
+        let layer: PdfLayerReference = doc.get_page(page).get_layer(layer);
+
+        for element in elements {
+            match element {
+                Element::Text {
+                    x,
+                    y,
+                    size,
+                    style,
+                    text,
+                } => layer.use_text(
+                    winansi(&text),
+                    size,
+                    Mm(x),
+                    Mm(PAGE_HEIGHT - y),
+                    font_for(style),
+                ),
+                Element::Rule { y } => {
+                    layer.set_outline_color(Color::Greyscale(printpdf::Greyscale::new(0.8, None)));
+                    layer.set_outline_thickness(0.5);
+                    layer.add_line(Line {
+                        points: vec![
+                            (Point::new(Mm(MARGIN), Mm(PAGE_HEIGHT - y)), false),
+                            (
+                                Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(PAGE_HEIGHT - y)),
+                                false,
+                            ),
+                        ],
+                        is_closed: false,
+                    });
+                }
+                Element::Image { x, y, width, index } => {
+                    if let Some(image) = images.get_mut(index).and_then(Option::take) {
+                        let pixels = image.width.0 as f32;
+                        let height = width * image.height.0 as f32 / pixels;
+                        Image::from(image).add_to_layer(
+                            layer.clone(),
+                            ImageTransform {
+                                translate_x: Some(Mm(x)),
+                                translate_y: Some(Mm(PAGE_HEIGHT - y - height)),
+                                dpi: Some(pixels * 25.4 / width),
+                                ..Default::default()
+                            },
+                        );
+                        image_count += 1;
+                    }
+                }
+            }
+        }
+
+        if number > 0 {
+            let footer = format!("{} / {}", number + 1, page_count);
+            let width = text_width(&footer, 8.0, Style::Regular);
+            layer.use_text(
+                footer,
+                8.0,
+                Mm((PAGE_WIDTH - width) / 2.0),
+                Mm(MARGIN / 2.0),
+                &regular,
+            );
+        }
+    }
+
+    // One outline entry per page; the first section starting on a page wins
+    let mut bookmarked = HashSet::new();
+    for (page, title) in bookmarks {
+        if page < page_indices.len() && bookmarked.insert(page) {
+            doc.add_bookmark(winansi(&title), page_indices[page]);
+        }
+    }
+
+    let bytes = doc
+        .save_to_bytes()
+        .map_err(|e| format!("Failed to render PDF: {}", e))?;
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    fs::write(destination, bytes).map_err(|e| format!("Failed to write PDF: {}", e))?;
+
+    Ok(BriefingReport {
+        path: destination.to_string_lossy().to_string(),
+        pages: page_count,
+        notes: note_count,
+        images: image_count,
+    })
+}
+
+/// Render a country or topic briefing as a PDF, optionally through an
+/// export template
+#[tauri::command]
+pub fn generate_briefing(
+    owner_type: String,
+    owner_id: String,
+    destination: String,
+    template: Option<String>,
+    state: State<AppState>,
+) -> Result<BriefingReport, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    render_briefing(
+        &PathBuf::from(vault_path),
+        &owner_type,
+        &owner_id,
+        &PathBuf::from(destination),
+        template.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_wrap_fits_width() {
+        let text = "The quick brown fox jumps over the lazy dog ".repeat(10);
+        let lines = wrap(&text, 10.0, Style::Regular, 60.0);
+        assert!(lines.len() > 5);
+        assert!(lines
+            .iter()
+            .all(|line| text_width(line, 10.0, Style::Regular) <= 60.0));
+    }
+
+    #[test]
+    fn test_generate_country_briefing() {
+        let vault = TestVault::new();
+        let finland = vault.join("notes").join("fi");
+        let topic = vault.join("topics").join("T1");
+        fs::create_dir_all(finland.join("assets")).unwrap();
+        fs::create_dir_all(&topic).unwrap();
+
+        RgbImage::from_pixel(40, 20, Rgb([200, 30, 30]))
+            .save(finland.join("assets").join("flag.png"))
+            .unwrap();
+        fs::write(
+            finland.join("01A.md"),
+            "---\nid: 01A\ntitle: Elections\ndate: 2025-01-10\ntags: [politics]\n---\n\n# Result\n\nTurnout was high.\n\n- one\n- two\n\n![flag](assets/flag.png)",
+        )
+        .unwrap();
+        fs::write(
+            topic.join("01B.md"),
+            "---\nid: 01B\ntitle: Grid\ndate: 2025-02-01\ntags: [energy]\ntopic_id: T1\ncountry_targets: [fi, se]\n---\n\nShared grid.",
+        )
+        .unwrap();
+
+        let destination = vault.join("out").join("fi.pdf");
+        let report = render_briefing(&vault, "country", "fi", &destination, None).unwrap();
+        assert_eq!(report.notes, 2);
+        assert_eq!(report.images, 1);
+        assert!(report.pages >= 3); // Cover, contents, notes
+
+        let bytes = fs::read(&destination).unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+        let text = pdf_extract::extract_text_from_mem(&bytes).unwrap();
+        assert!(text.contains("Contents"));
+        assert!(text.contains("Topic: T1"));
+        assert!(text.contains("Turnout was high."));
+
+        assert!(render_briefing(&vault, "country", "zz", &destination, None).is_err());
+
+        let template = vault.join(".mapanote/templates/export/client");
+        fs::create_dir_all(&template).unwrap();
+        fs::write(
+            template.join("note.md"),
+            "Filed under {{ countries | map(attribute='iso3') | join(', ') }}",
+        )
+        .unwrap();
+        render_briefing(&vault, "country", "fi", &destination, Some("client")).unwrap();
+        let text = pdf_extract::extract_text_from_mem(&fs::read(&destination).unwrap()).unwrap();
+        assert!(text.contains("Filed under FIN, SWE"));
+        assert!(!text.contains("Turnout was high."));
+    }
+
+    #[test]
+    fn test_briefing_embeds_encoded_asset_names() {
+        let vault = TestVault::new();
+        let assets = vault.join("notes/fi/assets");
+        fs::create_dir_all(&assets).unwrap();
+        RgbImage::from_pixel(40, 20, Rgb([30, 30, 200]))
+            .save(assets.join("border map.png"))
+            .unwrap();
+        vault.write(
+            "notes/fi/01A.md",
+            "---\nid: 01A\ntitle: Border\ndate: 2025-01-10\ntags: []\n---\n\n\
+             ![one](assets/border%20map.png)\n\n![two](./assets/border%20map.png#detail)\n\n\
+             ![three](<assets/border map.png> \"Title\")",
+        );
+
+        let destination = vault.join("fi.pdf");
+        let report = render_briefing(&vault, "country", "fi", &destination, None).unwrap();
+        assert_eq!(report.images, 3);
+    }
+
+    #[test]
+    fn test_contents_numbers_follow_a_long_cover() {
+        let vault = TestVault::new();
+        vault.write(
+            "topics.json",
+            serde_json::json!({
+                "version": "1.0",
+                "topics": [{
+                    "id": "T1", "title": "Energy", "pinned": false,
+                    "summary": "Long summary text. ".repeat(400),
+                    "created_at": "", "updated_at": ""
+                }],
+                "relations": []
+            })
+            .to_string(),
+        );
+        vault.write(
+            "topics/T1/01B.md",
+            "---\nid: 01B\ntitle: Grid\ndate: 2025-02-01\ntags: []\ntopic_id: T1\ncountry_targets: [fi]\n---\n\nShared grid.",
+        );
+
+        let destination = vault.join("energy.pdf");
+        render_briefing(&vault, "topic", "T1", &destination, None).unwrap();
+        let pages =
+            pdf_extract::extract_text_from_mem_by_pages(&fs::read(&destination).unwrap()).unwrap();
+
+        let contents = pages.iter().position(|p| p.contains("Contents")).unwrap();
+        assert!(contents >= 2, "the cover should span several pages");
+        let note_page = pages
+            .iter()
+            .position(|p| p.contains("Shared grid."))
+            .unwrap();
+        let listed: String = pages[contents]
+            .lines()
+            .find(|line| line.contains("Grid"))
+            .unwrap()
+            .chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect();
+        assert_eq!(listed, (note_page + 1).to_string());
+    }
+}
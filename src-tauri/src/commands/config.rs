@@ -0,0 +1,66 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Vault settings, one top-level key per feature
+pub const CONFIG_FILE: &str = ".mapanote/config.json";
+
+fn config_path(vault_root: &Path) -> PathBuf {
+    vault_root.join(CONFIG_FILE)
+}
+
+/// The whole config; an absent file is an empty one
+pub fn load_config(vault_root: &Path) -> Result<serde_json::Value, String> {
+    match fs::read_to_string(config_path(vault_root)) {
+        Ok(content) => {
+            serde_json::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))
+        }
+        Err(_) => Ok(serde_json::json!({})),
+    }
+}
+
+/// Replace the whole config
+pub fn write_config(vault_root: &Path, config: &serde_json::Value) -> Result<(), String> {
+    let path = config_path(vault_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+
+    fs::write(
+        &path,
+        serde_json::to_string_pretty(config)
+            .map_err(|e| format!("Failed to serialize config: {}", e))?,
+    )
+    .map_err(|e| format!("Failed to write config: {}", e))
+}
+
+/// The settings under `key`, or their defaults when the config, the key or
+/// its value can't be read, so a broken config never blocks the app
+pub fn config_section<T: DeserializeOwned + Default>(vault_root: &Path, key: &str) -> T {
+    load_config(vault_root)
+        .ok()
+        .and_then(|config| config.get(key).cloned())
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Store `value` under `key`, keeping the other settings
+pub fn save_config_section<T: Serialize>(
+    vault_root: &Path,
+    key: &str,
+    value: &T,
+) -> Result<(), String> {
+    let mut config = load_config(vault_root)?;
+    let value =
+        serde_json::to_value(value).map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    match config.as_object_mut() {
+        Some(object) => {
+            object.insert(key.to_string(), value);
+        }
+        None => return Err("Config is not a JSON object".to_string()),
+    }
+
+    write_config(vault_root, &config)
+}
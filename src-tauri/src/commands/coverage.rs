@@ -0,0 +1,365 @@
+use super::config::config_section;
+use super::entities::PlaceResolver;
+use super::groups::CountryGroups;
+use super::metadata::get_countries_metadata;
+use super::note_index::load_note_index;
+use super::topics::load_topics_manifest;
+use chrono::NaiveDate;
+use mapanote_lib::models::VaultManifest;
+use mapanote_lib::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Staleness settings are kept under this key of `.mapanote/config.json`
+const CONFIG_KEY: &str = "staleness";
+
+const DEFAULT_THRESHOLD_DAYS: u32 = 90;
+
+/// How closely a country or topic is watched: high priority goes stale in
+/// half the threshold, low priority in twice the threshold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchPriority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl WatchPriority {
+    fn weight(self) -> f64 {
+        match self {
+            WatchPriority::High => 0.5,
+            WatchPriority::Normal => 1.0,
+            WatchPriority::Low => 2.0,
+        }
+    }
+}
+
+/// e.g. `"staleness": {"threshold_days": 60, "region_weights": {"Europe": 0.5},
+/// "watch": {"ua": "high", "T01...": "low"}}`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StalenessSettings {
+    pub threshold_days: u32,
+    pub region_weights: HashMap<String, f64>, // Multiplies the threshold per region
+    pub watch: HashMap<String, WatchPriority>, // Country slug or topic ID
+}
+
+impl Default for StalenessSettings {
+    fn default() -> Self {
+        StalenessSettings {
+            threshold_days: DEFAULT_THRESHOLD_DAYS,
+            region_weights: HashMap::new(),
+            watch: HashMap::new(),
+        }
+    }
+}
+
+impl StalenessSettings {
+    /// Read from `.mapanote/config.json` (defaults if absent)
+    pub fn load(vault_root: &Path) -> Self {
+        config_section(vault_root, CONFIG_KEY)
+    }
+
+    /// Days without a note before `key` counts as stale, and its priority
+    fn threshold(&self, key: &str, region: Option<&str>) -> (u32, WatchPriority) {
+        let priority = self.watch.get(key).copied().unwrap_or_default();
+        let region_weight = region
+            .and_then(|r| self.region_weights.get(r))
+            .copied()
+            .unwrap_or(1.0);
+
+        let days = (self.threshold_days as f64 * region_weight * priority.weight()).round();
+        (days.max(1.0) as u32, priority)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaleCountry {
+    pub slug: String,
+    pub name: String,
+    pub region: String,
+    pub subregion: String,
+    pub last_note: String,
+    pub days_since: i64,
+    pub threshold_days: u32,
+    pub priority: WatchPriority,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaleTopic {
+    pub topic_id: String,
+    pub title: String,
+    pub last_note: Option<String>, // None when the topic has no notes
+    pub days_since: Option<i64>,
+    pub threshold_days: u32,
+    pub priority: WatchPriority,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UncoveredCountry {
+    pub slug: String,
+    pub name: String,
+    pub region: String,
+    pub subregion: String,
+}
+
+/// Most overdue first
+#[derive(Debug, Serialize)]
+pub struct StaleCoverageReport {
+    pub threshold_days: u32,
+    pub countries: Vec<StaleCountry>,
+    pub topics: Vec<StaleTopic>,
+    pub uncovered: Vec<UncoveredCountry>, // Embedded countries without any note
+}
+
+/// Date part of a `YYYY-MM-DD` or RFC3339 value
+fn parse_day(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Keep the later of two dates
+fn bump(latest: &mut HashMap<String, String>, key: &str, date: &str) {
+    if parse_day(date).is_none() {
+        return;
+    }
+
+    let current = latest.entry(key.to_string()).or_default();
+    if current.as_str() < date {
+        *current = date.to_string();
+    }
+}
+
+/// Countries and topics whose latest note is older than their threshold,
+/// and countries nobody has written about, optionally within one region
+pub fn stale_coverage(
+    vault_root: &Path,
+    settings: &StalenessSettings,
+    today: NaiveDate,
+    region: Option<&str>,
+) -> Result<StaleCoverageReport, String> {
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let resolver = PlaceResolver::load(&vault_path)?;
+    let groups = CountryGroups::load(Some(&vault_path));
+    let topics_manifest = load_topics_manifest(&vault_path)?;
+    let manifest: VaultManifest = fs::read_to_string(vault_root.join("vault.json"))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_else(VaultManifest::new);
+
+    // Latest note date per country (its own notes, from the manifest, and
+    // topic notes targeting it) and per topic
+    let mut country_latest: HashMap<String, String> = HashMap::new();
+    let mut topic_latest: HashMap<String, String> = HashMap::new();
+
+    for (slug, stats) in &manifest.countries {
+        if stats.note_count > 0 {
+            if let Some(date) = &stats.last_updated {
+                bump(&mut country_latest, slug, date);
+            }
+        }
+    }
+
+    for entry in load_note_index(vault_root)? {
+        if entry.source_type != "topic" {
+            continue;
+        }
+
+        bump(&mut topic_latest, &entry.owner_id, &entry.date);
+        for slug in groups.expand_targets(&entry.country_targets) {
+            bump(&mut country_latest, &slug, &entry.date);
+        }
+    }
+
+    let in_region = |r: &str| region.is_none_or(|wanted| wanted.eq_ignore_ascii_case(r));
+
+    let mut countries = Vec::new();
+    for (slug, last_note) in &country_latest {
+        let Some(place) = resolver.resolve(slug) else {
+            continue;
+        };
+        if !in_region(&place.region) {
+            continue;
+        }
+
+        let days_since = parse_day(last_note)
+            .map(|date| (today - date).num_days())
+            .unwrap_or_default();
+        let (threshold_days, priority) = settings.threshold(slug, Some(&place.region));
+
+        if days_since > threshold_days as i64 {
+            countries.push(StaleCountry {
+                slug: slug.clone(),
+                name: place.name,
+                region: place.region,
+                subregion: place.subregion,
+                last_note: last_note.clone(),
+                days_since,
+                threshold_days,
+                priority,
+            });
+        }
+    }
+    countries.sort_by(|a, b| {
+        let overdue = |c: &StaleCountry| c.days_since as f64 / c.threshold_days as f64;
+        overdue(b)
+            .total_cmp(&overdue(a))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    // Topics have no region of their own, so a region filter leaves them out
+    let mut topics = Vec::new();
+    if region.is_none() {
+        for topic in &topics_manifest.topics {
+            let last_note = topic_latest.get(&topic.id).cloned();
+            let days_since = last_note
+                .as_deref()
+                .and_then(parse_day)
+                .map(|date| (today - date).num_days());
+            let (threshold_days, priority) = settings.threshold(&topic.id, None);
+
+            if days_since.is_none_or(|days| days > threshold_days as i64) {
+                topics.push(StaleTopic {
+                    topic_id: topic.id.clone(),
+                    title: topic.title.clone(),
+                    last_note,
+                    days_since,
+                    threshold_days,
+                    priority,
+                });
+            }
+        }
+    }
+    topics.sort_by(|a, b| {
+        let overdue = |t: &StaleTopic| {
+            t.days_since
+                .map_or(f64::INFINITY, |days| days as f64 / t.threshold_days as f64)
+        };
+        overdue(b)
+            .total_cmp(&overdue(a))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+
+    let covered: HashSet<&String> = country_latest.keys().collect();
+    let mut uncovered: Vec<UncoveredCountry> = get_countries_metadata()
+        .iter()
+        .filter(|c| !covered.contains(&c.slug) && in_region(&c.region))
+        .map(|c| UncoveredCountry {
+            slug: c.slug.clone(),
+            name: c.name.clone(),
+            region: c.region.clone(),
+            subregion: c.subregion.clone(),
+        })
+        .collect();
+    uncovered.sort_by(|a, b| {
+        (&a.region, &a.subregion, &a.name).cmp(&(&b.region, &b.subregion, &b.name))
+    });
+
+    Ok(StaleCoverageReport {
+        threshold_days: settings.threshold_days,
+        countries,
+        topics,
+        uncovered,
+    })
+}
+
+/// Slugs of countries whose coverage is stale today, for the map
+pub fn stale_country_slugs(vault_root: &Path) -> HashSet<String> {
+    let settings = StalenessSettings::load(vault_root);
+    let today = chrono::Utc::now().date_naive();
+
+    stale_coverage(vault_root, &settings, today, None)
+        .map(|report| report.countries.into_iter().map(|c| c.slug).collect())
+        .unwrap_or_default()
+}
+
+/// Countries and topics gone quiet for longer than the staleness threshold
+/// (`threshold_days` overrides the configured one), plus countries with no
+/// notes at all; `region` narrows the country lists to one region
+#[tauri::command]
+pub fn get_stale_coverage(
+    threshold_days: Option<u32>,
+    region: Option<String>,
+    state: State<AppState>,
+) -> Result<StaleCoverageReport, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    let vault_root = PathBuf::from(vault_path);
+    let mut settings = StalenessSettings::load(&vault_root);
+    if let Some(days) = threshold_days {
+        settings.threshold_days = days;
+    }
+
+    stale_coverage(
+        &vault_root,
+        &settings,
+        chrono::Utc::now().date_naive(),
+        region.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+    use mapanote_lib::models::CountryStats;
+
+    #[test]
+    fn test_flags_stale_countries_and_topics() {
+        let vault = TestVault::new();
+
+        let mut manifest = VaultManifest::new();
+        manifest.countries.insert(
+            "fi".to_string(),
+            CountryStats {
+                note_count: 2,
+                last_updated: Some("2025-01-01".to_string()),
+                tags: Vec::new(),
+            },
+        );
+        vault.write_manifest(&manifest);
+        vault.write(
+            "topics.json",
+            r#"{"version": "1.0", "topics": [
+                {"id": "T1", "title": "Energy", "pinned": false, "created_at": "", "updated_at": ""},
+                {"id": "T2", "title": "Empty", "pinned": false, "created_at": "", "updated_at": ""}
+            ], "relations": []}"#,
+        );
+        vault.write(
+            "topics/T1/01B.md",
+            "---\nid: 01B\ndate: 2025-06-01\ntitle: Swedish grid\ntopic_id: T1\ncountry_targets: [se]\n---\n\nBody",
+        );
+
+        let today = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let report = stale_coverage(&vault, &StalenessSettings::default(), today, None).unwrap();
+
+        let stale: Vec<&str> = report.countries.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(stale, vec!["fi"]);
+        assert_eq!(report.countries[0].days_since, 181);
+
+        let topics: Vec<&str> = report.topics.iter().map(|t| t.topic_id.as_str()).collect();
+        assert_eq!(topics, vec!["T2"]);
+
+        // Watching Sweden closely halves its threshold below 30 days
+        let mut settings = StalenessSettings {
+            threshold_days: 50,
+            ..Default::default()
+        };
+        settings.watch.insert("se".to_string(), WatchPriority::High);
+        let report = stale_coverage(&vault, &settings, today, Some("Europe")).unwrap();
+        let stale: Vec<&str> = report.countries.iter().map(|c| c.slug.as_str()).collect();
+        assert_eq!(stale, vec!["fi", "se"]);
+        assert_eq!(report.countries[1].threshold_days, 25);
+        assert!(report.topics.is_empty());
+
+        let uncovered: Vec<&str> = report.uncovered.iter().map(|c| c.slug.as_str()).collect();
+        assert!(uncovered.contains(&"dk"));
+        assert!(!uncovered.contains(&"fi") && !uncovered.contains(&"se"));
+        assert!(report.uncovered.iter().all(|c| c.region == "Europe"));
+    }
+}
@@ -0,0 +1,618 @@
+use super::assets::{asset_references, owner_dir, validate_file_name};
+use super::attachments::attachments_dir;
+use super::entities::PlaceResolver;
+use super::groups::CountryGroups;
+use super::import::add_to_manifest;
+use super::journal::JournalEvent;
+use super::notes::{collect_note_files, format_note, parse_note, NoteSource};
+use super::templates::{note_context, note_countries, ExportTemplates};
+use super::topics::{load_topics_manifest, save_topics_manifest};
+use mapanote_lib::models::{Attachment, FollowUp, Note, Topic, TopicCountryRelation, Visibility};
+use mapanote_lib::AppState;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+const NOTES_FILE: &str = "notes.jsonl";
+const TOPICS_FILE: &str = "topics.csv";
+const RELATIONS_FILE: &str = "topic_relations.csv";
+
+/// One line of `notes.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteRecord {
+    pub id: String,
+    pub title: String,
+    pub date: String,
+    pub created_at: Option<String>, // RFC3339
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    pub tags: Vec<String>,
+    pub source_type: String, // "country" or "topic"
+    pub owner_id: String,    // Country slug or topic ID the note is stored under
+    pub topic_id: Option<String>,
+    pub country_targets: Vec<String>,
+    pub countries: Vec<CountryRef>, // Targets with groups expanded, resolved from metadata
+    #[serde(default)]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>, // Copied to attachments/<id>/
+    #[serde(default)]
+    pub assets: Vec<String>, // Copied to assets/<source_type>/<owner_id>/
+    #[serde(default)]
+    pub review_by: Option<String>,
+    #[serde(default)]
+    pub follow_ups: Vec<FollowUp>,
+    #[serde(default)]
+    pub reviewed: Vec<String>,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rendered: Option<String>, // Output of the selected export template's note template
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryRef {
+    pub slug: String,
+    pub iso2: String,
+    pub iso3: String,
+    pub region: String,
+}
+
+/// Row of `topics.csv`
+#[derive(Debug, Serialize, Deserialize)]
+struct TopicRecord {
+    id: String,
+    title: String,
+    summary: Option<String>,
+    color: Option<String>,
+    pinned: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+/// Row of `topic_relations.csv`
+#[derive(Debug, Serialize, Deserialize)]
+struct RelationRecord {
+    topic_id: String,
+    country_slug: String,
+    iso2: String,
+    iso3: String,
+    region: String,
+    note_count: usize,
+    last_updated: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataExportReport {
+    pub destination: String,
+    pub notes: usize,
+    pub topics: usize,
+    pub relations: usize,
+    pub files: usize, // Assets and attachments copied
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataImportReport {
+    pub notes_imported: usize,
+    pub notes_skipped: usize, // A note with the same ID already exists
+    pub topics_imported: usize,
+    pub relations_imported: usize,
+    pub files: usize,
+}
+
+fn country_ref(resolver: &PlaceResolver, slug: &str) -> CountryRef {
+    let metadata = resolver.resolve(slug);
+
+    CountryRef {
+        slug: slug.to_string(),
+        iso2: metadata
+            .as_ref()
+            .map(|m| m.iso2.clone())
+            .unwrap_or_default(),
+        iso3: metadata
+            .as_ref()
+            .map(|m| m.iso3.clone())
+            .unwrap_or_default(),
+        region: metadata.map(|m| m.region).unwrap_or_default(),
+    }
+}
+
+fn copy_file(source: &Path, target: &Path) -> Result<(), String> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::copy(source, target)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))
+}
+
+fn export_data(
+    vault_root: &Path,
+    destination: &Path,
+    template: Option<&str>,
+) -> Result<DataExportReport, String> {
+    fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let resolver = PlaceResolver::load(&vault_path)?;
+    let groups = CountryGroups::load(Some(&vault_path));
+    let topics_manifest = load_topics_manifest(&vault_path)?;
+    let templates = ExportTemplates::load_selected(vault_root, template, &["md", "html"])?;
+
+    let mut records = Vec::new();
+    let mut files = 0;
+
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+        let note = match parse_note(&content) {
+            Some(note) => note,
+            None => continue,
+        };
+
+        let (source_type, owner_id) = match &file.source {
+            NoteSource::Country(slug) => ("country", slug.clone()),
+            NoteSource::Topic(topic_id) => ("topic", topic_id.clone()),
+        };
+        let source_dir = file.path.parent().unwrap_or(vault_root);
+
+        let mut targets = groups.expand_targets(&note.country_targets);
+        if targets.is_empty() && source_type == "country" {
+            targets.push(owner_id.clone());
+        }
+
+        // Carry the files the note depends on
+        let mut assets = Vec::new();
+        for name in asset_references(&note.content) {
+            let source = source_dir.join("assets").join(&name);
+            if validate_file_name(&name).is_err() || !source.is_file() || assets.contains(&name) {
+                continue;
+            }
+            copy_file(
+                &source,
+                &destination
+                    .join("assets")
+                    .join(source_type)
+                    .join(&owner_id)
+                    .join(&name),
+            )?;
+            assets.push(name);
+            files += 1;
+        }
+
+        for attachment in &note.attachments {
+            let source = attachments_dir(source_dir, &note.id).join(&attachment.name);
+            if source.is_file() {
+                copy_file(
+                    &source,
+                    &attachments_dir(destination, &note.id).join(&attachment.name),
+                )?;
+                files += 1;
+            }
+        }
+
+        let rendered = match &templates {
+            Some(templates) => {
+                let topic = match &file.source {
+                    NoteSource::Topic(topic_id) => {
+                        topics_manifest.topics.iter().find(|t| &t.id == topic_id)
+                    }
+                    NoteSource::Country(_) => None,
+                };
+                let countries = note_countries(&file.source, &note, &groups, &resolver);
+                templates.render(
+                    "note",
+                    &note_context(&note, &file.source, topic, &countries),
+                )?
+            }
+            None => None,
+        };
+
+        records.push(NoteRecord {
+            rendered,
+            created_at: Some(note.created_at).filter(|t| !t.is_empty()),
+            updated_at: Some(note.updated_at).filter(|t| !t.is_empty()),
+            countries: targets.iter().map(|s| country_ref(&resolver, s)).collect(),
+            source_type: source_type.to_string(),
+            owner_id,
+            assets,
+            id: note.id,
+            title: note.title,
+            date: note.date,
+            tags: note.tags,
+            topic_id: note.topic_id,
+            country_targets: note.country_targets,
+            visibility: note.visibility,
+            attachments: note.attachments,
+            review_by: note.review_by,
+            follow_ups: note.follow_ups,
+            reviewed: note.reviewed,
+            content: note.content,
+        });
+    }
+
+    records.sort_by(|a, b| (&a.date, &a.id).cmp(&(&b.date, &b.id)));
+
+    let mut writer = BufWriter::new(
+        fs::File::create(destination.join(NOTES_FILE))
+            .map_err(|e| format!("Failed to create {}: {}", NOTES_FILE, e))?,
+    );
+    for record in &records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| format!("Failed to serialize note: {}", e))?;
+        writeln!(writer, "{}", line).map_err(|e| format!("Failed to write note: {}", e))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write {}: {}", NOTES_FILE, e))?;
+
+    let mut topics_csv = csv::Writer::from_path(destination.join(TOPICS_FILE))
+        .map_err(|e| format!("Failed to create {}: {}", TOPICS_FILE, e))?;
+    for topic in &topics_manifest.topics {
+        topics_csv
+            .serialize(TopicRecord {
+                id: topic.id.clone(),
+                title: topic.title.clone(),
+                summary: topic.summary.clone(),
+                color: topic.color.clone(),
+                pinned: topic.pinned,
+                created_at: topic.created_at.clone(),
+                updated_at: topic.updated_at.clone(),
+            })
+            .map_err(|e| format!("Failed to write topic: {}", e))?;
+    }
+    topics_csv
+        .flush()
+        .map_err(|e| format!("Failed to write {}: {}", TOPICS_FILE, e))?;
+
+    let mut relations_csv = csv::Writer::from_path(destination.join(RELATIONS_FILE))
+        .map_err(|e| format!("Failed to create {}: {}", RELATIONS_FILE, e))?;
+    for relation in &topics_manifest.relations {
+        let country = country_ref(&resolver, &relation.country_slug);
+        relations_csv
+            .serialize(RelationRecord {
+                topic_id: relation.topic_id.clone(),
+                country_slug: relation.country_slug.clone(),
+                iso2: country.iso2,
+                iso3: country.iso3,
+                region: country.region,
+                note_count: relation.note_count,
+                last_updated: relation.last_updated.clone(),
+            })
+            .map_err(|e| format!("Failed to write topic relation: {}", e))?;
+    }
+    relations_csv
+        .flush()
+        .map_err(|e| format!("Failed to write {}: {}", RELATIONS_FILE, e))?;
+
+    Ok(DataExportReport {
+        destination: destination.to_string_lossy().to_string(),
+        notes: records.len(),
+        topics: topics_manifest.topics.len(),
+        relations: topics_manifest.relations.len(),
+        files,
+    })
+}
+
+/// Copy a note's assets and attachments and write it, unless a note with
+/// its ID is already there. Returns the note when it was written.
+fn import_note(
+    source: &Path,
+    dir: &Path,
+    record: NoteRecord,
+    report: &mut DataImportReport,
+) -> Result<Option<Note>, String> {
+    let note_path = dir.join(format!("{}.md", record.id));
+    if note_path.exists() {
+        report.notes_skipped += 1;
+        return Ok(None);
+    }
+
+    for name in &record.assets {
+        let exported = source
+            .join("assets")
+            .join(&record.source_type)
+            .join(&record.owner_id)
+            .join(name);
+        if exported.is_file() && !dir.join("assets").join(name).exists() {
+            copy_file(&exported, &dir.join("assets").join(name))?;
+            report.files += 1;
+        }
+    }
+
+    for attachment in &record.attachments {
+        let exported = attachments_dir(source, &record.id).join(&attachment.name);
+        if exported.is_file() {
+            copy_file(
+                &exported,
+                &attachments_dir(dir, &record.id).join(&attachment.name),
+            )?;
+            report.files += 1;
+        }
+    }
+
+    let note = Note {
+        id: record.id,
+        title: record.title,
+        content: record.content,
+        date: record.date,
+        tags: record.tags,
+        topic_id: record.topic_id,
+        country_targets: record.country_targets,
+        attachments: record.attachments,
+        visibility: record.visibility,
+        created_at: record.created_at.unwrap_or_default(),
+        updated_at: record.updated_at.unwrap_or_default(),
+        review_by: record.review_by,
+        follow_ups: record.follow_ups,
+        reviewed: record.reviewed,
+    };
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create notes directory: {}", e))?;
+    fs::write(&note_path, format_note(&note))
+        .map_err(|e| format!("Failed to write note: {}", e))?;
+    report.notes_imported += 1;
+
+    Ok(Some(note))
+}
+
+fn import_data(vault_root: &Path, source: &Path) -> Result<DataImportReport, String> {
+    let notes_path = source.join(NOTES_FILE);
+    if !notes_path.is_file() {
+        return Err(format!("{} not found in {}", NOTES_FILE, source.display()));
+    }
+
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let mut report = DataImportReport {
+        notes_imported: 0,
+        notes_skipped: 0,
+        topics_imported: 0,
+        relations_imported: 0,
+        files: 0,
+    };
+
+    // Read and check everything before writing, so a bad record doesn't
+    // leave the vault half imported
+    let mut topics_manifest = load_topics_manifest(&vault_path)?;
+
+    if source.join(TOPICS_FILE).is_file() {
+        let mut reader = csv::Reader::from_path(source.join(TOPICS_FILE))
+            .map_err(|e| format!("Failed to read {}: {}", TOPICS_FILE, e))?;
+        for record in reader.deserialize::<TopicRecord>() {
+            let record = record.map_err(|e| format!("Invalid topic row: {}", e))?;
+            if topics_manifest.topics.iter().any(|t| t.id == record.id) {
+                continue;
+            }
+            topics_manifest.topics.push(Topic {
+                id: record.id,
+                title: record.title,
+                summary: record.summary.filter(|s| !s.is_empty()),
+                color: record.color.filter(|s| !s.is_empty()),
+                pinned: record.pinned,
+                created_at: record.created_at,
+                updated_at: record.updated_at,
+            });
+            report.topics_imported += 1;
+        }
+    }
+
+    if source.join(RELATIONS_FILE).is_file() {
+        let mut reader = csv::Reader::from_path(source.join(RELATIONS_FILE))
+            .map_err(|e| format!("Failed to read {}: {}", RELATIONS_FILE, e))?;
+        for record in reader.deserialize::<RelationRecord>() {
+            let record = record.map_err(|e| format!("Invalid topic relation row: {}", e))?;
+            let exists = topics_manifest
+                .relations
+                .iter()
+                .any(|r| r.topic_id == record.topic_id && r.country_slug == record.country_slug);
+            if exists {
+                continue;
+            }
+            topics_manifest.relations.push(TopicCountryRelation {
+                topic_id: record.topic_id,
+                country_slug: record.country_slug,
+                note_count: record.note_count,
+                last_updated: record.last_updated.filter(|s| !s.is_empty()),
+            });
+            report.relations_imported += 1;
+        }
+    }
+
+    let file =
+        fs::File::open(&notes_path).map_err(|e| format!("Failed to read {}: {}", NOTES_FILE, e))?;
+    let mut records = Vec::new();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read {}: {}", NOTES_FILE, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: NoteRecord = serde_json::from_str(&line)
+            .map_err(|e| format!("Invalid note on line {}: {}", index + 1, e))?;
+        validate_file_name(&record.id)?;
+        for name in &record.assets {
+            validate_file_name(name)?;
+        }
+        for attachment in &record.attachments {
+            validate_file_name(&attachment.name)?;
+        }
+
+        let dir = owner_dir(vault_root, &record.source_type, &record.owner_id)?;
+        records.push((dir, record));
+    }
+
+    // Topics and relations first, so imported topic notes have a home
+    save_topics_manifest(&vault_path, &topics_manifest)?;
+
+    let mut country_notes = Vec::new();
+    let mut result = Ok(());
+
+    for (dir, record) in records {
+        let owner = (record.source_type == "country").then(|| record.owner_id.clone());
+        match import_note(source, &dir, record, &mut report) {
+            Ok(Some(note)) => {
+                if let Some(slug) = owner {
+                    country_notes.push((slug, note.date, note.tags));
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+
+    // Count the notes that were written even if a later one failed
+    add_to_manifest(
+        vault_root,
+        country_notes
+            .iter()
+            .map(|(slug, date, tags)| (slug.as_str(), date.as_str(), &tags[..])),
+    )?;
+    result?;
+
+    Ok(report)
+}
+
+/// Export every note as `notes.jsonl`, plus `topics.csv` and
+/// `topic_relations.csv`, with the assets and attachments notes use.
+/// With a `template`, each record also carries the note rendered through it.
+#[tauri::command]
+pub fn export_vault_data(
+    destination: String,
+    template: Option<String>,
+    state: State<AppState>,
+) -> Result<DataExportReport, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    export_data(
+        &PathBuf::from(vault_path),
+        &PathBuf::from(destination),
+        template.as_deref(),
+    )
+}
+
+/// Import a folder written by `export_vault_data`, keeping note IDs.
+/// Notes whose ID already exists are skipped.
+#[tauri::command]
+pub fn import_vault_data(
+    source_path: String,
+    state: State<AppState>,
+) -> Result<DataImportReport, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let vault_root = PathBuf::from(vault_path);
+    let report = import_data(&vault_root, &PathBuf::from(source_path))?;
+
+    if report.notes_imported + report.topics_imported + report.relations_imported > 0 {
+        JournalEvent::new("import.vault_data").record(&vault_root);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+    use mapanote_lib::models::TopicsManifest;
+
+    #[test]
+    fn test_export_and_reimport() {
+        let vault = TestVault::new();
+        let export = vault.scratch("export");
+        let fresh = TestVault::new();
+
+        let finland = vault.join("notes").join("fi");
+        fs::create_dir_all(finland.join("assets")).unwrap();
+        fs::create_dir_all(attachments_dir(&finland, "01JA0000000000000000000000")).unwrap();
+        fs::write(finland.join("assets").join("map.png"), b"png").unwrap();
+        fs::write(
+            attachments_dir(&finland, "01JA0000000000000000000000").join("report.pdf"),
+            b"pdf",
+        )
+        .unwrap();
+        fs::write(
+            finland.join("01JA0000000000000000000000.md"),
+            "---\nid: 01JA0000000000000000000000\ndate: 2025-10-07\ntitle: Energy\ntags: [\"energy\"]\ntopic_id: \ncountry_targets: [fi]\nattachments: [{\"name\":\"report.pdf\",\"mime\":\"application/pdf\",\"size\":3,\"added\":\"2025-10-07\"}]\n---\n\n![Map](assets/map.png)",
+        )
+        .unwrap();
+
+        let mut topics = TopicsManifest::new();
+        topics.topics.push(Topic {
+            id: "T1".to_string(),
+            title: "Arctic".to_string(),
+            summary: None,
+            color: Some("#3b82f6".to_string()),
+            pinned: false,
+            created_at: "2025-10-01T00:00:00Z".to_string(),
+            updated_at: "2025-10-01T00:00:00Z".to_string(),
+        });
+        topics.relations.push(TopicCountryRelation {
+            topic_id: "T1".to_string(),
+            country_slug: "fi".to_string(),
+            note_count: 0,
+            last_updated: None,
+        });
+        fs::write(
+            vault.join("topics.json"),
+            serde_json::to_string(&topics).unwrap(),
+        )
+        .unwrap();
+
+        let exported = export_data(&vault, &export, None).unwrap();
+        assert_eq!(
+            (exported.notes, exported.topics, exported.relations),
+            (1, 1, 1)
+        );
+        assert_eq!(exported.files, 2);
+
+        let line = fs::read_to_string(export.join(NOTES_FILE)).unwrap();
+        let record: NoteRecord = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(record.countries[0].iso3, "FIN");
+        assert_eq!(record.countries[0].region, "Europe");
+        assert!(record.created_at.is_some());
+
+        let imported = import_data(&fresh, &export).unwrap();
+        assert_eq!(imported.notes_imported, 1);
+        assert_eq!(imported.topics_imported, 1);
+        assert_eq!(imported.relations_imported, 1);
+        assert!(fresh.join("notes/fi/assets/map.png").exists());
+        assert!(
+            attachments_dir(&fresh.join("notes/fi"), "01JA0000000000000000000000")
+                .join("report.pdf")
+                .exists()
+        );
+
+        let note = parse_note(
+            &fs::read_to_string(fresh.join("notes/fi/01JA0000000000000000000000.md")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(note.title, "Energy");
+        assert_eq!(note.attachments.len(), 1);
+
+        assert_eq!(fresh.manifest().countries["fi"].note_count, 1);
+
+        // A second import finds the note already there
+        assert_eq!(import_data(&fresh, &export).unwrap().notes_skipped, 1);
+
+        // A bad record anywhere stops the import before anything is written
+        let broken = TestVault::new();
+        let mut lines = fs::read_to_string(export.join(NOTES_FILE)).unwrap();
+        lines.push_str("{\"id\": \"01JB\"}\n");
+        fs::write(export.join(NOTES_FILE), lines).unwrap();
+        assert!(import_data(&broken, &export).is_err());
+        assert!(!broken.join("notes").exists());
+        assert!(load_topics_manifest(&broken.to_string_lossy())
+            .unwrap()
+            .topics
+            .is_empty());
+        assert!(broken.manifest().countries.is_empty());
+    }
+}
@@ -0,0 +1,309 @@
+use super::entities::PlaceResolver;
+use super::metadata::get_countries_metadata;
+use mapanote_lib::AppState;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CountryMatch {
+    pub field: String, // "title" or "content"
+    pub text: String,  // Matched text as written in the note
+    pub start: usize,  // UTF-16 offsets, so the frontend can slice strings directly
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CountrySuggestion {
+    pub slug: String,
+    pub name: String,
+    pub matches: Vec<CountryMatch>,
+}
+
+/// How a term was registered; lower values win when two places share a term
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum TermKind {
+    Name,
+    Alias,
+    Demonym,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    slug: Option<String>, // None when the term is ambiguous at its priority
+    kind: TermKind,
+    exact_case: Option<String>, // Acronyms ("USA", "UK") must match case exactly
+}
+
+/// A word in the scanned text with its byte range
+struct Token<'a> {
+    start: usize,
+    end: usize,
+    text: &'a str,
+    lower: String,
+}
+
+/// Finds country and entity names, aliases and demonyms in free text
+pub struct CountryDetector {
+    terms: HashMap<Vec<String>, Term>,
+    names: HashMap<String, String>,
+    max_words: usize,
+}
+
+impl CountryDetector {
+    /// Detector for embedded countries plus the vault's entities (if open)
+    pub fn load(vault_path: Option<&str>) -> Result<Self, String> {
+        let mut detector = Self {
+            terms: HashMap::new(),
+            names: HashMap::new(),
+            max_words: 0,
+        };
+
+        for country in get_countries_metadata() {
+            detector.add_place(
+                &country.slug,
+                &country.name,
+                &country.aliases,
+                &country.demonyms,
+                &country.iso2,
+            );
+        }
+
+        if let Some(vault_path) = vault_path {
+            let resolver = PlaceResolver::load(vault_path)?;
+            for entity in resolver.entities() {
+                detector.add_place(&entity.slug, &entity.name, &entity.aliases, &[], "");
+            }
+        }
+
+        Ok(detector)
+    }
+
+    fn add_place(
+        &mut self,
+        slug: &str,
+        name: &str,
+        aliases: &[String],
+        demonyms: &[String],
+        iso2: &str,
+    ) {
+        self.names.insert(slug.to_string(), name.to_string());
+        self.add_term(slug, name, TermKind::Name);
+
+        // Two-letter codes collide with ordinary words (IT, IS, NO)
+        for alias in aliases.iter().filter(|a| a.as_str() != iso2) {
+            self.add_term(slug, alias, TermKind::Alias);
+        }
+
+        for demonym in demonyms {
+            self.add_term(slug, demonym, TermKind::Demonym);
+        }
+    }
+
+    fn add_term(&mut self, slug: &str, term: &str, kind: TermKind) {
+        let words: Vec<String> = tokenize(term).into_iter().map(|t| t.lower).collect();
+
+        if words.is_empty() || (words.len() == 1 && words[0].chars().count() < 2) {
+            return;
+        }
+
+        let is_acronym = term.chars().all(|c| c.is_uppercase() || !c.is_alphabetic());
+        let exact_case = is_acronym.then(|| term.to_string());
+
+        self.max_words = self.max_words.max(words.len());
+
+        match self.terms.get_mut(&words) {
+            Some(existing) if existing.kind < kind => {}
+            Some(existing) if existing.kind == kind => {
+                if existing.slug.as_deref() != Some(slug) {
+                    existing.slug = None;
+                }
+            }
+            _ => {
+                self.terms.insert(
+                    words,
+                    Term {
+                        slug: Some(slug.to_string()),
+                        kind,
+                        exact_case,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Scan a note's title and content, returning one suggestion per place
+    /// in order of first appearance
+    pub fn detect(&self, title: &str, content: &str) -> Vec<CountrySuggestion> {
+        let mut suggestions: Vec<CountrySuggestion> = Vec::new();
+
+        for (field, text) in [("title", title), ("content", content)] {
+            for (slug, m) in self.scan(field, text) {
+                match suggestions.iter_mut().find(|s| s.slug == slug) {
+                    Some(suggestion) => suggestion.matches.push(m),
+                    None => suggestions.push(CountrySuggestion {
+                        name: self.names.get(&slug).cloned().unwrap_or(slug.clone()),
+                        slug,
+                        matches: vec![m],
+                    }),
+                }
+            }
+        }
+
+        suggestions
+    }
+
+    fn scan(&self, field: &str, text: &str) -> Vec<(String, CountryMatch)> {
+        let tokens = tokenize(text);
+        let mut found = Vec::new();
+        let mut i = 0;
+
+        while i < tokens.len() {
+            // Prefer the longest term ("South Sudan" over "Sudan")
+            let longest = (1..=self.max_words.min(tokens.len() - i))
+                .rev()
+                .find_map(|len| {
+                    self.match_at(text, &tokens[i..i + len])
+                        .map(|slug| (len, slug))
+                });
+
+            match longest {
+                Some((len, slug)) => {
+                    let start = tokens[i].start;
+                    let end = tokens[i + len - 1].end;
+
+                    found.push((
+                        slug,
+                        CountryMatch {
+                            field: field.to_string(),
+                            text: text[start..end].to_string(),
+                            start: utf16_offset(text, start),
+                            end: utf16_offset(text, end),
+                        },
+                    ));
+                    i += len;
+                }
+                None => i += 1,
+            }
+        }
+
+        found
+    }
+
+    fn match_at(&self, text: &str, tokens: &[Token]) -> Option<String> {
+        let key: Vec<String> = tokens.iter().map(|t| t.lower.clone()).collect();
+        let term = self.terms.get(&key)?;
+        let slug = term.slug.clone()?;
+
+        // Words must be separated by spaces or dashes, not sentence punctuation
+        let joined = tokens.windows(2).all(|pair| {
+            !text[pair[0].end..pair[1].start].contains(['.', '!', '?', ';', ':', '(', ')', '\n'])
+        });
+        if !joined {
+            return None;
+        }
+
+        let first = tokens[0].text;
+        let matched = &text[tokens[0].start..tokens[tokens.len() - 1].end];
+
+        // Place names are proper nouns: "turkey" or "chad" in lower case are ignored
+        let case_ok = match &term.exact_case {
+            Some(exact) => matched == exact,
+            None => first.chars().next().is_some_and(|c| c.is_uppercase()),
+        };
+
+        case_ok.then_some(slug)
+    }
+}
+
+/// Split text into runs of alphanumeric characters
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push(token(text, s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(s) = start {
+        tokens.push(token(text, s, text.len()));
+    }
+
+    tokens
+}
+
+fn token(text: &str, start: usize, end: usize) -> Token<'_> {
+    Token {
+        start,
+        end,
+        text: &text[start..end],
+        lower: text[start..end].to_lowercase(),
+    }
+}
+
+fn utf16_offset(text: &str, byte_offset: usize) -> usize {
+    text[..byte_offset].encode_utf16().count()
+}
+
+/// Suggest `country_targets` for a note by scanning its title and content
+#[tauri::command]
+pub fn suggest_countries_for_text(
+    title: String,
+    content: String,
+    state: State<AppState>,
+) -> Result<Vec<CountrySuggestion>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let detector = CountryDetector::load(reader.as_deref())?;
+
+    Ok(detector.detect(&title, &content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slugs(suggestions: &[CountrySuggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.slug.as_str()).collect()
+    }
+
+    #[test]
+    fn test_detects_names_aliases_and_demonyms() {
+        let detector = CountryDetector::load(None).unwrap();
+
+        let found = detector.detect(
+            "Suomi and South Sudan",
+            "Finnish diplomats met Russian officials in the USA.",
+        );
+
+        assert_eq!(slugs(&found), vec!["fi", "ss", "ru", "us"]);
+        assert_eq!(found[0].matches.len(), 2);
+        assert_eq!(found[0].matches[1].field, "content");
+    }
+
+    #[test]
+    fn test_ignores_common_words() {
+        let detector = CountryDetector::load(None).unwrap();
+
+        let found = detector.detect("", "We ate turkey, then IT said it is fine. Chad");
+        assert_eq!(slugs(&found), vec!["td"]);
+    }
+
+    #[test]
+    fn test_match_offsets_are_utf16() {
+        let detector = CountryDetector::load(None).unwrap();
+
+        let found = detector.detect("", "Ça va: Côte d'Ivoire");
+        let m = &found[0].matches[0];
+
+        assert_eq!(found[0].slug, "ci");
+        assert_eq!(m.text, "Côte d'Ivoire");
+        assert_eq!((m.start, m.end), (7, 20));
+    }
+}
@@ -0,0 +1,451 @@
+use super::journal::JournalEvent;
+use super::metadata::{find_country, get_countries_metadata};
+use mapanote_lib::models::{CountryMetadata, EntitiesManifest, EntityKind, GeoEntity};
+use mapanote_lib::AppState;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+fn get_entities_path(vault_path: &str) -> PathBuf {
+    PathBuf::from(vault_path)
+        .join(".mapanote")
+        .join("entities.json")
+}
+
+/// Load the vault's custom entities (empty if the file doesn't exist)
+pub fn load_entities_manifest(vault_path: &str) -> Result<EntitiesManifest, String> {
+    let entities_path = get_entities_path(vault_path);
+
+    if !entities_path.exists() {
+        return Ok(EntitiesManifest::new());
+    }
+
+    let content = fs::read_to_string(&entities_path)
+        .map_err(|e| format!("Failed to read entities.json: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse entities.json: {}", e))
+}
+
+/// Save the vault's custom entities
+fn save_entities_manifest(vault_path: &str, manifest: &EntitiesManifest) -> Result<(), String> {
+    let entities_path = get_entities_path(vault_path);
+
+    if let Some(parent) = entities_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize entities: {}", e))?;
+    fs::write(&entities_path, json).map_err(|e| format!("Failed to write entities.json: {}", e))?;
+    Ok(())
+}
+
+/// Turn a display name into a slug ("Western Sahara" -> "western-sahara")
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.trim_matches('-').to_string()
+}
+
+/// Resolves note slugs to display metadata: embedded countries first,
+/// then entities defined in the open vault
+pub struct PlaceResolver {
+    entities: Vec<GeoEntity>,
+}
+
+impl PlaceResolver {
+    /// Fails when the vault's entities.json can't be read: without it every
+    /// entity-owned note would silently drop out of listings and exports
+    pub fn load(vault_path: &str) -> Result<Self, String> {
+        let entities = load_entities_manifest(vault_path)?.entities;
+
+        Ok(Self { entities })
+    }
+
+    pub fn entity(&self, slug: &str) -> Option<&GeoEntity> {
+        self.entities.iter().find(|e| e.slug == slug)
+    }
+
+    pub fn entities(&self) -> &[GeoEntity] {
+        &self.entities
+    }
+
+    /// Metadata for a country or entity slug
+    pub fn resolve(&self, slug: &str) -> Option<CountryMetadata> {
+        if let Some(country) = find_country(slug) {
+            return Some(country.clone());
+        }
+
+        self.entity(slug).map(|entity| self.entity_metadata(entity))
+    }
+
+    /// Display name for a slug, falling back to the slug itself
+    pub fn name(&self, slug: &str) -> String {
+        self.resolve(slug)
+            .map(|m| m.name)
+            .unwrap_or_else(|| slug.to_string())
+    }
+
+    /// Present an entity in the same shape as an embedded country, inheriting
+    /// region and location from its parent or map reference when not set
+    fn entity_metadata(&self, entity: &GeoEntity) -> CountryMetadata {
+        let related = entity
+            .parent
+            .as_deref()
+            .or(entity.map_ref.as_deref())
+            .filter(|slug| *slug != entity.slug)
+            .and_then(|slug| {
+                find_country(slug).cloned().or_else(|| {
+                    // Parent is another entity (one level only, avoids cycles)
+                    self.entity(slug).map(|parent| CountryMetadata {
+                        region: "Other".to_string(),
+                        subregion: parent.name.clone(),
+                        centroid: parent.centroid,
+                        ..empty_metadata(parent)
+                    })
+                })
+            });
+
+        CountryMetadata {
+            region: related
+                .as_ref()
+                .map(|r| r.region.clone())
+                .unwrap_or_else(|| "Other".to_string()),
+            subregion: related
+                .as_ref()
+                .map(|r| r.subregion.clone())
+                .unwrap_or_else(|| entity.kind.as_str().to_string()),
+            centroid: entity
+                .centroid
+                .or_else(|| related.as_ref().and_then(|r| r.centroid)),
+            ..empty_metadata(entity)
+        }
+    }
+}
+
+fn empty_metadata(entity: &GeoEntity) -> CountryMetadata {
+    CountryMetadata {
+        slug: entity.slug.clone(),
+        name: entity.name.clone(),
+        iso2: String::new(),
+        iso3: String::new(),
+        iso_numeric: String::new(),
+        summary: entity.summary.clone().unwrap_or_default(),
+        region: String::new(),
+        subregion: String::new(),
+        aliases: entity.aliases.clone(),
+        demonyms: Vec::new(),
+        capital: None,
+        centroid: entity.centroid,
+        bbox: None,
+        currencies: Vec::new(),
+        languages: Vec::new(),
+    }
+}
+
+fn validate_entity(
+    manifest: &EntitiesManifest,
+    slug: &str,
+    parent: &Option<String>,
+    map_ref: &Option<String>,
+) -> Result<(), String> {
+    if slug.is_empty() {
+        return Err("Entity slug cannot be empty".to_string());
+    }
+
+    if let Some(parent) = parent {
+        if parent == slug {
+            return Err("An entity cannot be its own parent".to_string());
+        }
+        if find_country(parent).is_none() && !manifest.entities.iter().any(|e| &e.slug == parent) {
+            return Err(format!("Unknown parent: {}", parent));
+        }
+    }
+
+    if let Some(map_ref) = map_ref {
+        if find_country(map_ref).is_none() {
+            return Err(format!("Unknown map reference: {}", map_ref));
+        }
+    }
+
+    Ok(())
+}
+
+/// Get all custom entities defined in the vault
+#[tauri::command]
+pub fn get_entities(state: State<AppState>) -> Result<Vec<GeoEntity>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    Ok(load_entities_manifest(vault_path)?.entities)
+}
+
+/// Define a new custom entity (territory, region, organisation...)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn create_entity(
+    name: String,
+    kind: EntityKind,
+    slug: Option<String>,
+    aliases: Vec<String>,
+    parent: Option<String>,
+    map_ref: Option<String>,
+    summary: Option<String>,
+    state: State<AppState>,
+) -> Result<GeoEntity, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let mut manifest = load_entities_manifest(vault_path)?;
+
+    let slug = slugify(slug.as_deref().unwrap_or(&name));
+
+    if find_country(&slug).is_some() {
+        return Err(format!("Slug {} is already used by a country", slug));
+    }
+
+    if manifest.entities.iter().any(|e| e.slug == slug) {
+        return Err(format!("Entity {} already exists", slug));
+    }
+
+    validate_entity(&manifest, &slug, &parent, &map_ref)?;
+
+    let entity = GeoEntity {
+        slug,
+        name: name.trim().to_string(),
+        kind,
+        aliases,
+        parent,
+        map_ref,
+        summary,
+        centroid: None,
+    };
+
+    manifest.entities.push(entity.clone());
+    save_entities_manifest(vault_path, &manifest)?;
+
+    JournalEvent::new("entity.created")
+        .entity(&entity.slug)
+        .country(&entity.slug)
+        .record(Path::new(vault_path));
+
+    Ok(entity)
+}
+
+/// Update an existing custom entity (the slug itself is immutable)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn update_entity(
+    slug: String,
+    name: String,
+    kind: EntityKind,
+    aliases: Vec<String>,
+    parent: Option<String>,
+    map_ref: Option<String>,
+    summary: Option<String>,
+    state: State<AppState>,
+) -> Result<GeoEntity, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let mut manifest = load_entities_manifest(vault_path)?;
+
+    validate_entity(&manifest, &slug, &parent, &map_ref)?;
+
+    let entity = manifest
+        .entities
+        .iter_mut()
+        .find(|e| e.slug == slug)
+        .ok_or_else(|| format!("Entity {} not found", slug))?;
+
+    entity.name = name.trim().to_string();
+    entity.kind = kind;
+    entity.aliases = aliases;
+    entity.parent = parent;
+    entity.map_ref = map_ref;
+    entity.summary = summary;
+
+    let updated = entity.clone();
+    save_entities_manifest(vault_path, &manifest)?;
+
+    JournalEvent::new("entity.updated")
+        .entity(&slug)
+        .country(&slug)
+        .record(Path::new(vault_path));
+
+    Ok(updated)
+}
+
+/// Remove a custom entity definition (its notes stay on disk)
+#[tauri::command]
+pub fn delete_entity(slug: String, state: State<AppState>) -> Result<(), String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let mut manifest = load_entities_manifest(vault_path)?;
+
+    if manifest
+        .entities
+        .iter()
+        .any(|e| e.parent.as_deref() == Some(slug.as_str()))
+    {
+        return Err(format!("Entity {} is the parent of other entities", slug));
+    }
+
+    manifest.entities.retain(|e| e.slug != slug);
+    save_entities_manifest(vault_path, &manifest)?;
+
+    JournalEvent::new("entity.deleted")
+        .entity(&slug)
+        .country(&slug)
+        .record(Path::new(vault_path));
+
+    Ok(())
+}
+
+/// All embedded countries plus the open vault's entities, as metadata
+pub fn all_places(vault_path: Option<&str>) -> Result<Vec<CountryMetadata>, String> {
+    let mut places = get_countries_metadata().clone();
+
+    if let Some(vault_path) = vault_path {
+        let resolver = PlaceResolver::load(vault_path)?;
+        places.extend(
+            resolver
+                .entities()
+                .iter()
+                .map(|e| resolver.entity_metadata(e)),
+        );
+    }
+
+    Ok(places)
+}
+
+/// Resolves free-form place references from imported data (slug, ISO code,
+/// name or alias, in any case) to a slug. References shared by two places
+/// at the same rank resolve to nothing.
+pub struct PlaceLookup {
+    keys: HashMap<String, (u8, Option<String>)>, // key -> (rank, slug)
+}
+
+impl PlaceLookup {
+    pub fn load(vault_path: Option<&str>) -> Result<Self, String> {
+        let mut lookup = Self {
+            keys: HashMap::new(),
+        };
+
+        for country in get_countries_metadata() {
+            lookup.add(&country.slug, &country.slug, 0);
+            lookup.add(&country.iso2, &country.slug, 0);
+            lookup.add(&country.iso3, &country.slug, 0);
+            lookup.add(&country.name, &country.slug, 1);
+            for alias in &country.aliases {
+                lookup.add(alias, &country.slug, 2);
+            }
+        }
+
+        if let Some(vault_path) = vault_path {
+            let resolver = PlaceResolver::load(vault_path)?;
+            for entity in resolver.entities() {
+                lookup.add(&entity.slug, &entity.slug, 0);
+                lookup.add(&entity.name, &entity.slug, 1);
+                for alias in &entity.aliases {
+                    lookup.add(alias, &entity.slug, 2);
+                }
+            }
+        }
+
+        Ok(lookup)
+    }
+
+    fn add(&mut self, reference: &str, slug: &str, rank: u8) {
+        let key = slugify(reference);
+        if key.is_empty() {
+            return;
+        }
+
+        match self.keys.get_mut(&key) {
+            Some((existing, _)) if *existing < rank => {}
+            Some((existing, existing_slug)) if *existing == rank => {
+                if existing_slug.as_deref() != Some(slug) {
+                    *existing_slug = None;
+                }
+            }
+            _ => {
+                self.keys.insert(key, (rank, Some(slug.to_string())));
+            }
+        }
+    }
+
+    /// Slug for a reference such as "FI", "FIN", "Finland" or "Suomi"
+    pub fn find(&self, reference: &str) -> Option<&str> {
+        self.keys
+            .get(&slugify(reference))
+            .and_then(|(_, slug)| slug.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Western Sahara"), "western-sahara");
+        assert_eq!(slugify("  The Arctic! "), "the-arctic");
+    }
+
+    #[test]
+    fn test_entity_inherits_parent_region() {
+        let resolver = PlaceResolver {
+            entities: vec![GeoEntity {
+                slug: "aland".to_string(),
+                name: "Åland".to_string(),
+                kind: EntityKind::Territory,
+                aliases: Vec::new(),
+                parent: Some("fi".to_string()),
+                map_ref: None,
+                summary: None,
+                centroid: None,
+            }],
+        };
+
+        let meta = resolver.resolve("aland").unwrap();
+        assert_eq!(meta.name, "Åland");
+        assert_eq!(meta.region, "Europe");
+        assert!(meta.centroid.is_some());
+        assert_eq!(resolver.name("unknown"), "unknown");
+    }
+
+    #[test]
+    fn test_place_lookup() {
+        let lookup = PlaceLookup::load(None).unwrap();
+
+        assert_eq!(lookup.find("FI"), Some("fi"));
+        assert_eq!(lookup.find("fin"), Some("fi"));
+        assert_eq!(lookup.find("finland"), Some("fi"));
+        assert_eq!(lookup.find("Republic of Finland"), Some("fi"));
+        assert_eq!(lookup.find("Atlantis"), None);
+    }
+
+    #[test]
+    fn test_malformed_entities_fail_to_load() {
+        let vault = TestVault::new();
+        vault.write(".mapanote/entities.json", "{ not json");
+        let vault_path = vault.to_string_lossy();
+
+        let err = PlaceResolver::load(&vault_path).err().unwrap();
+        assert!(err.contains("Failed to parse entities.json"));
+        assert!(all_places(Some(&vault_path)).is_err());
+    }
+}
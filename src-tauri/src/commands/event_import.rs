@@ -0,0 +1,620 @@
+use super::entities::PlaceLookup;
+use super::import::{
+    imported_source_keys, record_notes_in_manifest, remove_imported_note, write_imported_note,
+    NewNote,
+};
+use super::journal::JournalEvent;
+use super::notes::NoteSource;
+use super::topics::{load_topics_manifest, save_topics_manifest};
+use mapanote_lib::models::TopicCountryRelation;
+use mapanote_lib::AppState;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::State;
+
+/// Prefix of `imported_from` values written by the event importer, which
+/// are `event:<dataset>:<row key>`
+const EVENT_KEY_PREFIX: &str = "event:";
+
+/// Date formats tried when the import doesn't specify one
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%d/%m/%Y", "%Y/%m/%d", "%d.%m.%Y"];
+
+/// Which column holds each field of an event
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventColumnMapping {
+    pub date: String,
+    pub country: String, // ISO2, ISO3 or name; several separated by `;` or `|`
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    pub tags: Option<String>, // Separated by `;` or `,`
+    #[serde(default)]
+    pub source_key: Option<String>, // Stable row ID used to skip rows imported before
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventImportOptions {
+    #[serde(default)]
+    pub topic_id: Option<String>, // Create topic notes targeting the row's countries
+    #[serde(default)]
+    pub tags: Vec<String>, // Added to every note
+    #[serde(default)]
+    pub date_format: Option<String>, // chrono format, e.g. "%m/%d/%Y"
+    #[serde(default)]
+    pub dataset: Option<String>, // Namespace for row keys; defaults to the file's path
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedEvent {
+    pub row: usize, // 1-based, not counting the CSV header
+    pub source_key: String,
+    pub targets: Vec<String>,
+    pub note_ids: Vec<String>, // Empty in a dry run
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkippedEvent {
+    pub row: usize,
+    pub source_key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedEvent {
+    pub row: usize,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventImportSummary {
+    pub dry_run: bool,
+    pub created: Vec<CreatedEvent>,
+    pub skipped: Vec<SkippedEvent>,
+    pub failed: Vec<FailedEvent>,
+}
+
+type Row = HashMap<String, String>;
+
+/// Read rows from a CSV file, or a JSON array of objects
+fn read_rows(path: &Path) -> Result<Vec<Row>, String> {
+    let is_json = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+
+    if is_json {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let items: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(&content)
+            .map_err(|e| format!("Expected a JSON array of objects: {}", e))?;
+
+        return Ok(items
+            .into_iter()
+            .map(|item| {
+                item.into_iter()
+                    .map(|(key, value)| (key, json_to_string(&value)))
+                    .collect()
+            })
+            .collect());
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .map_err(|e| format!("Failed to read CSV: {}", e))?;
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV header: {}", e))?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| format!("Failed to read CSV row: {}", e))?;
+            Ok(headers
+                .iter()
+                .cloned()
+                .zip(record.iter().map(|v| v.trim().to_string()))
+                .collect())
+        })
+        .collect()
+}
+
+fn json_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.trim().to_string(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(json_to_string)
+            .collect::<Vec<_>>()
+            .join(";"),
+        other => other.to_string(),
+    }
+}
+
+fn split_values(value: &str, separators: &[char]) -> Vec<String> {
+    value
+        .split(separators)
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn parse_date(value: &str, format: Option<&str>) -> Option<chrono::NaiveDate> {
+    let value = value.trim();
+
+    match format {
+        Some(format) => chrono::NaiveDate::parse_from_str(value, format).ok(),
+        None => DATE_FORMATS
+            .iter()
+            .find_map(|format| chrono::NaiveDate::parse_from_str(value, format).ok())
+            // Timestamps such as "2024-03-01T12:00:00Z"
+            .or_else(|| chrono::NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()),
+    }
+}
+
+/// Title for an event: the title column, else the actor and the start of the description
+fn event_title(title: &str, actor: &str, description: &str, date: &str) -> String {
+    if !title.is_empty() {
+        return title.to_string();
+    }
+
+    let first_line = description.lines().next().unwrap_or("");
+    let summary: String = first_line.chars().take(80).collect();
+    let summary = if first_line.chars().count() > 80 {
+        format!("{}...", summary.trim_end())
+    } else {
+        summary
+    };
+
+    match (actor.is_empty(), summary.is_empty()) {
+        (false, false) => format!("{}: {}", actor, summary),
+        (false, true) => format!("{} ({})", actor, date),
+        (true, false) => summary,
+        (true, true) => format!("Event on {}", date),
+    }
+}
+
+/// A row's source key: the mapped column, or a hash of the mapped fields
+fn row_key(row: &Row, mapping: &EventColumnMapping) -> String {
+    let value = |column: &Option<String>| {
+        column
+            .as_ref()
+            .and_then(|c| row.get(c))
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let key = value(&mapping.source_key);
+    if !key.is_empty() {
+        return key;
+    }
+
+    let fields = [
+        row.get(&mapping.date).cloned().unwrap_or_default(),
+        row.get(&mapping.country).cloned().unwrap_or_default(),
+        value(&mapping.actor),
+        value(&mapping.title),
+        value(&mapping.description),
+    ];
+    let hash = Sha256::digest(fields.join("\u{1f}").as_bytes());
+    hash[..16].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Create notes for `rows`. Row keys are only compared within `dataset`, so
+/// two files that both number their rows from 1 don't skip each other.
+fn import_rows(
+    vault_root: &Path,
+    dataset: &str,
+    rows: Vec<Row>,
+    mapping: &EventColumnMapping,
+    options: &EventImportOptions,
+) -> Result<EventImportSummary, String> {
+    // Every mapped column must exist (checked against the first row)
+    if let Some(first) = rows.first() {
+        let columns = [
+            Some(&mapping.date),
+            Some(&mapping.country),
+            mapping.title.as_ref(),
+            mapping.description.as_ref(),
+            mapping.actor.as_ref(),
+            mapping.tags.as_ref(),
+            mapping.source_key.as_ref(),
+        ];
+        if let Some(missing) = columns
+            .into_iter()
+            .flatten()
+            .find(|c| !first.contains_key(*c))
+        {
+            return Err(format!("Column not found: {}", missing));
+        }
+    }
+
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let mut topics_manifest = match &options.topic_id {
+        Some(topic_id) => {
+            let manifest = load_topics_manifest(&vault_path)?;
+            if !manifest.topics.iter().any(|t| &t.id == topic_id) {
+                return Err(format!("Topic {} not found", topic_id));
+            }
+            Some(manifest)
+        }
+        None => None,
+    };
+
+    let lookup = PlaceLookup::load(Some(&vault_path))?;
+    let key_prefix = format!("{}{}:", EVENT_KEY_PREFIX, dataset);
+    let mut seen: HashSet<String> = imported_source_keys(vault_root)?
+        .into_iter()
+        .filter_map(|key| key.strip_prefix(&key_prefix).map(|k| k.to_string()))
+        .collect();
+
+    let mut summary = EventImportSummary {
+        dry_run: options.dry_run,
+        created: Vec::new(),
+        skipped: Vec::new(),
+        failed: Vec::new(),
+    };
+    let mut written = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        let row_number = index + 1;
+        let field = |column: &Option<String>| {
+            column
+                .as_ref()
+                .and_then(|c| row.get(c))
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        let source_key = row_key(row, mapping);
+        if seen.contains(&source_key) {
+            summary.skipped.push(SkippedEvent {
+                row: row_number,
+                source_key,
+            });
+            continue;
+        }
+
+        let raw_date = row.get(&mapping.date).cloned().unwrap_or_default();
+        let date = match parse_date(&raw_date, options.date_format.as_deref()) {
+            Some(date) => date,
+            None => {
+                summary.failed.push(FailedEvent {
+                    row: row_number,
+                    error: format!("Invalid date: {}", raw_date),
+                });
+                continue;
+            }
+        };
+
+        let mut targets = Vec::new();
+        let mut unknown = Vec::new();
+        for reference in split_values(row.get(&mapping.country).map_or("", |c| c), &[';', '|']) {
+            match lookup.find(&reference) {
+                Some(slug) if !targets.iter().any(|t| t == slug) => targets.push(slug.to_string()),
+                Some(_) => {}
+                None => unknown.push(reference),
+            }
+        }
+
+        if !unknown.is_empty() || targets.is_empty() {
+            summary.failed.push(FailedEvent {
+                row: row_number,
+                error: if unknown.is_empty() {
+                    "No country".to_string()
+                } else {
+                    format!("Unknown country: {}", unknown.join(", "))
+                },
+            });
+            continue;
+        }
+
+        let mut tags = options.tags.clone();
+        for tag in split_values(&field(&mapping.tags), &[';', ',']) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let date_string = date.format("%Y-%m-%d").to_string();
+        let actor = field(&mapping.actor);
+        let description = field(&mapping.description);
+        let title = event_title(&field(&mapping.title), &actor, &description, &date_string);
+
+        let body = if actor.is_empty() {
+            description
+        } else {
+            format!("**Actor:** {}\n\n{}", actor, description)
+        };
+
+        let created = date
+            .and_hms_opt(0, 0, 0)
+            .map(|d| SystemTime::from(d.and_utc()))
+            .unwrap_or_else(SystemTime::now);
+
+        // One topic note targeting every country, or one note per country
+        let owners: Vec<NoteSource> = match &options.topic_id {
+            Some(topic_id) => vec![NoteSource::Topic(topic_id.clone())],
+            None => targets.iter().cloned().map(NoteSource::Country).collect(),
+        };
+
+        let mut note_ids = Vec::new();
+        let mut notes = Vec::new();
+        let mut error = None;
+
+        for owner in owners {
+            let country_targets = match &owner {
+                NoteSource::Country(slug) => vec![slug.clone()],
+                NoteSource::Topic(_) => targets.clone(),
+            };
+
+            let note = NewNote {
+                owner,
+                title: title.clone(),
+                date: date_string.clone(),
+                tags: tags.clone(),
+                country_targets,
+                body: body.clone(),
+                source_key: format!("{}{}", key_prefix, source_key),
+                created,
+            };
+
+            if !options.dry_run {
+                match write_imported_note(vault_root, &note) {
+                    Ok(id) => note_ids.push(id),
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
+                }
+            }
+            notes.push(note);
+        }
+
+        // A row is imported whole or not at all: remove the notes already
+        // written for its other countries
+        if let Some(mut error) = error {
+            for (note, id) in notes.iter().zip(&note_ids) {
+                if let Err(e) = remove_imported_note(vault_root, &note.owner, id) {
+                    error = format!("{} ({})", error, e);
+                }
+            }
+            summary.failed.push(FailedEvent {
+                row: row_number,
+                error,
+            });
+            continue;
+        }
+        written.extend(notes);
+
+        // Targeted countries join the topic, as with add_country_to_topic
+        if let (Some(manifest), Some(topic_id)) = (topics_manifest.as_mut(), &options.topic_id) {
+            for slug in &targets {
+                let exists = manifest
+                    .relations
+                    .iter()
+                    .any(|r| &r.topic_id == topic_id && &r.country_slug == slug);
+
+                if !exists {
+                    manifest.relations.push(TopicCountryRelation {
+                        topic_id: topic_id.clone(),
+                        country_slug: slug.clone(),
+                        note_count: 0,
+                        last_updated: None,
+                    });
+                }
+            }
+        }
+
+        seen.insert(source_key.clone());
+        summary.created.push(CreatedEvent {
+            row: row_number,
+            source_key,
+            targets,
+            note_ids,
+        });
+    }
+
+    if !options.dry_run {
+        record_notes_in_manifest(vault_root, &written)?;
+
+        if let Some(manifest) = &topics_manifest {
+            save_topics_manifest(&vault_path, manifest)?;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Bulk-import events from a CSV file or JSON array as country notes (or
+/// topic notes when `options.topic_id` is set). Rows whose source key was
+/// imported before are skipped.
+#[tauri::command]
+pub fn import_events(
+    source_path: String,
+    mapping: EventColumnMapping,
+    options: Option<EventImportOptions>,
+    state: State<AppState>,
+) -> Result<EventImportSummary, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let source_path = PathBuf::from(source_path);
+    let rows = read_rows(&source_path)?;
+
+    let vault_root = PathBuf::from(vault_path);
+    let options = options.unwrap_or_default();
+    let dataset = match options.dataset.as_deref().map(str::trim) {
+        Some(dataset) if !dataset.is_empty() => dataset.to_string(),
+        _ => source_path
+            .canonicalize()
+            .unwrap_or(source_path)
+            .to_string_lossy()
+            .replace('\\', "/"),
+    };
+    let summary = import_rows(&vault_root, &dataset, rows, &mapping, &options)?;
+
+    let note_ids: Vec<&String> = summary
+        .created
+        .iter()
+        .flat_map(|event| &event.note_ids)
+        .collect();
+    if !note_ids.is_empty() {
+        let mut event = JournalEvent::new("import.events").entities(&note_ids);
+        if let Some(topic_id) = &options.topic_id {
+            event = event.topic(topic_id);
+        }
+        event.record(&vault_root);
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, TestVault};
+
+    #[test]
+    fn test_parse_date() {
+        let expected = chrono::NaiveDate::from_ymd_opt(2024, 3, 1);
+
+        assert_eq!(parse_date("2024-03-01", None), expected);
+        assert_eq!(parse_date("01/03/2024", None), expected);
+        assert_eq!(parse_date("2024-03-01T12:00:00Z", None), expected);
+        assert_eq!(parse_date("03/01/2024", Some("%m/%d/%Y")), expected);
+        assert_eq!(parse_date("soon", None), None);
+    }
+
+    #[test]
+    fn test_import_events_from_csv() {
+        let vault = TestVault::new();
+        let csv_path = write_file(
+            &vault.scratch("data").join("events.csv"),
+            "id,date,country,actor,description\n\
+             e1,2024-03-01,FIN,Coast guard,Patrol near the border\n\
+             e2,2024-03-02,SE;Denmark,Navy,Joint exercise\n\
+             e3,2024-03-03,Atlantis,Nobody,Unknown place\n\
+             e4,not a date,FI,Nobody,Bad date\n",
+        );
+
+        let mapping = EventColumnMapping {
+            date: "date".to_string(),
+            country: "country".to_string(),
+            title: None,
+            description: Some("description".to_string()),
+            actor: Some("actor".to_string()),
+            tags: None,
+            source_key: Some("id".to_string()),
+        };
+        let options = EventImportOptions {
+            tags: vec!["events".to_string()],
+            ..Default::default()
+        };
+
+        let summary = import_rows(
+            &vault,
+            "events",
+            read_rows(&csv_path).unwrap(),
+            &mapping,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(summary.created.len(), 2);
+        assert_eq!(summary.created[1].targets, vec!["se", "dk"]);
+        assert_eq!(summary.failed.len(), 2);
+        assert_eq!(summary.failed[0].row, 3);
+
+        let manifest = vault.manifest();
+        assert_eq!(manifest.countries["fi"].note_count, 1);
+        assert_eq!(manifest.countries["dk"].tags, vec!["events"]);
+
+        // Rows already imported are skipped on the next run
+        let again = import_rows(
+            &vault,
+            "events",
+            read_rows(&csv_path).unwrap(),
+            &mapping,
+            &options,
+        )
+        .unwrap();
+        assert!(again.created.is_empty());
+        assert_eq!(again.skipped.len(), 2);
+
+        // Mapped columns must exist
+        let bad_mapping = EventColumnMapping {
+            actor: Some("who".to_string()),
+            ..mapping
+        };
+        assert!(import_rows(
+            &vault,
+            "events",
+            read_rows(&csv_path).unwrap(),
+            &bad_mapping,
+            &options
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_failed_row_is_rolled_back() {
+        let vault = TestVault::new();
+        let csv_path = write_file(
+            &vault.scratch("data").join("exercises.csv"),
+            "id,date,country,actor,description\n\
+             e1,2024-04-01,FI;Estonia,Navy,Joint exercise\n",
+        );
+        let mapping = EventColumnMapping {
+            date: "date".to_string(),
+            country: "country".to_string(),
+            title: None,
+            description: Some("description".to_string()),
+            actor: Some("actor".to_string()),
+            tags: None,
+            source_key: Some("id".to_string()),
+        };
+        let options = EventImportOptions::default();
+        let import = |dataset: &str| {
+            import_rows(
+                &vault,
+                dataset,
+                read_rows(&csv_path).unwrap(),
+                &mapping,
+                &options,
+            )
+            .unwrap()
+        };
+        let finnish_notes = || fs::read_dir(vault.join("notes/fi")).map_or(0, |d| d.count());
+
+        // Estonia's folder can't be created, so Finland's note is removed too
+        let blocker = vault.write("notes/ee", "not a folder");
+        let summary = import("exercises");
+        assert!(summary.created.is_empty());
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(finnish_notes(), 0);
+        assert!(!vault.manifest().countries.contains_key("fi"));
+
+        fs::remove_file(blocker).unwrap();
+        assert_eq!(import("exercises").created.len(), 1);
+        assert_eq!(finnish_notes(), 1);
+        assert_eq!(import("exercises").skipped.len(), 1);
+
+        // The same row ID in another dataset is a different event
+        assert_eq!(import("patrols").created.len(), 1);
+        assert_eq!(finnish_notes(), 2);
+    }
+}
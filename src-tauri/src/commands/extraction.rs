@@ -0,0 +1,270 @@
+use mapanote_lib::models::Attachment;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Hidden folder (inside a note's attachments folder) caching extracted text
+const EXTRACTED_DIR: &str = ".text";
+
+/// Text pulled out of an attachment for search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedText {
+    pub name: String,
+    pub size: u64, // Size and mtime (ns) of the attachment when extracted, to detect replacements
+    #[serde(default)]
+    pub modified: u64,
+    pub paged: bool, // PDFs keep one entry per page; plain text is a single entry
+    pub pages: Vec<String>,
+    #[serde(default)]
+    pub error: Option<String>, // Extraction failed; not retried until the file changes
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentMatch {
+    pub name: String,
+    pub page: Option<usize>, // 1-based, for PDFs
+    pub snippet: String,
+}
+
+fn extension(name: &str) -> String {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Whether text can be extracted locally from this attachment
+pub fn is_extractable(name: &str) -> bool {
+    matches!(
+        extension(name).as_str(),
+        "pdf" | "txt" | "md" | "csv" | "json" | "geojson" | "html" | "htm" | "xml"
+    )
+}
+
+/// Collapse runs of whitespace so phrases broken across lines still match
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn extract_pages(path: &Path) -> Result<(bool, Vec<String>), String> {
+    if extension(&path.to_string_lossy()) == "pdf" {
+        let path = path.to_path_buf();
+
+        // pdf-extract can panic on malformed documents
+        let pages = std::panic::catch_unwind(move || pdf_extract::extract_text_by_pages(&path))
+            .map_err(|_| "Failed to extract PDF text".to_string())?
+            .map_err(|e| format!("Failed to extract PDF text: {}", e))?;
+
+        Ok((true, pages.iter().map(|p| normalize(p)).collect()))
+    } else {
+        let bytes = fs::read(path).map_err(|e| format!("Failed to read attachment: {}", e))?;
+        Ok((false, vec![normalize(&String::from_utf8_lossy(&bytes))]))
+    }
+}
+
+/// Size and mtime (in nanoseconds) of an attachment
+fn file_stamp(path: &Path) -> Result<(u64, u64), String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("Failed to read attachment: {}", e))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+
+    Ok((metadata.len(), modified))
+}
+
+fn cache_path(attachments_dir: &Path, name: &str) -> PathBuf {
+    attachments_dir
+        .join(EXTRACTED_DIR)
+        .join(format!("{}.json", name))
+}
+
+/// Extract an attachment's text and cache it next to the attachment. A
+/// failure is cached too, so searches don't keep re-reading a document
+/// that can't be extracted.
+pub fn extract_attachment_text(
+    attachments_dir: &Path,
+    attachment: &Attachment,
+) -> Result<Option<ExtractedText>, String> {
+    if !is_extractable(&attachment.name) {
+        return Ok(None);
+    }
+
+    let path = attachments_dir.join(&attachment.name);
+    let (size, modified) = file_stamp(&path)?;
+
+    let (paged, pages, error) = match extract_pages(&path) {
+        Ok((paged, pages)) => (paged, pages, None),
+        Err(e) => (false, Vec::new(), Some(e)),
+    };
+    let extracted = ExtractedText {
+        name: attachment.name.clone(),
+        size,
+        modified,
+        paged,
+        pages,
+        error,
+    };
+
+    let cache = cache_path(attachments_dir, &attachment.name);
+    if let Some(parent) = cache.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create text cache directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string(&extracted)
+        .map_err(|e| format!("Failed to serialize extracted text: {}", e))?;
+    fs::write(&cache, json).map_err(|e| format!("Failed to write extracted text: {}", e))?;
+
+    match &extracted.error {
+        Some(error) => Err(error.clone()),
+        None => Ok(Some(extracted)),
+    }
+}
+
+/// Cached text for an attachment, extracting it first if the cache is
+/// missing or stale (the file's size or mtime changed)
+pub fn load_extracted_text(
+    attachments_dir: &Path,
+    attachment: &Attachment,
+) -> Option<ExtractedText> {
+    let (size, modified) = file_stamp(&attachments_dir.join(&attachment.name)).ok()?;
+
+    let cached = fs::read_to_string(cache_path(attachments_dir, &attachment.name))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ExtractedText>(&content).ok())
+        .filter(|text| text.size == size && text.modified == modified);
+
+    match cached {
+        Some(text) => text.error.is_none().then_some(text),
+        None => extract_attachment_text(attachments_dir, attachment)
+            .ok()
+            .flatten(),
+    }
+}
+
+/// Remove an attachment's cached text
+pub fn remove_extracted_text(attachments_dir: &Path, name: &str) {
+    let _ = fs::remove_file(cache_path(attachments_dir, name));
+}
+
+/// Up to `radius` characters either side of a byte offset, on char boundaries
+fn snippet_around(text: &str, offset: usize, radius: usize) -> String {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let center = chars.iter().position(|(i, _)| *i >= offset).unwrap_or(0);
+
+    let start = center.saturating_sub(radius);
+    let end = (center + radius).min(chars.len());
+
+    let mut snippet: String = chars[start..end].iter().map(|(_, c)| c).collect();
+    if start > 0 {
+        snippet = format!("...{}", snippet);
+    }
+    if end < chars.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+/// First place the (lowercase) query occurs in an attachment's text
+pub fn find_in_text(text: &ExtractedText, query_lower: &str) -> Option<AttachmentMatch> {
+    text.pages.iter().enumerate().find_map(|(index, page)| {
+        let page_lower = page.to_lowercase();
+        let offset = page_lower.find(query_lower)?;
+
+        // Lowercasing can change byte lengths; fall back to the page start
+        let offset = if page_lower.len() == page.len() {
+            offset
+        } else {
+            0
+        };
+
+        Some(AttachmentMatch {
+            name: text.name.clone(),
+            page: text.paged.then_some(index + 1),
+            snippet: snippet_around(page, offset, 75),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in_text_reports_page() {
+        let text = ExtractedText {
+            name: "treaty.pdf".to_string(),
+            size: 0,
+            modified: 0,
+            paged: true,
+            pages: vec![
+                "Preamble".to_string(),
+                "Article 5 An armed attack against one shall be considered an attack against all"
+                    .to_string(),
+            ],
+            error: None,
+        };
+
+        let found = find_in_text(&text, "armed attack").unwrap();
+        assert_eq!(found.page, Some(2));
+        assert!(found.snippet.contains("Article 5 An armed attack"));
+        assert!(find_in_text(&text, "annex").is_none());
+    }
+
+    #[test]
+    fn test_plain_text_extraction() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("data.csv"), "country,gdp\nFinland,\n  300").unwrap();
+
+        let attachment = Attachment {
+            name: "data.csv".to_string(),
+            mime: "text/csv".to_string(),
+            size: 0,
+            added: "2025-10-07".to_string(),
+        };
+
+        let text = load_extracted_text(dir, &attachment).unwrap();
+        assert!(!text.paged);
+        assert_eq!(text.pages, vec!["country,gdp Finland, 300"]);
+        assert!(cache_path(dir, "data.csv").exists());
+
+        // Same size, new contents: the newer mtime invalidates the cache
+        fs::write(dir.join("data.csv"), "country,gdp\nSweden,\n  500").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(dir.join("data.csv"))
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(5))
+            .unwrap();
+        let text = load_extracted_text(dir, &attachment).unwrap();
+        assert_eq!(text.pages, vec!["country,gdp Sweden, 500"]);
+    }
+
+    #[test]
+    fn test_failed_extraction_is_cached() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::write(dir.join("broken.pdf"), b"not a pdf").unwrap();
+
+        let attachment = Attachment {
+            name: "broken.pdf".to_string(),
+            mime: "application/pdf".to_string(),
+            size: 0,
+            added: "2025-10-07".to_string(),
+        };
+
+        assert!(load_extracted_text(dir, &attachment).is_none());
+        let cached: ExtractedText =
+            serde_json::from_str(&fs::read_to_string(cache_path(dir, "broken.pdf")).unwrap())
+                .unwrap();
+        assert!(cached.error.is_some());
+        assert!(cached.pages.is_empty());
+        assert!(load_extracted_text(dir, &attachment).is_none());
+    }
+}
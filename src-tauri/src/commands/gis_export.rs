@@ -0,0 +1,377 @@
+use super::entities::PlaceResolver;
+use super::groups::CountryGroups;
+use super::notes::{collect_note_files, parse_note, NoteSource};
+use super::tags::tag_matches;
+use super::templates::{country_context, ExportTemplates};
+use mapanote_lib::models::Note;
+use mapanote_lib::AppState;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+const GEOJSON_FILE: &str = "country_stats.geojson";
+const CSV_FILE: &str = "country_stats.csv";
+
+/// How many of a country's most used tags are exported
+const TOP_TAGS: usize = 5;
+
+/// Restricts which notes are counted
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StatsFilter {
+    #[serde(default)]
+    pub from: Option<String>, // YYYY-MM-DD, inclusive
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>, // Also matches hierarchical children
+}
+
+impl StatsFilter {
+    fn matches(&self, date: &str, tags: &[String]) -> bool {
+        self.from.as_deref().is_none_or(|from| date >= from)
+            && self.to.as_deref().is_none_or(|to| date <= to)
+            && self
+                .tag
+                .as_deref()
+                .is_none_or(|query| tags.iter().any(|tag| tag_matches(tag, query)))
+    }
+}
+
+/// One row of the export
+#[derive(Debug, Clone, Serialize)]
+pub struct CountryStatsRow {
+    pub slug: String,
+    pub name: String,
+    pub iso2: String,
+    pub iso3: String,
+    pub region: String,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub country_notes: usize,
+    pub topic_notes: usize,
+    pub note_count: usize,
+    pub topic_count: usize, // Distinct topics with notes targeting the country
+    pub last_updated: Option<String>,
+    pub top_tags: String, // Most used first, separated by `;`
+}
+
+#[derive(Debug, Serialize)]
+pub struct GisExportReport {
+    pub geojson_path: String,
+    pub csv_path: String,
+    pub countries: usize,
+}
+
+#[derive(Default)]
+struct Tally {
+    country_notes: usize,
+    topic_notes: usize,
+    topics: HashSet<String>,
+    last_updated: Option<String>,
+    tags: HashMap<String, usize>,
+}
+
+impl Tally {
+    fn add(&mut self, date: &str, tags: &[String]) {
+        if self.last_updated.as_deref() < Some(date) {
+            self.last_updated = Some(date.to_string());
+        }
+        for tag in tags {
+            *self.tags.entry(tag.clone()).or_default() += 1;
+        }
+    }
+}
+
+/// Per-country totals (country notes plus topic notes targeting the
+/// country, as on the map) for the notes matching `filter`
+pub fn country_stats(
+    vault_root: &Path,
+    filter: &StatsFilter,
+) -> Result<Vec<CountryStatsRow>, String> {
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let groups = CountryGroups::load(Some(&vault_path));
+    let resolver = PlaceResolver::load(&vault_path)?;
+
+    let mut tallies: BTreeMap<String, Tally> = BTreeMap::new();
+
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+        let note = match parse_note(&content) {
+            Some(note) => note,
+            None => continue,
+        };
+
+        if !filter.matches(&note.date, &note.tags) {
+            continue;
+        }
+
+        match &file.source {
+            NoteSource::Country(slug) => {
+                let tally = tallies.entry(slug.clone()).or_default();
+                tally.country_notes += 1;
+                tally.add(&note.date, &note.tags);
+            }
+            NoteSource::Topic(topic_id) => {
+                for slug in groups.expand_targets(&note.country_targets) {
+                    let tally = tallies.entry(slug).or_default();
+                    tally.topic_notes += 1;
+                    tally.topics.insert(topic_id.clone());
+                    tally.add(&note.date, &note.tags);
+                }
+            }
+        }
+    }
+
+    let rows = tallies
+        .into_iter()
+        .filter_map(|(slug, tally)| {
+            let metadata = resolver.resolve(&slug)?;
+
+            let mut tags: Vec<(String, usize)> = tally.tags.into_iter().collect();
+            tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+            Some(CountryStatsRow {
+                slug,
+                name: metadata.name,
+                iso2: metadata.iso2,
+                iso3: metadata.iso3,
+                region: metadata.region,
+                lat: metadata.centroid.map(|c| c.lat),
+                lon: metadata.centroid.map(|c| c.lon),
+                country_notes: tally.country_notes,
+                topic_notes: tally.topic_notes,
+                note_count: tally.country_notes + tally.topic_notes,
+                topic_count: tally.topics.len(),
+                last_updated: tally.last_updated,
+                top_tags: tags
+                    .into_iter()
+                    .take(TOP_TAGS)
+                    .map(|(tag, _)| tag)
+                    .collect::<Vec<_>>()
+                    .join(";"),
+            })
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+/// Notes keyed by country slug
+type NotesByCountry = HashMap<String, Vec<Note>>;
+
+/// Country notes and topic notes targeting each country, for templates
+fn notes_by_country(
+    vault_root: &Path,
+    filter: &StatsFilter,
+) -> Result<(NotesByCountry, NotesByCountry), String> {
+    let groups = CountryGroups::load(Some(&vault_root.to_string_lossy()));
+    let mut country_notes: NotesByCountry = HashMap::new();
+    let mut topic_notes: NotesByCountry = HashMap::new();
+
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+        let note = match parse_note(&content) {
+            Some(note) if filter.matches(&note.date, &note.tags) => note,
+            _ => continue,
+        };
+
+        match file.source {
+            NoteSource::Country(slug) => country_notes.entry(slug).or_default().push(note),
+            NoteSource::Topic(_) => {
+                for slug in groups.expand_targets(&note.country_targets) {
+                    topic_notes.entry(slug).or_default().push(note.clone());
+                }
+            }
+        }
+    }
+
+    for notes in country_notes.values_mut().chain(topic_notes.values_mut()) {
+        notes.sort_by(|a, b| b.date.cmp(&a.date));
+    }
+
+    Ok((country_notes, topic_notes))
+}
+
+/// Render each row's country template into a `description` property, which
+/// GIS tools show in feature popups
+fn add_descriptions(
+    vault_root: &Path,
+    filter: &StatsFilter,
+    templates: &ExportTemplates,
+    rows: &[CountryStatsRow],
+    geojson: &mut serde_json::Value,
+) -> Result<(), String> {
+    let resolver = PlaceResolver::load(&vault_root.to_string_lossy())?;
+    let (country_notes, topic_notes) = notes_by_country(vault_root, filter)?;
+    let features = match geojson["features"].as_array_mut() {
+        Some(features) => features,
+        None => return Ok(()),
+    };
+
+    for (row, feature) in rows.iter().zip(features.iter_mut()) {
+        let metadata = match resolver.resolve(&row.slug) {
+            Some(metadata) => metadata,
+            None => continue,
+        };
+        let notes: Vec<&Note> = country_notes.get(&row.slug).into_iter().flatten().collect();
+        let targeting: Vec<&Note> = topic_notes.get(&row.slug).into_iter().flatten().collect();
+
+        if let Some(description) = templates.render(
+            "country",
+            &country_context(&metadata, &notes, &targeting, Some(row)),
+        )? {
+            feature["properties"]["description"] = json!(description);
+        }
+    }
+
+    Ok(())
+}
+
+/// GeoJSON FeatureCollection with a centroid point per country. Places
+/// without a known centroid get a null geometry so their stats aren't lost.
+pub fn to_geojson(rows: &[CountryStatsRow]) -> serde_json::Value {
+    let features: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            let geometry = match (row.lon, row.lat) {
+                (Some(lon), Some(lat)) => json!({ "type": "Point", "coordinates": [lon, lat] }),
+                _ => serde_json::Value::Null,
+            };
+
+            json!({
+                "type": "Feature",
+                "id": row.slug,
+                "geometry": geometry,
+                "properties": {
+                    "slug": row.slug,
+                    "name": row.name,
+                    "iso2": row.iso2,
+                    "iso3": row.iso3,
+                    "region": row.region,
+                    "country_notes": row.country_notes,
+                    "topic_notes": row.topic_notes,
+                    "note_count": row.note_count,
+                    "topic_count": row.topic_count,
+                    "last_updated": row.last_updated,
+                    "top_tags": row.top_tags,
+                },
+            })
+        })
+        .collect();
+
+    json!({ "type": "FeatureCollection", "features": features })
+}
+
+/// Write per-country statistics as `country_stats.geojson` and
+/// `country_stats.csv` into `destination`, for GIS tools. With a `template`,
+/// features get a `description` rendered by its country template.
+#[tauri::command]
+pub fn export_country_stats(
+    destination: String,
+    filter: Option<StatsFilter>,
+    template: Option<String>,
+    state: State<AppState>,
+) -> Result<GisExportReport, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    let vault_root = PathBuf::from(vault_path);
+    let filter = filter.unwrap_or_default();
+    let templates =
+        ExportTemplates::load_selected(&vault_root, template.as_deref(), &["html", "md"])?;
+    let rows = country_stats(&vault_root, &filter)?;
+
+    let mut geojson = to_geojson(&rows);
+    if let Some(templates) = &templates {
+        add_descriptions(&vault_root, &filter, templates, &rows, &mut geojson)?;
+    }
+
+    let destination = PathBuf::from(destination);
+    fs::create_dir_all(&destination)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let geojson_path = destination.join(GEOJSON_FILE);
+    let geojson = serde_json::to_string_pretty(&geojson)
+        .map_err(|e| format!("Failed to serialize GeoJSON: {}", e))?;
+    fs::write(&geojson_path, geojson).map_err(|e| format!("Failed to write GeoJSON: {}", e))?;
+
+    let csv_path = destination.join(CSV_FILE);
+    let mut writer =
+        csv::Writer::from_path(&csv_path).map_err(|e| format!("Failed to create CSV: {}", e))?;
+    for row in &rows {
+        writer
+            .serialize(row)
+            .map_err(|e| format!("Failed to write CSV row: {}", e))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+
+    Ok(GisExportReport {
+        geojson_path: geojson_path.to_string_lossy().to_string(),
+        csv_path: csv_path.to_string_lossy().to_string(),
+        countries: rows.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_country_stats_with_filters() {
+        let vault = TestVault::new();
+        let finland = vault.join("notes").join("fi");
+        let topic = vault.join("topics").join("T1");
+        fs::create_dir_all(&finland).unwrap();
+        fs::create_dir_all(&topic).unwrap();
+
+        fs::write(
+            finland.join("01A.md"),
+            "---\nid: 01A\ntitle: A\ndate: 2025-01-10\ntags: [energy/nuclear, politics]\n---\n\nA",
+        )
+        .unwrap();
+        fs::write(
+            finland.join("01B.md"),
+            "---\nid: 01B\ntitle: B\ndate: 2024-06-01\ntags: [politics]\n---\n\nB",
+        )
+        .unwrap();
+        fs::write(
+            topic.join("01C.md"),
+            "---\nid: 01C\ntitle: C\ndate: 2025-02-01\ntags: [energy]\ntopic_id: T1\ncountry_targets: [fi, se]\n---\n\nC",
+        )
+        .unwrap();
+
+        let all = country_stats(&vault, &StatsFilter::default()).unwrap();
+        let fi = all.iter().find(|r| r.slug == "fi").unwrap();
+        assert_eq!(
+            (fi.country_notes, fi.topic_notes, fi.topic_count),
+            (2, 1, 1)
+        );
+        assert_eq!(fi.iso3, "FIN");
+        assert_eq!(fi.last_updated.as_deref(), Some("2025-02-01"));
+        assert_eq!(fi.top_tags, "politics;energy;energy/nuclear");
+
+        let filtered = country_stats(
+            &vault,
+            &StatsFilter {
+                from: Some("2025-01-01".to_string()),
+                to: None,
+                tag: Some("energy".to_string()),
+            },
+        )
+        .unwrap();
+        let fi = filtered.iter().find(|r| r.slug == "fi").unwrap();
+        assert_eq!(fi.note_count, 2);
+
+        let geojson = to_geojson(&filtered);
+        assert_eq!(geojson["features"].as_array().unwrap().len(), 2);
+        assert_eq!(geojson["features"][0]["geometry"]["type"], "Point");
+    }
+}
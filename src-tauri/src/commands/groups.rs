@@ -0,0 +1,294 @@
+use super::entities::slugify;
+use super::journal::JournalEvent;
+use mapanote_lib::models::{CountryGroup, GroupsManifest};
+use mapanote_lib::AppState;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::State;
+
+/// Prefix marking a group reference in `country_targets` and search queries
+pub const GROUP_PREFIX: &str = "group:";
+
+// Embed default groups at compile time
+static GROUPS_DATA: &str = include_str!("../data/groups.json");
+
+fn get_default_groups() -> &'static Vec<CountryGroup> {
+    static GROUPS: OnceLock<Vec<CountryGroup>> = OnceLock::new();
+    GROUPS.get_or_init(|| {
+        serde_json::from_str(GROUPS_DATA).expect("Failed to parse embedded groups.json")
+    })
+}
+
+fn get_groups_path(vault_path: &str) -> PathBuf {
+    PathBuf::from(vault_path)
+        .join(".mapanote")
+        .join("groups.json")
+}
+
+/// Load the vault's own groups (empty if the file doesn't exist)
+pub fn load_groups_manifest(vault_path: &str) -> Result<GroupsManifest, String> {
+    let groups_path = get_groups_path(vault_path);
+
+    if !groups_path.exists() {
+        return Ok(GroupsManifest::new());
+    }
+
+    let content = fs::read_to_string(&groups_path)
+        .map_err(|e| format!("Failed to read groups.json: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse groups.json: {}", e))
+}
+
+fn save_groups_manifest(vault_path: &str, manifest: &GroupsManifest) -> Result<(), String> {
+    let groups_path = get_groups_path(vault_path);
+
+    if let Some(parent) = groups_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize groups: {}", e))?;
+    fs::write(&groups_path, json).map_err(|e| format!("Failed to write groups.json: {}", e))?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct CountryGroupWithSource {
+    #[serde(flatten)]
+    pub group: CountryGroup,
+    pub builtin: bool, // Shipped default (possibly overridden by the vault)
+}
+
+/// Default groups merged with the vault's groups (vault wins on slug clash)
+pub struct CountryGroups {
+    groups: Vec<CountryGroup>,
+}
+
+impl CountryGroups {
+    pub fn load(vault_path: Option<&str>) -> Self {
+        let mut groups = get_default_groups().clone();
+
+        if let Some(vault_path) = vault_path {
+            let vault_groups = load_groups_manifest(vault_path)
+                .map(|m| m.groups)
+                .unwrap_or_default();
+
+            for group in vault_groups {
+                match groups.iter_mut().find(|g| g.slug == group.slug) {
+                    Some(existing) => *existing = group,
+                    None => groups.push(group),
+                }
+            }
+        }
+
+        Self { groups }
+    }
+
+    pub fn get(&self, slug: &str) -> Option<&CountryGroup> {
+        let slug = slug.strip_prefix(GROUP_PREFIX).unwrap_or(slug);
+        self.groups.iter().find(|g| g.slug == slug)
+    }
+
+    pub fn all(&self) -> &[CountryGroup] {
+        &self.groups
+    }
+
+    /// Replace `group:<slug>` references with the group's members,
+    /// keeping order and dropping duplicates. Unknown groups are kept as-is.
+    pub fn expand_targets(&self, targets: &[String]) -> Vec<String> {
+        let mut expanded: Vec<String> = Vec::new();
+
+        for target in targets {
+            let members = match target.strip_prefix(GROUP_PREFIX) {
+                Some(slug) => match self.get(slug) {
+                    Some(group) => group.members.clone(),
+                    None => vec![target.clone()],
+                },
+                None => vec![target.clone()],
+            };
+
+            for member in members {
+                if !expanded.contains(&member) {
+                    expanded.push(member);
+                }
+            }
+        }
+
+        expanded
+    }
+
+    /// Display name for a target, resolving group references
+    pub fn target_name(&self, target: &str) -> Option<String> {
+        target
+            .strip_prefix(GROUP_PREFIX)
+            .and_then(|slug| self.get(slug))
+            .map(|g| g.name.clone())
+    }
+}
+
+/// Split `group:` filters out of a search query
+/// ("group:eu energy" -> (["eu"], "energy"))
+pub fn split_group_filters(query: &str) -> (Vec<String>, String) {
+    let mut groups = Vec::new();
+    let mut words = Vec::new();
+
+    for word in query.split_whitespace() {
+        match word.strip_prefix(GROUP_PREFIX) {
+            Some(slug) if !slug.is_empty() => groups.push(slug.to_lowercase()),
+            _ => words.push(word),
+        }
+    }
+
+    (groups, words.join(" "))
+}
+
+/// Get all country groups (defaults and vault-defined)
+#[tauri::command]
+pub fn get_country_groups(state: State<AppState>) -> Result<Vec<CountryGroupWithSource>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let groups = CountryGroups::load(reader.as_deref());
+
+    let defaults = get_default_groups();
+
+    Ok(groups
+        .all()
+        .iter()
+        .map(|group| CountryGroupWithSource {
+            builtin: defaults.iter().any(|d| d.slug == group.slug),
+            group: group.clone(),
+        })
+        .collect())
+}
+
+/// Lowercase member slugs without blanks or repeats, keeping the order they
+/// were given in
+fn normalize_members(members: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    members
+        .into_iter()
+        .map(|m| m.trim().to_lowercase())
+        .filter(|m| !m.is_empty() && seen.insert(m.clone()))
+        .collect()
+}
+
+/// Create or update a vault group. Saving a group with a default's slug
+/// overrides the default for this vault.
+#[tauri::command]
+pub fn save_country_group(
+    slug: Option<String>,
+    name: String,
+    description: Option<String>,
+    members: Vec<String>,
+    state: State<AppState>,
+) -> Result<CountryGroup, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let slug = slugify(slug.as_deref().unwrap_or(&name));
+    if slug.is_empty() {
+        return Err("Group slug cannot be empty".to_string());
+    }
+
+    if members.iter().any(|m| m.starts_with(GROUP_PREFIX)) {
+        return Err("Groups cannot contain other groups".to_string());
+    }
+
+    let members = normalize_members(members);
+
+    let group = CountryGroup {
+        slug,
+        name: name.trim().to_string(),
+        description,
+        members,
+    };
+
+    let mut manifest = load_groups_manifest(vault_path)?;
+
+    match manifest.groups.iter_mut().find(|g| g.slug == group.slug) {
+        Some(existing) => *existing = group.clone(),
+        None => manifest.groups.push(group.clone()),
+    }
+
+    save_groups_manifest(vault_path, &manifest)?;
+
+    JournalEvent::new("group.saved")
+        .entity(&group.slug)
+        .entities(&group.members)
+        .record(Path::new(vault_path));
+
+    Ok(group)
+}
+
+/// Delete a vault group (restores the default if it overrode one)
+#[tauri::command]
+pub fn delete_country_group(slug: String, state: State<AppState>) -> Result<(), String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let mut manifest = load_groups_manifest(vault_path)?;
+
+    if !manifest.groups.iter().any(|g| g.slug == slug) {
+        return Err(format!("Group {} is not defined in this vault", slug));
+    }
+
+    manifest.groups.retain(|g| g.slug != slug);
+    save_groups_manifest(vault_path, &manifest)?;
+
+    JournalEvent::new("group.deleted")
+        .entity(&slug)
+        .record(Path::new(vault_path));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::metadata::find_country;
+
+    #[test]
+    fn test_expand_targets() {
+        let groups = CountryGroups::load(None);
+
+        let targets = vec!["fi".to_string(), "group:nordic".to_string()];
+        let expanded = groups.expand_targets(&targets);
+
+        assert_eq!(expanded, vec!["fi", "dk", "is", "se"]);
+        assert_eq!(
+            groups.expand_targets(&["group:unknown".to_string()]),
+            vec!["group:unknown"]
+        );
+    }
+
+    #[test]
+    fn test_default_members_are_known_countries() {
+        for group in get_default_groups() {
+            for member in &group.members {
+                assert!(
+                    find_country(member).is_some(),
+                    "{} lists unknown country {}",
+                    group.slug,
+                    member
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_group_filters() {
+        let (groups, query) = split_group_filters("group:EU energy  policy");
+        assert_eq!(groups, vec!["eu"]);
+        assert_eq!(query, "energy policy");
+    }
+
+    #[test]
+    fn test_normalize_members_drops_repeats() {
+        let members = ["fi", " SE", "fi", "", "se"].map(String::from).to_vec();
+        assert_eq!(normalize_members(members), vec!["fi", "se"]);
+    }
+}
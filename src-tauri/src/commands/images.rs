@@ -0,0 +1,520 @@
+use super::config::config_section;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use mapanote_lib::models::ImageSettings;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+/// Folder (inside an `assets/` folder) holding generated thumbnails
+pub const THUMBNAILS_DIR: &str = "thumbs";
+
+/// Image formats accepted into the vault, detected from file contents
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    WebP,
+    Bmp,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Gif => "gif",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+/// Detect the real image format from magic bytes (file names lie)
+pub fn sniff_image_format(bytes: &[u8]) -> Option<ImageFormat> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(ImageFormat::Png)
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(ImageFormat::Jpeg)
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some(ImageFormat::Gif)
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some(ImageFormat::WebP)
+    } else if bytes.starts_with(b"BM") && bytes.len() >= 26 {
+        Some(ImageFormat::Bmp)
+    } else {
+        // SVG is text: look for the root element near the start
+        let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_lowercase();
+        let head = head.trim_start_matches('\u{feff}').trim_start();
+        if (head.starts_with("<svg") || head.starts_with("<?xml")) && head.contains("<svg") {
+            Some(ImageFormat::Svg)
+        } else {
+            None
+        }
+    }
+}
+
+/// Drop EXIF/XMP (APP1), IPTC (APP13), comments and other application
+/// segments from a JPEG without re-encoding it. Keeps JFIF (APP0),
+/// ICC profiles (APP2) and Adobe colour info (APP14).
+fn strip_jpeg_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut output = vec![0xFF, 0xD8];
+    let mut pos = 2;
+
+    loop {
+        if pos + 4 > bytes.len() || bytes[pos] != 0xFF {
+            return None;
+        }
+
+        let marker = bytes[pos + 1];
+
+        // Start of scan: the compressed data follows, copy everything
+        if marker == 0xDA {
+            output.extend_from_slice(&bytes[pos..]);
+            return Some(output);
+        }
+
+        let len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let end = pos + 2 + len;
+        if len < 2 || end > bytes.len() {
+            return None;
+        }
+
+        let keep = !matches!(marker, 0xE1 | 0xE3..=0xED | 0xEF | 0xFE);
+        if keep {
+            output.extend_from_slice(&bytes[pos..end]);
+        }
+
+        pos = end;
+    }
+}
+
+/// Drop EXIF and text chunks (which may hold GPS or camera data) from a PNG
+fn strip_png_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut output = bytes[..8].to_vec();
+    let mut pos = 8;
+
+    while pos < bytes.len() {
+        if pos + 12 > bytes.len() {
+            return None;
+        }
+
+        let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().ok()?) as usize;
+        let end = pos + 12 + len;
+        if end > bytes.len() {
+            return None;
+        }
+
+        let chunk_type = &bytes[pos + 4..pos + 8];
+        if !matches!(chunk_type, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            output.extend_from_slice(&bytes[pos..end]);
+        }
+
+        pos = end;
+    }
+
+    Some(output)
+}
+
+/// Drop EXIF and XMP chunks from a WebP container
+fn strip_webp_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut chunks = Vec::new();
+    let mut pos = 12;
+
+    while pos < bytes.len() {
+        if pos + 8 > bytes.len() {
+            return None;
+        }
+
+        let fourcc = &bytes[pos..pos + 4];
+        let len = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let end = (pos + 8 + len + (len & 1)).min(bytes.len());
+        if pos + 8 + len > bytes.len() {
+            return None;
+        }
+
+        match fourcc {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" if len >= 1 => {
+                // Clear the EXIF and XMP presence flags
+                let mut chunk = bytes[pos..end].to_vec();
+                chunk[8] &= !(0x08 | 0x04);
+                chunks.extend_from_slice(&chunk);
+            }
+            _ => chunks.extend_from_slice(&bytes[pos..end]),
+        }
+
+        pos = end;
+    }
+
+    let mut output = b"RIFF".to_vec();
+    output.extend_from_slice(&((chunks.len() + 4) as u32).to_le_bytes());
+    output.extend_from_slice(b"WEBP");
+    output.extend_from_slice(&chunks);
+    Some(output)
+}
+
+/// Skip a run of GIF data sub-blocks starting at `pos`, returning the
+/// position after the terminating empty block
+fn skip_gif_sub_blocks(bytes: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *bytes.get(pos)? as usize;
+        pos += 1 + len;
+        if len == 0 {
+            return (pos <= bytes.len()).then_some(pos);
+        }
+    }
+}
+
+/// Drop comment and application extensions (XMP and other embedded data)
+/// from a GIF, keeping the NETSCAPE/ANIMEXTS looping block. Returns the
+/// cleaned bytes and the number of frames.
+fn strip_gif_metadata(bytes: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let color_table_len = |packed: u8| {
+        if packed & 0x80 != 0 {
+            3 << ((packed & 0x07) + 1)
+        } else {
+            0
+        }
+    };
+
+    // Header and logical screen descriptor, then the global colour table
+    let mut pos = 13 + color_table_len(*bytes.get(10)?);
+    let mut output = bytes.get(..pos)?.to_vec();
+    let mut frames = 0;
+
+    loop {
+        match *bytes.get(pos)? {
+            0x3B => {
+                output.push(0x3B);
+                return Some((output, frames));
+            }
+            0x2C => {
+                // Image descriptor, local colour table, LZW code size, data
+                let start = pos;
+                pos += 10 + color_table_len(*bytes.get(pos + 9)?) + 1;
+                pos = skip_gif_sub_blocks(bytes, pos)?;
+                output.extend_from_slice(&bytes[start..pos]);
+                frames += 1;
+            }
+            0x21 => {
+                let start = pos;
+                let label = *bytes.get(pos + 1)?;
+                let keep = match label {
+                    0xFE => false,
+                    0xFF => matches!(bytes.get(pos + 3..pos + 11)?, b"NETSCAPE" | b"ANIMEXTS"),
+                    _ => true,
+                };
+                pos = skip_gif_sub_blocks(bytes, pos + 2)?;
+                if keep {
+                    output.extend_from_slice(&bytes[start..pos]);
+                }
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buffer = Vec::new();
+
+    let result = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, quality)),
+        ImageFormat::WebP => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_with_encoder(WebPEncoder::new_lossless(&mut buffer)),
+        _ => image.write_with_encoder(PngEncoder::new(&mut buffer)),
+    };
+
+    result.map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(buffer)
+}
+
+/// An image ready to be written to the vault
+pub struct ProcessedImage {
+    pub bytes: Vec<u8>,
+    pub format: ImageFormat,
+    pub thumbnail: Option<(Vec<u8>, ImageFormat)>,
+}
+
+/// Validate and clean an incoming image: strip metadata, apply the EXIF
+/// orientation, downscale anything larger than the configured size and
+/// render a thumbnail. Single-frame GIFs and BMPs become PNGs; animated
+/// GIFs keep their frames and only lose embedded metadata. SVGs are
+/// stored unchanged.
+pub fn process_image(bytes: &[u8], settings: &ImageSettings) -> Result<ProcessedImage, String> {
+    let format = sniff_image_format(bytes).ok_or("Unsupported image format")?;
+
+    if format == ImageFormat::Svg {
+        return Ok(ProcessedImage {
+            bytes: bytes.to_vec(),
+            format,
+            thumbnail: None,
+        });
+    }
+
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    image.apply_orientation(orientation);
+
+    let max = settings.max_dimension;
+    let too_large = max > 0 && (image.width() > max || image.height() > max);
+
+    // Re-encoding would drop the animation, so animated GIFs are only stripped
+    let animation = match format {
+        ImageFormat::Gif => {
+            let (stripped, frames) = strip_gif_metadata(bytes).ok_or("Failed to read GIF")?;
+            (frames > 1).then_some(stripped)
+        }
+        _ => None,
+    };
+
+    let (bytes, format) = match (format, animation) {
+        (_, Some(stripped)) => (stripped, format),
+        (ImageFormat::Gif | ImageFormat::Bmp, None) => {
+            if too_large {
+                image = image.resize(max, max, image::imageops::FilterType::Lanczos3);
+            }
+            (
+                encode(&image, ImageFormat::Png, settings.jpeg_quality)?,
+                ImageFormat::Png,
+            )
+        }
+        _ if too_large || orientation != Orientation::NoTransforms => {
+            if too_large {
+                image = image.resize(max, max, image::imageops::FilterType::Lanczos3);
+            }
+            (encode(&image, format, settings.jpeg_quality)?, format)
+        }
+        // Already fine: only remove metadata, keeping the original encoding
+        _ => {
+            let stripped = match format {
+                ImageFormat::Jpeg => strip_jpeg_metadata(bytes),
+                ImageFormat::Png => strip_png_metadata(bytes),
+                _ => strip_webp_metadata(bytes),
+            };
+
+            match stripped {
+                Some(stripped) => (stripped, format),
+                None => (encode(&image, format, settings.jpeg_quality)?, format),
+            }
+        }
+    };
+
+    let thumbnail = if settings.thumbnail_size > 0 {
+        let size = settings.thumbnail_size;
+        let thumb = if image.width() > size || image.height() > size {
+            image.thumbnail(size, size)
+        } else {
+            image.clone()
+        };
+
+        let thumb_format = if thumb.color().has_alpha() {
+            ImageFormat::Png
+        } else {
+            ImageFormat::Jpeg
+        };
+
+        Some((
+            encode(&thumb, thumb_format, settings.jpeg_quality)?,
+            thumb_format,
+        ))
+    } else {
+        None
+    };
+
+    Ok(ProcessedImage {
+        bytes,
+        format,
+        thumbnail,
+    })
+}
+
+/// Read image settings from `.mapanote/config.json` (defaults if absent)
+pub fn load_image_settings(vault_path: &str) -> ImageSettings {
+    config_section(Path::new(vault_path), "images")
+}
+
+/// Process an image and store it content-addressed in `assets_dir`,
+/// returning its file name. Storing the same image twice reuses the file.
+pub fn store_image(
+    assets_dir: &Path,
+    bytes: &[u8],
+    settings: &ImageSettings,
+) -> Result<String, String> {
+    let processed = process_image(bytes, settings)?;
+
+    let hash = Sha256::digest(&processed.bytes);
+    let name: String = hash[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    let filename = format!("{}.{}", name, processed.format.extension());
+
+    fs::create_dir_all(assets_dir)
+        .map_err(|e| format!("Failed to create assets directory: {}", e))?;
+
+    let image_path = assets_dir.join(&filename);
+    if !image_path.exists() {
+        fs::write(&image_path, &processed.bytes)
+            .map_err(|e| format!("Failed to write image file: {}", e))?;
+    }
+
+    if let Some((thumb_bytes, thumb_format)) = processed.thumbnail {
+        let thumbs_dir = assets_dir.join(THUMBNAILS_DIR);
+        fs::create_dir_all(&thumbs_dir)
+            .map_err(|e| format!("Failed to create thumbnails directory: {}", e))?;
+
+        let thumb_path = thumbs_dir.join(format!("{}.{}", name, thumb_format.extension()));
+        if !thumb_path.exists() {
+            fs::write(&thumb_path, thumb_bytes)
+                .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+        }
+    }
+
+    Ok(filename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::ImageRgb8(RgbImage::new(width, height));
+        encode(&image, ImageFormat::Png, 85).unwrap()
+    }
+
+    #[test]
+    fn test_sniff_image_format() {
+        assert_eq!(sniff_image_format(&png_bytes(2, 2)), Some(ImageFormat::Png));
+        assert_eq!(
+            sniff_image_format(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(
+            sniff_image_format(b"<?xml version=\"1.0\"?><svg></svg>"),
+            Some(ImageFormat::Svg)
+        );
+        assert_eq!(sniff_image_format(b"not an image"), None);
+    }
+
+    #[test]
+    fn test_strip_jpeg_metadata() {
+        let mut jpeg = vec![0xFF, 0xD8];
+        jpeg.extend_from_slice(&[0xFF, 0xE1, 0x00, 0x08]); // APP1 (EXIF)
+        jpeg.extend_from_slice(b"Exif");
+        jpeg.extend_from_slice(&[0x00, 0x00]);
+        jpeg.extend_from_slice(&[0xFF, 0xDB, 0x00, 0x03, 0x01]); // DQT (kept)
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xAB, 0xFF, 0xD9]);
+
+        let stripped = strip_jpeg_metadata(&jpeg).unwrap();
+        assert_eq!(
+            stripped,
+            vec![
+                0xFF, 0xD8, 0xFF, 0xDB, 0x00, 0x03, 0x01, 0xFF, 0xDA, 0x00, 0x02, 0xAB, 0xFF, 0xD9
+            ]
+        );
+    }
+
+    #[test]
+    fn test_process_image_strips_png_text_and_downscales() {
+        let png = png_bytes(40, 10);
+
+        // Insert a tEXt chunk after IHDR (8 byte signature + 25 byte IHDR)
+        let mut with_text = png[..33].to_vec();
+        with_text.extend_from_slice(&[0, 0, 0, 4]);
+        with_text.extend_from_slice(b"tEXtGPS!");
+        with_text.extend_from_slice(&[0, 0, 0, 0]);
+        with_text.extend_from_slice(&png[33..]);
+
+        let settings = ImageSettings {
+            max_dimension: 100,
+            ..Default::default()
+        };
+        let processed = process_image(&with_text, &settings).unwrap();
+        assert_eq!(processed.bytes, png);
+
+        let small = ImageSettings {
+            max_dimension: 20,
+            thumbnail_size: 4,
+            jpeg_quality: 85,
+        };
+        let processed = process_image(&png, &small).unwrap();
+        let image = image::load_from_memory(&processed.bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (20, 5));
+        assert_eq!(processed.thumbnail.unwrap().1, ImageFormat::Jpeg);
+    }
+
+    #[test]
+    fn test_process_image_cleans_gifs() {
+        use image::codecs::gif::{GifEncoder, Repeat};
+        use image::{Delay, Frame, RgbaImage};
+
+        // A comment and an XMP application block before the trailer
+        let with_metadata = |gif: Vec<u8>| {
+            let mut bytes = gif[..gif.len() - 1].to_vec();
+            bytes.extend_from_slice(&[0x21, 0xFE, 4]);
+            bytes.extend_from_slice(b"GPS!\0");
+            bytes.extend_from_slice(&[0x21, 0xFF, 11]);
+            bytes.extend_from_slice(b"XMP DataXMP");
+            bytes.extend_from_slice(&[3, b'x', b'm', b'p', 0, 0x3B]);
+            bytes
+        };
+
+        let mut still = Vec::new();
+        GifEncoder::new(&mut still)
+            .encode_frame(Frame::new(RgbaImage::new(40, 10)))
+            .unwrap();
+        let settings = ImageSettings {
+            max_dimension: 20,
+            ..Default::default()
+        };
+        let processed = process_image(&with_metadata(still), &settings).unwrap();
+        assert_eq!(processed.format, ImageFormat::Png);
+        let image = image::load_from_memory(&processed.bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (20, 5));
+
+        let mut animated = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut animated);
+            encoder.set_repeat(Repeat::Infinite).unwrap();
+            let delay = Delay::from_numer_denom_ms(100, 1);
+            encoder
+                .encode_frames([
+                    Frame::from_parts(RgbaImage::new(4, 4), 0, 0, delay),
+                    Frame::from_parts(RgbaImage::from_pixel(4, 4, [255; 4].into()), 0, 0, delay),
+                ])
+                .unwrap();
+        }
+        let processed = process_image(&with_metadata(animated.clone()), &settings).unwrap();
+        assert_eq!(processed.format, ImageFormat::Gif);
+        assert_eq!(processed.bytes, animated);
+        assert_eq!(strip_gif_metadata(&processed.bytes).unwrap().1, 2);
+    }
+
+    #[test]
+    fn test_store_image_dedupes() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let settings = ImageSettings::default();
+
+        let first = store_image(dir, &png_bytes(8, 8), &settings).unwrap();
+        let second = store_image(dir, &png_bytes(8, 8), &settings).unwrap();
+
+        assert_eq!(first, second);
+        assert!(first.ends_with(".png"));
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 2); // image + thumbs/
+    }
+}
@@ -0,0 +1,812 @@
+use super::entities::PlaceLookup;
+use super::images::{load_image_settings, store_image};
+use super::journal::JournalEvent;
+use super::notes::{collect_note_files, ulid_timestamp, NoteSource};
+use mapanote_lib::models::{CountryStats, VaultManifest};
+use mapanote_lib::AppState;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::State;
+use walkdir::WalkDir;
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// Frontmatter key recording where an imported note came from
+pub const IMPORTED_FROM_KEY: &str = "imported_from";
+
+/// Frontmatter keys checked for a country when none are given
+const DEFAULT_COUNTRY_KEYS: [&str; 2] = ["country", "countries"];
+
+const IMAGE_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+#[derive(Debug, Serialize)]
+pub struct ImportedFile {
+    pub source: String, // Path relative to the imported folder
+    pub slug: String,
+    pub matched_by: String, // e.g. "frontmatter:country", "tag:finland", "folder:Finland"
+    pub title: String,
+    pub note_id: Option<String>, // None in a dry run
+    pub images: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnmappedFile {
+    pub source: String,
+    pub title: String,
+    pub candidates: Vec<String>, // Folder names, tags and keys that were tried
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportFailure {
+    pub source: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarkdownImportReport {
+    pub dry_run: bool,
+    pub imported: Vec<ImportedFile>,
+    pub unmapped: Vec<UnmappedFile>,
+    pub already_imported: Vec<String>,
+    pub missing_images: Vec<String>,       // "<source>: <reference>"
+    pub failed_images: Vec<ImportFailure>, // Found but not stored; source is "<source>: <reference>"
+    pub failed: Vec<ImportFailure>,
+}
+
+/// A note ready to be written to `notes/<slug>/<id>.md` or `topics/<id>/<id>.md`
+pub struct NewNote {
+    pub owner: NoteSource,
+    pub title: String,
+    pub date: String,
+    pub tags: Vec<String>,
+    pub country_targets: Vec<String>,
+    pub body: String,
+    pub source_key: String,
+    pub created: SystemTime,
+}
+
+fn imported_note_dir(vault_root: &Path, owner: &NoteSource) -> PathBuf {
+    match owner {
+        NoteSource::Country(slug) => vault_root.join("notes").join(slug),
+        NoteSource::Topic(topic_id) => vault_root.join("topics").join(topic_id),
+    }
+}
+
+/// Write an imported note in Mapanote's layout and return its ID. The ID is
+/// generated from `created` so imported notes keep their original order.
+pub fn write_imported_note(vault_root: &Path, note: &NewNote) -> Result<String, String> {
+    let id = ulid::Ulid::from_datetime(note.created).to_string();
+
+    let dir = imported_note_dir(vault_root, &note.owner);
+    let topic_id = match &note.owner {
+        NoteSource::Country(_) => "",
+        NoteSource::Topic(topic_id) => topic_id.as_str(),
+    };
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create notes directory: {}", e))?;
+
+    let created_at = ulid_timestamp(&id).unwrap_or_default();
+    let content = format!(
+        "---\nid: {}\ndate: {}\ntitle: {}\ntags: {:?}\ntopic_id: {}\ncountry_targets: [{}]\ncreated_at: {}\nupdated_at: {}\n{}: {}\n---\n\n{}",
+        id,
+        note.date,
+        note.title,
+        note.tags,
+        topic_id,
+        note.country_targets.join(", "),
+        created_at,
+        created_at,
+        IMPORTED_FROM_KEY,
+        note.source_key,
+        note.body
+    );
+
+    fs::write(dir.join(format!("{}.md", id)), content)
+        .map_err(|e| format!("Failed to write note: {}", e))?;
+
+    Ok(id)
+}
+
+/// Delete a note written by `write_imported_note`, e.g. when the rest of its
+/// import failed
+pub fn remove_imported_note(vault_root: &Path, owner: &NoteSource, id: &str) -> Result<(), String> {
+    fs::remove_file(imported_note_dir(vault_root, owner).join(format!("{}.md", id)))
+        .map_err(|e| format!("Failed to remove note {}: {}", id, e))
+}
+
+/// Add newly written country notes to the per-country stats in `vault.json`
+pub fn record_notes_in_manifest(vault_root: &Path, notes: &[NewNote]) -> Result<(), String> {
+    add_to_manifest(
+        vault_root,
+        notes.iter().filter_map(|note| match &note.owner {
+            NoteSource::Country(slug) => Some((slug.as_str(), note.date.as_str(), &note.tags[..])),
+            NoteSource::Topic(_) => None,
+        }),
+    )
+}
+
+/// Count `(country slug, date, tags)` notes in the per-country stats of `vault.json`
+pub fn add_to_manifest<'a>(
+    vault_root: &Path,
+    notes: impl IntoIterator<Item = (&'a str, &'a str, &'a [String])>,
+) -> Result<(), String> {
+    let mut notes = notes.into_iter().peekable();
+    if notes.peek().is_none() {
+        return Ok(());
+    }
+
+    let manifest_path = vault_root.join("vault.json");
+    let manifest_str = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read manifest: {}", e))?;
+
+    let mut manifest: VaultManifest = serde_json::from_str(&manifest_str)
+        .map_err(|e| format!("Failed to parse manifest: {}", e))?;
+
+    for (slug, date, tags) in notes {
+        let stats = manifest
+            .countries
+            .entry(slug.to_string())
+            .or_insert(CountryStats {
+                note_count: 0,
+                last_updated: None,
+                tags: Vec::new(),
+            });
+
+        stats.note_count += 1;
+        if stats.last_updated.as_deref() < Some(date) {
+            stats.last_updated = Some(date.to_string());
+        }
+
+        for tag in tags {
+            if !stats.tags.contains(tag) {
+                stats.tags.push(tag.clone());
+            }
+        }
+        stats.tags.sort();
+    }
+
+    fs::write(
+        &manifest_path,
+        serde_json::to_string_pretty(&manifest)
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?,
+    )
+    .map_err(|e| format!("Failed to write manifest: {}", e))
+}
+
+/// `imported_from` values of every note already in the vault
+pub fn imported_source_keys(vault_root: &Path) -> Result<HashSet<String>, String> {
+    let prefix = format!("{}: ", IMPORTED_FROM_KEY);
+    let mut keys = HashSet::new();
+
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+
+        // Only look inside the frontmatter block
+        let frontmatter = content
+            .lines()
+            .skip(1)
+            .take_while(|line| *line != "---")
+            .find_map(|line| line.strip_prefix(&prefix));
+
+        if let Some(key) = frontmatter {
+            keys.insert(key.trim().to_string());
+        }
+    }
+
+    Ok(keys)
+}
+
+/// Split a Markdown file into its YAML frontmatter (if any) and body
+pub fn split_frontmatter(content: &str) -> (Option<Yaml>, &str) {
+    let content = content.trim_start_matches('\u{feff}');
+
+    let rest = match content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    {
+        Some(rest) => rest,
+        None => return (None, content),
+    };
+
+    let (yaml, body) = match rest.find("\n---") {
+        Some(end) => {
+            let body = rest[end + 4..].trim_start_matches('-');
+            (&rest[..end], body)
+        }
+        None => return (None, content),
+    };
+
+    let doc = YamlLoader::load_from_str(yaml)
+        .ok()
+        .and_then(|docs| docs.into_iter().next());
+
+    (doc, body.trim())
+}
+
+/// A frontmatter value as a list of strings (`key: a`, `key: [a, b]`, or a block list)
+pub fn yaml_strings(doc: Option<&Yaml>, key: &str) -> Vec<String> {
+    let value = match doc {
+        Some(doc) => &doc[key],
+        None => return Vec::new(),
+    };
+
+    let items: Vec<&Yaml> = match value {
+        Yaml::Array(items) => items.iter().collect(),
+        other => vec![other],
+    };
+
+    items
+        .into_iter()
+        .filter_map(|item| match item {
+            Yaml::String(s) => Some(s.clone()),
+            Yaml::Integer(i) => Some(i.to_string()),
+            _ => None,
+        })
+        .flat_map(|s| {
+            // Obsidian also accepts comma-separated strings
+            s.split(',')
+                .map(|part| part.trim().trim_start_matches('#').to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// A scalar frontmatter value
+pub fn yaml_string(doc: Option<&Yaml>, key: &str) -> Option<String> {
+    match &doc?[key] {
+        Yaml::String(s) => Some(s.trim().to_string()).filter(|s| !s.is_empty()),
+        Yaml::Integer(i) => Some(i.to_string()),
+        Yaml::Real(r) => Some(r.clone()),
+        _ => None,
+    }
+}
+
+/// `YYYY-MM-DD` prefix of a frontmatter date, if it has one
+fn yaml_date(doc: Option<&Yaml>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| yaml_string(doc, key))
+        .find_map(|value| {
+            let date = value.get(..10)?;
+            chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .ok()
+                .map(|_| date.to_string())
+        })
+}
+
+/// Resolve a note to a country: explicit mappings first, then the place
+/// lookup, trying frontmatter keys, tags, folders (deepest first) and the
+/// file name in that order. Returns `Err(candidates)` when nothing matches.
+fn map_to_country(
+    relative: &Path,
+    doc: Option<&Yaml>,
+    country_keys: &[String],
+    mappings: &HashMap<String, String>,
+    lookup: &PlaceLookup,
+) -> Result<(String, String), Vec<String>> {
+    let mut candidates: Vec<(String, String)> = Vec::new();
+
+    for key in country_keys {
+        for value in yaml_strings(doc, key) {
+            candidates.push((format!("frontmatter:{}", key), value));
+        }
+    }
+
+    for tag in yaml_strings(doc, "tags") {
+        // Nested tags ("country/finland") are tried whole, then segment by segment
+        candidates.push((format!("tag:{}", tag), tag.clone()));
+        if tag.contains('/') {
+            for segment in tag.rsplit('/') {
+                candidates.push((format!("tag:{}", tag), segment.to_string()));
+            }
+        }
+    }
+
+    if let Some(parent) = relative.parent() {
+        let folders: Vec<String> = parent
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        for folder in folders.into_iter().rev() {
+            candidates.push((format!("folder:{}", folder), folder));
+        }
+    }
+
+    if let Some(stem) = relative.file_stem() {
+        candidates.push(("file name".to_string(), stem.to_string_lossy().to_string()));
+    }
+
+    let mappings: HashMap<String, &String> = mappings
+        .iter()
+        .map(|(from, to)| (from.to_lowercase(), to))
+        .collect();
+
+    for (matched_by, value) in &candidates {
+        let slug = mappings
+            .get(&value.to_lowercase())
+            .map(|slug| slug.as_str())
+            .or_else(|| lookup.find(value));
+
+        if let Some(slug) = slug {
+            return Ok((slug.to_string(), matched_by.clone()));
+        }
+    }
+
+    Err(candidates.into_iter().map(|(_, value)| value).collect())
+}
+
+/// Find an embedded image: relative to the note first, then anywhere in the
+/// imported folder by file name (how Obsidian resolves `![[name.png]]`)
+fn find_image(
+    reference: &str,
+    note_dir: &Path,
+    files_by_name: &HashMap<String, PathBuf>,
+) -> Option<PathBuf> {
+    let decoded = percent_encoding::percent_decode_str(reference)
+        .decode_utf8_lossy()
+        .to_string();
+
+    let extension = Path::new(&decoded)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())?;
+    if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+
+    let relative = note_dir.join(&decoded);
+    if relative.is_file() {
+        return Some(relative);
+    }
+
+    let name = Path::new(&decoded)
+        .file_name()?
+        .to_string_lossy()
+        .to_lowercase();
+    files_by_name.get(&name).cloned()
+}
+
+fn wiki_embed_regex() -> Regex {
+    Regex::new(r"!\[\[([^\]|#]+)(?:#[^\]|]*)?(?:\|([^\]]*))?\]\]").unwrap()
+}
+
+fn markdown_image_regex() -> Regex {
+    Regex::new(r#"!\[([^\]]*)\]\((?:<([^>]+)>|([^)\s]+))(?:\s+"[^"]*")?\)"#).unwrap()
+}
+
+/// Rewrite image embeds (`![[x.png]]`, `![alt](path/x.png)`) with `store`,
+/// which returns the new asset file name. Remote and unresolved references
+/// are left as they are and passed to `missing`.
+fn rewrite_images(
+    body: &str,
+    mut store: impl FnMut(&str) -> Option<String>,
+    mut missing: impl FnMut(&str),
+) -> (String, usize) {
+    let mut count = 0;
+
+    let body = wiki_embed_regex()
+        .replace_all(body, |caps: &Captures| {
+            let target = caps[1].trim();
+
+            // `|300` is a display width, anything else is alt text
+            let alt = caps
+                .get(2)
+                .map(|m| m.as_str())
+                .filter(|alt| !alt.chars().all(|c| c.is_ascii_digit() || c == 'x'))
+                .unwrap_or_else(|| {
+                    Path::new(target)
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("")
+                });
+
+            match store(target) {
+                Some(name) => {
+                    count += 1;
+                    format!("![{}](assets/{})", alt, name)
+                }
+                None => {
+                    missing(target);
+                    caps[0].to_string()
+                }
+            }
+        })
+        .to_string();
+
+    let body = markdown_image_regex()
+        .replace_all(&body, |caps: &Captures| {
+            let target = caps.get(2).or(caps.get(3)).map_or("", |m| m.as_str());
+
+            if target.contains("://")
+                || target.starts_with("data:")
+                || target.starts_with("assets/")
+            {
+                return caps[0].to_string();
+            }
+
+            match store(target) {
+                Some(name) => {
+                    count += 1;
+                    format!("![{}](assets/{})", &caps[1], name)
+                }
+                None => {
+                    missing(target);
+                    caps[0].to_string()
+                }
+            }
+        })
+        .to_string();
+
+    (body, count)
+}
+
+/// `imported_from` value for a file: its path under the canonical imported
+/// folder, so two folders with the same layout don't collide
+fn markdown_source_key(source_root: &Path, relative: &str) -> String {
+    format!(
+        "{}/{}",
+        source_root
+            .to_string_lossy()
+            .replace('\\', "/")
+            .trim_end_matches('/'),
+        relative
+    )
+}
+
+/// Import every Markdown file under `source_dir` into the vault
+fn import_folder(
+    vault_root: &Path,
+    source_dir: &Path,
+    country_keys: &[String],
+    mappings: &HashMap<String, String>,
+    dry_run: bool,
+) -> Result<MarkdownImportReport, String> {
+    if !source_dir.is_dir() {
+        return Err(format!("Folder not found: {}", source_dir.display()));
+    }
+    let source_root = source_dir
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve folder: {}", e))?;
+
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let lookup = PlaceLookup::load(Some(&vault_path))?;
+    let image_settings = load_image_settings(&vault_path);
+    let already_imported = imported_source_keys(vault_root)?;
+
+    // Skip hidden folders such as `.obsidian` and `.trash`
+    let files: Vec<PathBuf> = WalkDir::new(source_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        })
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+
+    let files_by_name: HashMap<String, PathBuf> = files
+        .iter()
+        .filter_map(|path| {
+            Some((
+                path.file_name()?.to_string_lossy().to_lowercase(),
+                path.clone(),
+            ))
+        })
+        .collect();
+
+    let mut report = MarkdownImportReport {
+        dry_run,
+        imported: Vec::new(),
+        unmapped: Vec::new(),
+        already_imported: Vec::new(),
+        missing_images: Vec::new(),
+        failed_images: Vec::new(),
+        failed: Vec::new(),
+    };
+    let mut written = Vec::new();
+
+    for path in files
+        .iter()
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("md"))
+    {
+        let relative = path.strip_prefix(source_dir).unwrap_or(path);
+        let source = relative.to_string_lossy().replace('\\', "/");
+        let source_key = markdown_source_key(&source_root, &source);
+
+        if already_imported.contains(&source_key) {
+            report.already_imported.push(source);
+            continue;
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                report.failed.push(ImportFailure {
+                    source,
+                    error: format!("Failed to read file: {}", e),
+                });
+                continue;
+            }
+        };
+
+        let (doc, body) = split_frontmatter(&content);
+        let doc = doc.as_ref();
+
+        let title = yaml_string(doc, "title").unwrap_or_else(|| {
+            relative
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+
+        let (slug, matched_by) =
+            match map_to_country(relative, doc, country_keys, mappings, &lookup) {
+                Ok(mapped) => mapped,
+                Err(candidates) => {
+                    report.unmapped.push(UnmappedFile {
+                        source,
+                        title,
+                        candidates,
+                    });
+                    continue;
+                }
+            };
+
+        let modified = fs::metadata(path)
+            .and_then(|m| m.modified())
+            .unwrap_or_else(|_| SystemTime::now());
+        let date = yaml_date(doc, &["date", "created"]).unwrap_or_else(|| {
+            chrono::DateTime::<chrono::Utc>::from(modified)
+                .format("%Y-%m-%d")
+                .to_string()
+        });
+        let created = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| SystemTime::from(d.and_utc()))
+            .unwrap_or(modified);
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in yaml_strings(doc, "tags") {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let note_dir = path.parent().unwrap_or(source_dir);
+        let assets_dir = vault_root.join("notes").join(&slug).join("assets");
+        let mut missing = Vec::new();
+        let mut store_errors = Vec::new();
+
+        let (body, images) = rewrite_images(
+            body,
+            |reference| {
+                let image = find_image(reference, note_dir, &files_by_name)?;
+                if dry_run {
+                    return Some(reference.to_string());
+                }
+
+                let stored = fs::read(&image)
+                    .map_err(|e| format!("Failed to read image: {}", e))
+                    .and_then(|bytes| store_image(&assets_dir, &bytes, &image_settings));
+                match stored {
+                    Ok(name) => Some(name),
+                    Err(error) => {
+                        store_errors.push((reference.to_string(), error));
+                        None
+                    }
+                }
+            },
+            |reference| missing.push(reference.to_string()),
+        );
+
+        // Images that failed to store are reported as failures, not missing
+        report.missing_images.extend(
+            missing
+                .into_iter()
+                .filter(|reference| !store_errors.iter().any(|(r, _)| r == reference))
+                .map(|reference| format!("{}: {}", source, reference)),
+        );
+        report
+            .failed_images
+            .extend(
+                store_errors
+                    .into_iter()
+                    .map(|(reference, error)| ImportFailure {
+                        source: format!("{}: {}", source, reference),
+                        error,
+                    }),
+            );
+
+        let note = NewNote {
+            owner: NoteSource::Country(slug.clone()),
+            country_targets: vec![slug.clone()],
+            title: title.clone(),
+            date,
+            tags,
+            body,
+            source_key,
+            created,
+        };
+
+        let note_id = if dry_run {
+            None
+        } else {
+            match write_imported_note(vault_root, &note) {
+                Ok(id) => Some(id),
+                Err(error) => {
+                    report.failed.push(ImportFailure { source, error });
+                    continue;
+                }
+            }
+        };
+
+        report.imported.push(ImportedFile {
+            source,
+            slug,
+            matched_by,
+            title,
+            note_id,
+            images,
+        });
+        written.push(note);
+    }
+
+    if !dry_run {
+        record_notes_in_manifest(vault_root, &written)?;
+    }
+
+    Ok(report)
+}
+
+/// Import a folder of Markdown notes (e.g. an Obsidian vault). Notes are
+/// mapped to countries via `country_keys` frontmatter keys (default
+/// `country`/`countries`), tags, folder names and file names; `mappings`
+/// overrides the lookup for specific values ("Nordics/Finland" -> "fi").
+/// With `dry_run` nothing is written and the report lists what would happen.
+#[tauri::command]
+pub fn import_markdown_folder(
+    source_path: String,
+    dry_run: bool,
+    country_keys: Option<Vec<String>>,
+    mappings: Option<HashMap<String, String>>,
+    state: State<AppState>,
+) -> Result<MarkdownImportReport, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let country_keys = country_keys
+        .unwrap_or_else(|| DEFAULT_COUNTRY_KEYS.iter().map(|k| k.to_string()).collect());
+
+    let vault_root = PathBuf::from(vault_path);
+    let report = import_folder(
+        &vault_root,
+        &PathBuf::from(source_path),
+        &country_keys,
+        &mappings.unwrap_or_default(),
+        dry_run,
+    )?;
+
+    let note_ids: Vec<&str> = report
+        .imported
+        .iter()
+        .filter_map(|file| file.note_id.as_deref())
+        .collect();
+    if !note_ids.is_empty() {
+        JournalEvent::new("import.markdown")
+            .entities(&note_ids)
+            .record(&vault_root);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, TestVault};
+
+    #[test]
+    fn test_rewrite_images() {
+        let body = "![[map.png|300]]\n![Chart](img/chart%201.jpg \"c\")\n![Remote](https://x.org/a.png)\n![[gone.png]]";
+
+        let (rewritten, count) = rewrite_images(
+            body,
+            |reference| (reference != "gone.png").then(|| "ab12.png".to_string()),
+            |_| {},
+        );
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            rewritten,
+            "![map](assets/ab12.png)\n![Chart](assets/ab12.png)\n![Remote](https://x.org/a.png)\n![[gone.png]]"
+        );
+    }
+
+    #[test]
+    fn test_import_folder() {
+        let vault = TestVault::new();
+        let source = vault.scratch("obsidian");
+
+        write_file(&source.join(".obsidian/ignored.md"), "x");
+        write_file(
+            &source.join("Europe/Finland/Energy.md"),
+            "---\ndate: 2024-03-01\ntags:\n  - energy\n---\n\nNuclear power.",
+        );
+        write_file(
+            &source.join("Trip.md"),
+            "---\ncountry: SE\ntitle: Stockholm trip\n---\nNotes ![[broken.png]]",
+        );
+        write_file(&source.join("broken.png"), "not an image");
+        write_file(&source.join("Ideas.md"), "No country here");
+
+        let dry = import_folder(
+            &vault,
+            &source,
+            &["country".to_string()],
+            &HashMap::new(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(dry.imported.len(), 2);
+        assert_eq!(dry.unmapped.len(), 1);
+        assert_eq!(dry.unmapped[0].source, "Ideas.md");
+        assert!(!vault.join("notes").exists());
+
+        let report = import_folder(
+            &vault,
+            &source,
+            &["country".to_string()],
+            &HashMap::new(),
+            false,
+        )
+        .unwrap();
+        let finland = report
+            .imported
+            .iter()
+            .find(|f| f.source == "Europe/Finland/Energy.md")
+            .unwrap();
+        assert_eq!(finland.slug, "fi");
+        assert_eq!(finland.matched_by, "folder:Finland");
+
+        // An image that is found but can't be stored is a failure, not missing
+        assert!(report.missing_images.is_empty());
+        assert_eq!(report.failed_images.len(), 1);
+        assert_eq!(report.failed_images[0].source, "Trip.md: broken.png");
+
+        let manifest = vault.manifest();
+        assert_eq!(manifest.countries["fi"].note_count, 1);
+        assert_eq!(manifest.countries["fi"].tags, vec!["energy"]);
+        assert_eq!(manifest.countries["se"].note_count, 1);
+
+        // Importing again skips files that were already brought in
+        let again = import_folder(
+            &vault,
+            &source,
+            &["country".to_string()],
+            &HashMap::new(),
+            false,
+        )
+        .unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.already_imported.len(), 2);
+
+        // A different folder with the same layout is a different source
+        let other = vault.scratch("other");
+        write_file(&other.join("Trip.md"), "---\ncountry: SE\n---\nOther trip");
+        let other_report = import_folder(
+            &vault,
+            &other,
+            &["country".to_string()],
+            &HashMap::new(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(other_report.imported.len(), 1);
+        assert_eq!(vault.manifest().countries["se"].note_count, 2);
+    }
+}
@@ -0,0 +1,340 @@
+use super::config::config_section;
+use super::notes::timestamp_now;
+use mapanote_lib::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// One JSON event per line, appended by every command that changes the vault
+pub const JOURNAL_FILE: &str = ".mapanote/journal.jsonl";
+
+/// A change to the vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEvent {
+    pub timestamp: String, // RFC3339
+    pub operation: String, // "<entity>.<action>", e.g. "note.created"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entity_ids: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>, // `author` from `.mapanote/config.json`
+}
+
+impl JournalEvent {
+    pub fn new(operation: &str) -> Self {
+        JournalEvent {
+            timestamp: String::new(),
+            operation: operation.to_string(),
+            entity_ids: Vec::new(),
+            country: None,
+            topic_id: None,
+            author: None,
+        }
+    }
+
+    pub fn entity(mut self, id: &str) -> Self {
+        self.entity_ids.push(id.to_string());
+        self
+    }
+
+    pub fn entities<S: AsRef<str>>(mut self, ids: &[S]) -> Self {
+        self.entity_ids
+            .extend(ids.iter().map(|id| id.as_ref().to_string()));
+        self
+    }
+
+    pub fn country(mut self, slug: &str) -> Self {
+        self.country = Some(slug.to_string());
+        self
+    }
+
+    pub fn topic(mut self, topic_id: &str) -> Self {
+        self.topic_id = Some(topic_id.to_string());
+        self
+    }
+
+    /// The country or topic a note belongs to, by `owner_type`
+    pub fn owner(self, owner_type: &str, owner_id: &str) -> Self {
+        match owner_type {
+            "topic" => self.topic(owner_id),
+            _ => self.country(owner_id),
+        }
+    }
+
+    /// Append the event to the vault's journal. The change it describes has
+    /// already happened, so a journal that can't be written is only logged.
+    pub fn record(mut self, vault_root: &Path) {
+        self.timestamp = timestamp_now();
+        self.author = load_author(vault_root);
+
+        if let Err(e) = append_event(vault_root, &self) {
+            eprintln!("Failed to journal {}: {}", self.operation, e);
+        }
+    }
+}
+
+fn journal_path(vault_root: &Path) -> PathBuf {
+    vault_root.join(JOURNAL_FILE)
+}
+
+/// Who is editing, from the `author` setting of `.mapanote/config.json`
+fn load_author(vault_root: &Path) -> Option<String> {
+    config_section::<Option<String>>(vault_root, "author")
+        .filter(|author| !author.trim().is_empty())
+}
+
+fn append_event(vault_root: &Path, event: &JournalEvent) -> Result<(), String> {
+    let path = journal_path(vault_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+
+    let line =
+        serde_json::to_string(event).map_err(|e| format!("Failed to serialize event: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open journal: {}", e))?;
+
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write journal: {}", e))
+}
+
+/// Every event in the journal, oldest first. A line cut short by a crash
+/// mid-append is skipped rather than failing the whole read.
+pub fn load_journal(vault_root: &Path) -> Result<Vec<JournalEvent>, String> {
+    let content = match fs::read_to_string(journal_path(vault_root)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read journal: {}", e)),
+    };
+
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Which journal events to return; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct JournalFilter {
+    pub operation: Option<String>, // "note" matches every "note.*" operation
+    pub entity_id: Option<String>,
+    pub country: Option<String>,
+    pub topic_id: Option<String>,
+    pub author: Option<String>,
+    pub from: Option<String>, // Inclusive date or RFC3339 timestamp
+    pub to: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl JournalFilter {
+    fn matches(&self, event: &JournalEvent) -> bool {
+        let operation = self.operation.as_deref().is_none_or(|op| {
+            event.operation == op || event.operation.starts_with(&format!("{}.", op))
+        });
+        let entity = self
+            .entity_id
+            .as_ref()
+            .is_none_or(|id| event.entity_ids.contains(id));
+        let country = self
+            .country
+            .as_ref()
+            .is_none_or(|slug| event.country.as_ref() == Some(slug));
+        let topic = self
+            .topic_id
+            .as_ref()
+            .is_none_or(|id| event.topic_id.as_ref() == Some(id));
+        let author = self
+            .author
+            .as_ref()
+            .is_none_or(|author| event.author.as_ref() == Some(author));
+
+        // Compare only as much of the timestamp as the bound gives, so a
+        // plain date covers that whole day
+        let from = self
+            .from
+            .as_deref()
+            .is_none_or(|from| truncate(&event.timestamp, from.len()) >= from);
+        let to = self
+            .to
+            .as_deref()
+            .is_none_or(|to| truncate(&event.timestamp, to.len()) <= to);
+
+        operation && entity && country && topic && author && from && to
+    }
+}
+
+fn truncate(value: &str, len: usize) -> &str {
+    value.get(..len).unwrap_or(value)
+}
+
+/// Journal events matching `filter`, newest first
+pub fn query_journal(
+    vault_root: &Path,
+    filter: &JournalFilter,
+) -> Result<Vec<JournalEvent>, String> {
+    let mut events: Vec<JournalEvent> = load_journal(vault_root)?
+        .into_iter()
+        .filter(|event| filter.matches(event))
+        .collect();
+
+    events.reverse();
+    if let Some(limit) = filter.limit {
+        events.truncate(limit);
+    }
+
+    Ok(events)
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompactionReport {
+    pub removed: usize,
+    pub remaining: usize,
+}
+
+/// Collapse events older than `cutoff` (RFC3339) to the latest one per
+/// operation and entity, so the journal keeps each entity's last known change
+/// without its whole history. Newer events are kept as they are.
+pub fn compact_events(events: Vec<JournalEvent>, cutoff: &str) -> (Vec<JournalEvent>, usize) {
+    let total = events.len();
+    let mut seen = HashSet::new();
+    let mut kept: Vec<JournalEvent> = events
+        .into_iter()
+        .rev()
+        .filter(|event| {
+            event.timestamp.as_str() >= cutoff
+                || seen.insert((event.operation.clone(), event.entity_ids.clone()))
+        })
+        .collect();
+    kept.reverse();
+
+    let removed = total - kept.len();
+    (kept, removed)
+}
+
+/// Compact the journal file in place (written to a temporary file first)
+pub fn compact_journal_file(vault_root: &Path, cutoff: &str) -> Result<CompactionReport, String> {
+    let (events, removed) = compact_events(load_journal(vault_root)?, cutoff);
+
+    if removed > 0 {
+        let mut content = String::new();
+        for event in &events {
+            content.push_str(
+                &serde_json::to_string(event)
+                    .map_err(|e| format!("Failed to serialize event: {}", e))?,
+            );
+            content.push('\n');
+        }
+
+        let path = journal_path(vault_root);
+        let temp = path.with_extension("jsonl.tmp");
+        fs::write(&temp, content).map_err(|e| format!("Failed to write journal: {}", e))?;
+        fs::rename(&temp, &path).map_err(|e| format!("Failed to replace journal: {}", e))?;
+    }
+
+    Ok(CompactionReport {
+        removed,
+        remaining: events.len(),
+    })
+}
+
+/// Journal events, newest first, narrowed by `filter`
+#[tauri::command]
+pub fn get_journal(
+    filter: Option<JournalFilter>,
+    state: State<AppState>,
+) -> Result<Vec<JournalEvent>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    query_journal(Path::new(vault_path), &filter.unwrap_or_default())
+}
+
+/// Compact journal events older than `older_than_days`
+#[tauri::command]
+pub fn compact_journal(
+    older_than_days: u32,
+    state: State<AppState>,
+) -> Result<CompactionReport, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let cutoff = (chrono::Utc::now() - chrono::Duration::days(older_than_days as i64))
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+    compact_journal_file(Path::new(vault_path), &cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_records_filters_and_compacts() {
+        let vault = TestVault::new();
+        fs::create_dir_all(vault.join(".mapanote")).unwrap();
+        fs::write(
+            vault.join(".mapanote/config.json"),
+            r#"{"name": "Test", "author": "Kim"}"#,
+        )
+        .unwrap();
+
+        JournalEvent::new("note.created")
+            .entity("01A")
+            .country("fi")
+            .record(&vault);
+        JournalEvent::new("note.updated")
+            .entity("01A")
+            .country("fi")
+            .record(&vault);
+        JournalEvent::new("note.updated")
+            .entity("01A")
+            .country("fi")
+            .record(&vault);
+        JournalEvent::new("topic.created")
+            .entity("t1")
+            .record(&vault);
+
+        let notes = query_journal(
+            &vault,
+            &JournalFilter {
+                operation: Some("note".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(notes.len(), 3);
+        assert_eq!(notes[0].operation, "note.updated");
+        assert_eq!(notes[0].author.as_deref(), Some("Kim"));
+
+        let today = &notes[0].timestamp[..10];
+        let in_fi_today = JournalFilter {
+            country: Some("fi".to_string()),
+            from: Some(today.to_string()),
+            to: Some(today.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query_journal(&vault, &in_fi_today).unwrap().len(), 3);
+
+        // Everything is older than a cutoff in the future: one event per
+        // operation and entity survives
+        let report = compact_journal_file(&vault, "9999-01-01T00:00:00.000Z").unwrap();
+        assert_eq!(report.removed, 1);
+        assert_eq!(report.remaining, 3);
+
+        let ops: Vec<String> = load_journal(&vault)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.operation)
+            .collect();
+        assert_eq!(ops, vec!["note.created", "note.updated", "topic.created"]);
+    }
+}
@@ -1,26 +1,95 @@
+use super::entities::{all_places, PlaceResolver};
 use mapanote_lib::models::CountryMetadata;
+use mapanote_lib::AppState;
 use std::sync::OnceLock;
+use tauri::State;
 
 // Embed countries.json at compile time
 static COUNTRIES_DATA: &str = include_str!("../data/countries.json");
 
-fn get_countries_metadata() -> &'static Vec<CountryMetadata> {
+pub fn get_countries_metadata() -> &'static Vec<CountryMetadata> {
     static COUNTRIES: OnceLock<Vec<CountryMetadata>> = OnceLock::new();
     COUNTRIES.get_or_init(|| {
         serde_json::from_str(COUNTRIES_DATA).expect("Failed to parse embedded countries.json")
     })
 }
 
+/// Find an embedded country by slug
+pub fn find_country(slug: &str) -> Option<&'static CountryMetadata> {
+    get_countries_metadata().iter().find(|c| c.slug == slug)
+}
+
+/// All embedded countries, plus the open vault's custom entities
 #[tauri::command]
-pub fn get_all_countries_metadata() -> Result<Vec<CountryMetadata>, String> {
-    Ok(get_countries_metadata().clone())
+pub fn get_all_countries_metadata(state: State<AppState>) -> Result<Vec<CountryMetadata>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    all_places(reader.as_deref())
 }
 
 #[tauri::command]
-pub fn get_country_metadata(slug: String) -> Result<CountryMetadata, String> {
-    get_countries_metadata()
-        .iter()
-        .find(|c| c.slug == slug)
+pub fn get_country_metadata(
+    slug: String,
+    state: State<AppState>,
+) -> Result<CountryMetadata, String> {
+    if let Some(country) = find_country(&slug) {
+        return Ok(country.clone());
+    }
+
+    // Fall back to entities defined in the open vault
+    let reader = state.vault_reader.lock().unwrap();
+    let entity = match reader.as_deref() {
+        Some(vault_path) => PlaceResolver::load(vault_path)?.resolve(&slug),
+        None => None,
+    };
+
+    entity.ok_or_else(|| format!("Country {} not found", slug))
+}
+
+/// Find a country by ISO 3166-1 alpha-2, alpha-3 or numeric code
+pub fn find_country_by_code(code: &str) -> Option<&'static CountryMetadata> {
+    let code = code.trim();
+
+    get_countries_metadata().iter().find(|c| {
+        c.iso2.eq_ignore_ascii_case(code)
+            || c.iso3.eq_ignore_ascii_case(code)
+            || (!c.iso_numeric.is_empty()
+                && c.iso_numeric.trim_start_matches('0') == code.trim_start_matches('0'))
+    })
+}
+
+#[tauri::command]
+pub fn get_country_metadata_by_code(code: String) -> Result<CountryMetadata, String> {
+    find_country_by_code(&code)
         .cloned()
-        .ok_or_else(|| format!("Country {} not found", slug))
+        .ok_or_else(|| format!("Country with code {} not found", code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_metadata_has_codes() {
+        for country in get_countries_metadata() {
+            assert_eq!(country.iso3.len(), 3, "bad iso3 for {}", country.slug);
+            assert_eq!(
+                country.iso_numeric.len(),
+                3,
+                "bad numeric for {}",
+                country.slug
+            );
+            assert!(
+                country.centroid.is_some(),
+                "no centroid for {}",
+                country.slug
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_country_by_code() {
+        assert_eq!(find_country_by_code("FIN").unwrap().slug, "fi");
+        assert_eq!(find_country_by_code("fi").unwrap().slug, "fi");
+        assert_eq!(find_country_by_code("4").unwrap().slug, "af");
+    }
 }
@@ -1,7 +1,49 @@
+pub mod analytics;
+pub mod assets;
+pub mod attachments;
+pub mod briefing;
+pub mod config;
+pub mod coverage;
+pub mod data_export;
+pub mod detection;
+pub mod entities;
+pub mod event_import;
+pub mod extraction;
+pub mod gis_export;
+pub mod groups;
+pub mod images;
+pub mod import;
+pub mod journal;
 pub mod metadata;
+pub mod note_index;
+pub mod notes;
+pub mod reviews;
+pub mod saved_searches;
+pub mod search;
+pub mod static_site;
+pub mod tags;
+pub mod templates;
 pub mod topics;
 pub mod vault; // ← ADD THIS
 
+pub use analytics::*;
+pub use assets::*;
+pub use attachments::*;
+pub use briefing::*;
+pub use coverage::*;
+pub use data_export::*;
+pub use detection::*;
+pub use entities::*;
+pub use event_import::*;
+pub use gis_export::*;
+pub use groups::*;
+pub use import::*;
+pub use journal::*;
 pub use metadata::*;
+pub use reviews::*;
+pub use saved_searches::*;
+pub use static_site::*;
+pub use tags::*;
+pub use templates::*;
 pub use topics::*;
 pub use vault::*; // ← ADD THIS
@@ -0,0 +1,350 @@
+use super::notes::{collect_note_files, parse_note, NoteSource};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::SecondsFormat;
+use mapanote_lib::models::{FollowUp, Note};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Frontmatter of every note, keyed by file and checked against its mtime
+pub const NOTE_INDEX_FILE: &str = ".mapanote/index.json";
+
+const INDEX_VERSION: u32 = 2;
+
+/// Page size when a listing command is called without a limit
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// The listing fields of one note file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub path: String,        // Relative to the vault root
+    pub source_type: String, // "country" or "topic"
+    pub owner_id: String,    // Country slug or topic ID
+    pub modified: u64,       // mtime in nanoseconds
+    pub size: u64,
+    pub id: String,
+    pub title: String,
+    pub date: String,
+    pub tags: Vec<String>,
+    pub country_targets: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub review_by: Option<String>,
+    pub follow_ups: Vec<FollowUp>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    entries: Vec<IndexEntry>,
+}
+
+/// Index entries for every note in the vault. Only files whose mtime or size
+/// changed since the last call are read and parsed; the rest come from
+/// `.mapanote/index.json`, so a listing costs a directory walk, not a full scan.
+pub fn load_note_index(vault_root: &Path) -> Result<Vec<IndexEntry>, String> {
+    let index_path = vault_root.join(NOTE_INDEX_FILE);
+    let mut cached: HashMap<String, IndexEntry> = fs::read_to_string(&index_path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<IndexFile>(&json).ok())
+        .filter(|index| index.version == INDEX_VERSION)
+        .map(|index| {
+            index
+                .entries
+                .into_iter()
+                .map(|entry| (entry.path.clone(), entry))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut entries = Vec::new();
+    let mut changed = false;
+
+    for file in collect_note_files(vault_root)? {
+        let relative = file
+            .path
+            .strip_prefix(vault_root)
+            .unwrap_or(&file.path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let metadata =
+            fs::metadata(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+
+        if let Some(entry) = cached.remove(&relative) {
+            if entry.modified == modified && entry.size == metadata.len() {
+                entries.push(entry);
+                continue;
+            }
+        }
+
+        changed = true;
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+        let Some(note) = parse_note(&content) else {
+            continue;
+        };
+
+        let (source_type, owner_id) = match file.source {
+            NoteSource::Country(slug) => ("country", slug),
+            NoteSource::Topic(topic_id) => ("topic", topic_id),
+        };
+
+        entries.push(IndexEntry {
+            path: relative,
+            source_type: source_type.to_string(),
+            owner_id,
+            modified,
+            size: metadata.len(),
+            id: note.id,
+            title: note.title,
+            date: note.date,
+            tags: note.tags,
+            country_targets: note.country_targets,
+            created_at: note.created_at,
+            updated_at: note.updated_at,
+            review_by: note.review_by,
+            follow_ups: note.follow_ups,
+        });
+    }
+
+    // Deleted notes leave stale entries behind
+    if changed || !cached.is_empty() {
+        let index = IndexFile {
+            version: INDEX_VERSION,
+            entries,
+        };
+
+        // The index is only a cache: a read-only vault still lists its notes
+        if let Ok(json) = serde_json::to_string(&index) {
+            let _ = fs::create_dir_all(vault_root.join(".mapanote"));
+            let _ = fs::write(&index_path, json);
+        }
+
+        return Ok(index.entries);
+    }
+
+    Ok(entries)
+}
+
+/// Read the full note behind an index entry
+pub fn read_indexed_note(vault_root: &Path, entry: &IndexEntry) -> Result<Option<Note>, String> {
+    let content = fs::read_to_string(vault_root.join(&entry.path))
+        .map_err(|e| format!("Failed to read note: {}", e))?;
+
+    Ok(parse_note(&content))
+}
+
+/// Order of a paginated note listing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteSort {
+    #[default]
+    Date, // Newest note date first
+    Created, // Newest creation first
+    Updated, // Most recently edited first
+    Title,   // A to Z
+}
+
+impl NoteSort {
+    fn key(self, entry: &IndexEntry) -> String {
+        match self {
+            NoteSort::Date => entry.date.clone(),
+            NoteSort::Created => normalize_timestamp(&entry.created_at),
+            NoteSort::Updated => normalize_timestamp(&entry.updated_at),
+            NoteSort::Title => entry.title.to_lowercase(),
+        }
+    }
+
+    /// Compare `(key, id)` positions; IDs break ties so every position is unique
+    fn compare(self, a: (&str, &str), b: (&str, &str)) -> Ordering {
+        match self {
+            NoteSort::Title => a.cmp(&b),
+            _ => b.cmp(&a),
+        }
+    }
+}
+
+/// Timestamps in one offset and precision so they compare as strings
+fn normalize_timestamp(value: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|t| {
+            t.with_timezone(&chrono::Utc)
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+        })
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// Position after the last item of a page
+#[derive(Serialize, Deserialize)]
+struct Cursor {
+    sort: NoteSort,
+    key: String,
+    id: String,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(value: &str, sort: NoteSort) -> Result<Self, String> {
+        let cursor: Cursor = general_purpose::URL_SAFE_NO_PAD
+            .decode(value)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or("Invalid cursor")?;
+
+        if cursor.sort != sort {
+            return Err("Cursor belongs to a different sort order".to_string());
+        }
+
+        Ok(cursor)
+    }
+}
+
+/// One page of a listing; pass `next_cursor` back to get the following page
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+/// Sort index entries and load the page after `cursor`. `load` turns an
+/// entry into an item, or skips it by returning `None` (e.g. search misses),
+/// so only the notes on the page are ever read from disk.
+pub fn paginate<T>(
+    entries: Vec<IndexEntry>,
+    sort: NoteSort,
+    cursor: Option<&str>,
+    limit: Option<usize>,
+    mut load: impl FnMut(&IndexEntry) -> Result<Option<T>, String>,
+) -> Result<Page<T>, String> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let after = cursor.map(|c| Cursor::decode(c, sort)).transpose()?;
+
+    let mut keyed: Vec<(String, IndexEntry)> = entries
+        .into_iter()
+        .map(|entry| (sort.key(&entry), entry))
+        .filter(|(key, entry)| match &after {
+            Some(after) => {
+                sort.compare((key, &entry.id), (&after.key, &after.id)) == Ordering::Greater
+            }
+            None => true,
+        })
+        .collect();
+    keyed.sort_by(|(a_key, a), (b_key, b)| sort.compare((a_key, &a.id), (b_key, &b.id)));
+
+    let mut items = Vec::new();
+    let mut remaining = keyed.iter();
+    let mut last = None;
+
+    for (key, entry) in remaining.by_ref() {
+        if let Some(item) = load(entry)? {
+            items.push(item);
+            if items.len() == limit {
+                last = Some((key, entry));
+                break;
+            }
+        }
+    }
+
+    // A full page with entries left over; the next page may still come back
+    // empty when `load` filters out everything after it
+    let next_cursor = match last {
+        Some((key, entry)) if remaining.len() > 0 => Some(
+            Cursor {
+                sort,
+                key: key.clone(),
+                id: entry.id.clone(),
+            }
+            .encode(),
+        ),
+        _ => None,
+    };
+
+    Ok(Page { items, next_cursor })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+
+    fn write_note(dir: &Path, id: &str, date: &str, title: &str) {
+        fs::write(
+            dir.join(format!("{}.md", id)),
+            format!(
+                "---\nid: {}\ndate: {}\ntitle: {}\ntags: []\ntopic_id: \ncountry_targets: []\n---\n\nBody",
+                id, date, title
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_paginates_from_index() {
+        let vault = TestVault::new();
+        let finland = vault.join("notes").join("fi");
+        fs::create_dir_all(&finland).unwrap();
+        write_note(&finland, "01A", "2025-01-01", "Charlie");
+        write_note(&finland, "01B", "2025-03-01", "Alpha");
+        write_note(&finland, "01C", "2025-02-01", "Bravo");
+
+        let load = |entry: &IndexEntry| Ok(Some(entry.title.clone()));
+
+        let first = paginate(
+            load_note_index(&vault).unwrap(),
+            NoteSort::Date,
+            None,
+            Some(2),
+            load,
+        )
+        .unwrap();
+        assert_eq!(first.items, vec!["Alpha", "Bravo"]);
+        let cursor = first.next_cursor.unwrap();
+
+        let second = paginate(
+            load_note_index(&vault).unwrap(),
+            NoteSort::Date,
+            Some(&cursor),
+            Some(2),
+            load,
+        )
+        .unwrap();
+        assert_eq!(second.items, vec!["Charlie"]);
+        assert!(second.next_cursor.is_none());
+
+        // A cursor only fits the sort it came from
+        assert!(paginate(
+            load_note_index(&vault).unwrap(),
+            NoteSort::Title,
+            Some(&cursor),
+            None,
+            load
+        )
+        .is_err());
+
+        // Edited and deleted files are picked up on the next load
+        write_note(&finland, "01A", "2025-01-01", "Delta, renamed");
+        fs::remove_file(finland.join("01B.md")).unwrap();
+        let titles = paginate(
+            load_note_index(&vault).unwrap(),
+            NoteSort::Title,
+            None,
+            None,
+            load,
+        )
+        .unwrap();
+        assert_eq!(titles.items, vec!["Bravo", "Delta, renamed"]);
+    }
+}
@@ -0,0 +1,389 @@
+use chrono::SecondsFormat;
+use mapanote_lib::models::{Attachment, FollowUp, Note, Visibility};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where a note file lives in the vault
+#[derive(Debug, Clone, PartialEq)]
+pub enum NoteSource {
+    Country(String), // notes/<slug>/<id>.md
+    Topic(String),   // topics/<id>/<note_id>.md
+}
+
+/// A note file on disk together with its location
+#[derive(Debug, Clone)]
+pub struct NoteFile {
+    pub path: PathBuf,
+    pub source: NoteSource,
+}
+
+/// Collect every note file in the vault (country notes and topic notes)
+pub fn collect_note_files(vault_root: &Path) -> Result<Vec<NoteFile>, String> {
+    let mut files = Vec::new();
+
+    for (dir_name, is_topic) in [("notes", false), ("topics", true)] {
+        let base_dir = vault_root.join(dir_name);
+
+        if !base_dir.exists() {
+            continue;
+        }
+
+        for entry in fs::read_dir(&base_dir)
+            .map_err(|e| format!("Failed to read {} directory: {}", dir_name, e))?
+        {
+            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let dir_path = entry.path();
+
+            if !dir_path.is_dir() {
+                continue;
+            }
+
+            let owner = dir_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string();
+
+            for note_entry in fs::read_dir(&dir_path)
+                .map_err(|e| format!("Failed to read directory {}: {}", owner, e))?
+            {
+                let note_entry = note_entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+                let path = note_entry.path();
+
+                if path.extension().and_then(|s| s.to_str()) != Some("md") {
+                    continue;
+                }
+
+                let source = if is_topic {
+                    NoteSource::Topic(owner.clone())
+                } else {
+                    NoteSource::Country(owner.clone())
+                };
+
+                files.push(NoteFile { path, source });
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Parse a frontmatter list such as `[a, b]` or `["a", "b"]`
+pub fn parse_list(value: &str) -> Vec<String> {
+    value
+        .trim()
+        .trim_matches(|c| c == '[' || c == ']')
+        .split(',')
+        .map(|s| s.trim().trim_matches('"').trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Format a list for frontmatter as `[a, b]`
+pub fn format_list(items: &[String]) -> String {
+    format!("[{}]", items.join(", "))
+}
+
+/// Current time as written to `created_at` / `updated_at`
+pub fn timestamp_now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Creation time encoded in a ULID note ID
+pub fn ulid_timestamp(id: &str) -> Option<String> {
+    let ulid = ulid::Ulid::from_string(id).ok()?;
+    Some(
+        chrono::DateTime::<chrono::Utc>::from(ulid.datetime())
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
+    )
+}
+
+/// Mark a note's file content as edited now
+pub fn touch_note(content: &str) -> String {
+    set_frontmatter_field(content, "updated_at", &timestamp_now())
+}
+
+/// Parse a note file (frontmatter + markdown body)
+pub fn parse_note(content: &str) -> Option<Note> {
+    let mut lines = content.lines();
+
+    if lines.next()? != "---" {
+        return None;
+    }
+
+    let mut id = String::new();
+    let mut title = String::new();
+    let mut date = String::new();
+    let mut tags = Vec::new();
+    let mut topic_id = None;
+    let mut country_targets = Vec::new();
+    let mut attachments = Vec::new();
+    let mut visibility = Visibility::default();
+    let mut created_at = String::new();
+    let mut updated_at = String::new();
+    let mut review_by = None;
+    let mut follow_ups = Vec::new();
+    let mut reviewed = Vec::new();
+
+    for line in lines.by_ref() {
+        if line == "---" {
+            break;
+        }
+
+        if let Some(value) = line.strip_prefix("id: ") {
+            id = value.to_string();
+        } else if let Some(value) = line.strip_prefix("title: ") {
+            title = value.to_string();
+        } else if let Some(value) = line.strip_prefix("date: ") {
+            date = value.to_string();
+        } else if let Some(value) = line.strip_prefix("tags: ") {
+            tags = parse_list(value);
+        } else if let Some(value) = line.strip_prefix("topic_id: ") {
+            topic_id = Some(value.to_string());
+        } else if let Some(value) = line.strip_prefix("country_targets: ") {
+            country_targets = parse_list(value);
+        } else if let Some(value) = line.strip_prefix("attachments: ") {
+            // JSON flow sequence, which is also valid YAML
+            attachments = serde_json::from_str(value).unwrap_or_default();
+        } else if let Some(value) = line.strip_prefix("visibility: ") {
+            visibility = Visibility::parse(value).unwrap_or_default();
+        } else if let Some(value) = line.strip_prefix("created_at: ") {
+            created_at = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("updated_at: ") {
+            updated_at = value.trim().to_string();
+        } else if let Some(value) = line.strip_prefix("review_by: ") {
+            review_by = Some(value.trim().to_string()).filter(|d| !d.is_empty());
+        } else if let Some(value) = line.strip_prefix("follow_ups: ") {
+            // JSON flow sequence, like attachments
+            follow_ups = serde_json::from_str(value).unwrap_or_default();
+        } else if let Some(value) = line.strip_prefix("reviewed: ") {
+            reviewed = parse_list(value);
+        }
+    }
+
+    // Notes written before timestamps existed: the ULID carries the creation
+    // time (imported notes get IDs from their source dates), else the date
+    if created_at.is_empty() {
+        created_at = ulid_timestamp(&id)
+            .or_else(|| (!date.is_empty()).then(|| format!("{}T00:00:00.000Z", date)))
+            .unwrap_or_default();
+    }
+    if updated_at.is_empty() {
+        updated_at = created_at.clone();
+    }
+
+    let content: String = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+
+    Some(Note {
+        id,
+        title,
+        content,
+        date,
+        tags,
+        topic_id,
+        country_targets,
+        attachments,
+        visibility,
+        created_at,
+        updated_at,
+        review_by,
+        follow_ups,
+        reviewed,
+    })
+}
+
+/// Replace (or insert) a single `key: value` line in a note's frontmatter,
+/// leaving every other line and the body untouched
+pub fn set_frontmatter_field(content: &str, key: &str, value: &str) -> String {
+    let prefix = format!("{}:", key);
+    let new_line = if value.is_empty() {
+        format!("{}:", key)
+    } else {
+        format!("{}: {}", key, value)
+    };
+
+    let mut output = Vec::new();
+    let mut lines = content.lines();
+    let mut replaced = false;
+
+    match lines.next() {
+        Some("---") => output.push("---".to_string()),
+        _ => return content.to_string(),
+    }
+
+    for line in lines.by_ref() {
+        if line == "---" {
+            if !replaced {
+                output.push(new_line.clone());
+            }
+            output.push(line.to_string());
+            break;
+        }
+
+        if line.starts_with(&prefix) {
+            output.push(new_line.clone());
+            replaced = true;
+        } else {
+            output.push(line.to_string());
+        }
+    }
+
+    for line in lines {
+        output.push(line.to_string());
+    }
+
+    let mut result = output.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// Replace the markdown body of a note, keeping its frontmatter as-is
+pub fn set_note_body(content: &str, body: &str) -> String {
+    let mut lines = content.lines();
+
+    if lines.next() != Some("---") {
+        return body.to_string();
+    }
+
+    let mut output = vec!["---"];
+    for line in lines {
+        output.push(line);
+        if line == "---" {
+            break;
+        }
+    }
+
+    format!("{}\n\n{}", output.join("\n"), body)
+}
+
+/// Write a note's attachment list to its frontmatter
+pub fn format_attachments(attachments: &[Attachment]) -> String {
+    serde_json::to_string(attachments).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Write a note's follow-up checklist to its frontmatter
+pub fn format_follow_ups(follow_ups: &[FollowUp]) -> String {
+    serde_json::to_string(follow_ups).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Serialize a whole note (frontmatter + body) in the vault's file format
+pub fn format_note(note: &Note) -> String {
+    let mut frontmatter = format!(
+        "---\nid: {}\ndate: {}\ntitle: {}\ntags: {:?}\ntopic_id: {}\ncountry_targets: {}\n",
+        note.id,
+        note.date,
+        note.title,
+        note.tags,
+        note.topic_id.as_deref().unwrap_or(""),
+        format_list(&note.country_targets)
+    );
+
+    if !note.attachments.is_empty() {
+        frontmatter.push_str(&format!(
+            "attachments: {}\n",
+            format_attachments(&note.attachments)
+        ));
+    }
+    if note.visibility != Visibility::default() {
+        frontmatter.push_str(&format!("visibility: {}\n", note.visibility.as_str()));
+    }
+    if !note.created_at.is_empty() {
+        frontmatter.push_str(&format!("created_at: {}\n", note.created_at));
+    }
+    if !note.updated_at.is_empty() {
+        frontmatter.push_str(&format!("updated_at: {}\n", note.updated_at));
+    }
+    if let Some(review_by) = &note.review_by {
+        frontmatter.push_str(&format!("review_by: {}\n", review_by));
+    }
+    if !note.follow_ups.is_empty() {
+        frontmatter.push_str(&format!(
+            "follow_ups: {}\n",
+            format_follow_ups(&note.follow_ups)
+        ));
+    }
+    if !note.reviewed.is_empty() {
+        frontmatter.push_str(&format!("reviewed: {}\n", format_list(&note.reviewed)));
+    }
+
+    format!("{}---\n\n{}", frontmatter, note.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_note_quoted_tags() {
+        let content = "---\nid: 01J\ndate: 2025-10-07\ntitle: Test\ntags: [\"energy\", \"politics\"]\ntopic_id: \ncountry_targets: [fi]\n---\n\nBody";
+
+        let note = parse_note(content).unwrap();
+        assert_eq!(note.visibility, Visibility::Internal);
+        assert_eq!(note.tags, vec!["energy", "politics"]);
+        assert_eq!(note.country_targets, vec!["fi"]);
+        assert_eq!(note.content, "Body");
+    }
+
+    #[test]
+    fn test_set_frontmatter_field() {
+        let content = "---\nid: 01J\ntags: [a, b]\n---\n\nBody with tags: [a]\n";
+
+        let updated = set_frontmatter_field(content, "tags", "[c]");
+        assert_eq!(
+            updated,
+            "---\nid: 01J\ntags: [c]\n---\n\nBody with tags: [a]\n"
+        );
+
+        let inserted = set_frontmatter_field(content, "country_targets", "[fi]");
+        assert!(inserted.contains("tags: [a, b]\ncountry_targets: [fi]\n---"));
+    }
+
+    #[test]
+    fn test_attachments_round_trip() {
+        let attachments = vec![Attachment {
+            name: "treaty, final.pdf".to_string(),
+            mime: "application/pdf".to_string(),
+            size: 1024,
+            added: "2025-10-07".to_string(),
+        }];
+
+        let content = "---\nid: 01J\ntitle: Test\n---\n\nOld body";
+        let content =
+            set_frontmatter_field(content, "attachments", &format_attachments(&attachments));
+        let content = set_note_body(&content, "New body");
+
+        let mut note = parse_note(&content).unwrap();
+        assert_eq!(note.attachments, attachments);
+        assert_eq!(note.content, "New body");
+
+        note.visibility = Visibility::Publishable;
+        let reparsed = parse_note(&format_note(&note)).unwrap();
+        assert_eq!(reparsed.attachments, attachments);
+        assert_eq!(reparsed.visibility, Visibility::Publishable);
+        assert_eq!(reparsed.content, "New body");
+    }
+
+    #[test]
+    fn test_timestamps_fall_back_to_ulid() {
+        let id = ulid::Ulid::from_datetime(
+            chrono::DateTime::parse_from_rfc3339("2025-03-04T05:06:07.089Z")
+                .unwrap()
+                .into(),
+        )
+        .to_string();
+        let legacy = format!("---\nid: {}\ndate: 2025-03-04\ntitle: Old\n---\n\nBody", id);
+
+        let note = parse_note(&legacy).unwrap();
+        assert_eq!(note.created_at, "2025-03-04T05:06:07.089Z");
+        assert_eq!(note.updated_at, note.created_at);
+
+        let edited = parse_note(&touch_note(&legacy)).unwrap();
+        assert_eq!(edited.created_at, "2025-03-04T05:06:07.089Z");
+        assert!(edited.updated_at > edited.created_at);
+
+        let dated = parse_note("---\nid: legacy\ndate: 2024-01-02\n---\n\nBody").unwrap();
+        assert_eq!(dated.created_at, "2024-01-02T00:00:00.000Z");
+    }
+}
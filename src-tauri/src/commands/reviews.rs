@@ -0,0 +1,352 @@
+use super::assets::{owner_dir, validate_file_name};
+use super::entities::PlaceResolver;
+use super::journal::JournalEvent;
+use super::note_index::{load_note_index, IndexEntry};
+use super::notes::{
+    format_follow_ups, format_list, parse_note, set_frontmatter_field, timestamp_now, touch_note,
+};
+use super::topics::load_topics_manifest;
+use chrono::NaiveDate;
+use mapanote_lib::models::{FollowUp, Note};
+use mapanote_lib::AppState;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// How far ahead `get_due_reviews` looks when called without `within_days`
+pub const DEFAULT_REVIEW_WINDOW_DAYS: u32 = 14;
+
+/// A note whose `review_by` date has passed or is coming up
+#[derive(Debug, Serialize)]
+pub struct DueReview {
+    pub note_id: String,
+    pub title: String,
+    pub source_type: String, // "country" or "topic"
+    pub owner_id: String,    // Country slug or topic ID
+    pub owner_name: String,
+    pub review_by: String,
+    pub days_until: i64, // Negative once overdue
+    pub open_follow_ups: Vec<String>,
+}
+
+/// Overdue reviews (most overdue first) and those due within the window
+/// (soonest first)
+#[derive(Debug, Serialize)]
+pub struct DueReviews {
+    pub overdue: Vec<DueReview>,
+    pub upcoming: Vec<DueReview>,
+}
+
+/// Check a `review_by` value before it goes into a note
+fn validate_review_date(date: &str) -> Result<(), String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| format!("Invalid review date: {}", date))
+}
+
+/// Notes across countries and topics due for review by `today + within_days`
+pub fn due_reviews(
+    vault_root: &Path,
+    today: NaiveDate,
+    within_days: u32,
+) -> Result<DueReviews, String> {
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let resolver = PlaceResolver::load(&vault_path)?;
+    let topic_titles: HashMap<String, String> = load_topics_manifest(&vault_path)?
+        .topics
+        .into_iter()
+        .map(|topic| (topic.id, topic.title))
+        .collect();
+
+    let owner_name = |entry: &IndexEntry| match entry.source_type.as_str() {
+        "topic" => topic_titles
+            .get(&entry.owner_id)
+            .cloned()
+            .unwrap_or_else(|| entry.owner_id.clone()),
+        _ => resolver.name(&entry.owner_id),
+    };
+
+    let mut overdue = Vec::new();
+    let mut upcoming = Vec::new();
+
+    for entry in load_note_index(vault_root)? {
+        let Some(review_by) = &entry.review_by else {
+            continue;
+        };
+        let Ok(date) = NaiveDate::parse_from_str(review_by, "%Y-%m-%d") else {
+            continue;
+        };
+
+        let days_until = (date - today).num_days();
+        if days_until > within_days as i64 {
+            continue;
+        }
+
+        let review = DueReview {
+            note_id: entry.id.clone(),
+            title: entry.title.clone(),
+            source_type: entry.source_type.clone(),
+            owner_id: entry.owner_id.clone(),
+            owner_name: owner_name(&entry),
+            review_by: review_by.clone(),
+            days_until,
+            open_follow_ups: entry
+                .follow_ups
+                .iter()
+                .filter(|item| !item.done)
+                .map(|item| item.text.clone())
+                .collect(),
+        };
+
+        if days_until < 0 {
+            overdue.push(review);
+        } else {
+            upcoming.push(review);
+        }
+    }
+
+    for reviews in [&mut overdue, &mut upcoming] {
+        reviews.sort_by(|a, b| {
+            (a.days_until, &a.title, &a.note_id).cmp(&(b.days_until, &b.title, &b.note_id))
+        });
+    }
+
+    Ok(DueReviews { overdue, upcoming })
+}
+
+fn note_path(
+    vault_root: &Path,
+    owner_type: &str,
+    owner_id: &str,
+    note_id: &str,
+) -> Result<PathBuf, String> {
+    validate_file_name(note_id)?;
+    let path = owner_dir(vault_root, owner_type, owner_id)?.join(format!("{}.md", note_id));
+
+    if !path.exists() {
+        return Err(format!("Note {} not found", note_id));
+    }
+
+    Ok(path)
+}
+
+/// Set (or clear) a note's review date and follow-up checklist
+pub fn set_review(
+    vault_root: &Path,
+    owner_type: &str,
+    owner_id: &str,
+    note_id: &str,
+    review_by: Option<&str>,
+    follow_ups: &[FollowUp],
+) -> Result<Note, String> {
+    if let Some(date) = review_by {
+        validate_review_date(date)?;
+    }
+
+    let path = note_path(vault_root, owner_type, owner_id, note_id)?;
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
+
+    // Empty values leave a bare `key:` line, which parses as unset
+    let follow_ups = if follow_ups.is_empty() {
+        String::new()
+    } else {
+        format_follow_ups(follow_ups)
+    };
+    let content = set_frontmatter_field(&content, "review_by", review_by.unwrap_or(""));
+    let updated = touch_note(&set_frontmatter_field(&content, "follow_ups", &follow_ups));
+
+    fs::write(&path, &updated).map_err(|e| format!("Failed to write note: {}", e))?;
+
+    JournalEvent::new("note.review_scheduled")
+        .entity(note_id)
+        .owner(owner_type, owner_id)
+        .record(vault_root);
+
+    parse_note(&updated).ok_or_else(|| format!("Failed to parse note {}", note_id))
+}
+
+/// Record that a note was reviewed: append the time to its `reviewed` list,
+/// which is the note's review history (the journal may be compacted), move
+/// `review_by` to `next_review_by` (or clear it) and bump `updated_at`
+pub fn mark_note_reviewed(
+    vault_root: &Path,
+    owner_type: &str,
+    owner_id: &str,
+    note_id: &str,
+    next_review_by: Option<&str>,
+) -> Result<Note, String> {
+    if let Some(date) = next_review_by {
+        validate_review_date(date)?;
+    }
+
+    let path = note_path(vault_root, owner_type, owner_id, note_id)?;
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
+    let mut reviewed = parse_note(&content)
+        .ok_or_else(|| format!("Failed to parse note {}", note_id))?
+        .reviewed;
+    reviewed.push(timestamp_now());
+
+    let content = set_frontmatter_field(&content, "reviewed", &format_list(&reviewed));
+    let updated = touch_note(&set_frontmatter_field(
+        &content,
+        "review_by",
+        next_review_by.unwrap_or(""),
+    ));
+
+    fs::write(&path, &updated).map_err(|e| format!("Failed to write note: {}", e))?;
+
+    JournalEvent::new("note.reviewed")
+        .entity(note_id)
+        .owner(owner_type, owner_id)
+        .record(vault_root);
+
+    parse_note(&updated).ok_or_else(|| format!("Failed to parse note {}", note_id))
+}
+
+/// Notes that are overdue for review or due within `within_days`
+/// (default 14)
+#[tauri::command]
+pub fn get_due_reviews(
+    within_days: Option<u32>,
+    state: State<AppState>,
+) -> Result<DueReviews, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    due_reviews(
+        Path::new(vault_path),
+        chrono::Utc::now().date_naive(),
+        within_days.unwrap_or(DEFAULT_REVIEW_WINDOW_DAYS),
+    )
+}
+
+/// Set a country or topic note's review date and follow-up checklist
+#[tauri::command]
+pub fn set_note_review(
+    owner_type: String,
+    owner_id: String,
+    note_id: String,
+    review_by: Option<String>,
+    follow_ups: Vec<FollowUp>,
+    state: State<AppState>,
+) -> Result<Note, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    set_review(
+        Path::new(vault_path),
+        &owner_type,
+        &owner_id,
+        &note_id,
+        review_by.as_deref(),
+        &follow_ups,
+    )
+}
+
+/// Mark a note reviewed, scheduling the next review if `next_review_by` is set
+#[tauri::command]
+pub fn mark_reviewed(
+    owner_type: String,
+    owner_id: String,
+    note_id: String,
+    next_review_by: Option<String>,
+    state: State<AppState>,
+) -> Result<Note, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    mark_note_reviewed(
+        Path::new(vault_path),
+        &owner_type,
+        &owner_id,
+        &note_id,
+        next_review_by.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::journal::load_journal;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_due_reviews_and_mark_reviewed() {
+        let vault = TestVault::new();
+        let finland = vault.join("notes").join("fi");
+        fs::create_dir_all(&finland).unwrap();
+
+        for (id, title) in [("01A", "Election"), ("01B", "Budget"), ("01C", "Grid")] {
+            fs::write(
+                finland.join(format!("{}.md", id)),
+                format!(
+                    "---\nid: {}\ndate: 2025-01-01\ntitle: {}\ntags: []\ntopic_id: \ncountry_targets: [fi]\n---\n\nBody",
+                    id, title
+                ),
+            )
+            .unwrap();
+        }
+
+        let follow_ups = vec![
+            FollowUp {
+                text: "Check turnout".to_string(),
+                done: true,
+            },
+            FollowUp {
+                text: "Read coalition deal".to_string(),
+                done: false,
+            },
+        ];
+        set_review(
+            &vault,
+            "country",
+            "fi",
+            "01A",
+            Some("2025-05-20"),
+            &follow_ups,
+        )
+        .unwrap();
+        set_review(&vault, "country", "fi", "01B", Some("2025-06-10"), &[]).unwrap();
+        set_review(&vault, "country", "fi", "01C", Some("2025-09-01"), &[]).unwrap();
+        assert!(set_review(&vault, "country", "fi", "01C", Some("soon"), &[]).is_err());
+
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let due = due_reviews(&vault, today, 14).unwrap();
+        assert_eq!(due.overdue.len(), 1);
+        assert_eq!(due.overdue[0].note_id, "01A");
+        assert_eq!(due.overdue[0].days_until, -12);
+        assert_eq!(due.overdue[0].owner_name, "Finland");
+        assert_eq!(due.overdue[0].open_follow_ups, vec!["Read coalition deal"]);
+        let upcoming: Vec<&str> = due.upcoming.iter().map(|r| r.note_id.as_str()).collect();
+        assert_eq!(upcoming, vec!["01B"]);
+
+        // Reviewing clears the date unless a next one is given
+        let before = parse_note(&fs::read_to_string(finland.join("01A.md")).unwrap()).unwrap();
+        let reviewed = mark_note_reviewed(&vault, "country", "fi", "01A", None).unwrap();
+        assert!(reviewed.review_by.is_none());
+        assert!(reviewed.updated_at >= before.updated_at);
+        assert_eq!(reviewed.follow_ups, follow_ups);
+        assert_eq!(reviewed.reviewed.len(), 1);
+        mark_note_reviewed(&vault, "country", "fi", "01B", Some("2025-12-01")).unwrap();
+
+        // The history is kept in the note, so compacting the journal can't lose it
+        let again = mark_note_reviewed(&vault, "country", "fi", "01A", None).unwrap();
+        assert_eq!(again.reviewed.len(), 2);
+        assert_eq!(again.reviewed[0], reviewed.reviewed[0]);
+        let note = parse_note(&fs::read_to_string(finland.join("01A.md")).unwrap()).unwrap();
+        assert_eq!(note.reviewed, again.reviewed);
+
+        let due = due_reviews(&vault, today, 14).unwrap();
+        assert!(due.overdue.is_empty() && due.upcoming.is_empty());
+
+        let reviews: Vec<String> = load_journal(&vault)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.operation == "note.reviewed")
+            .flat_map(|e| e.entity_ids)
+            .collect();
+        assert_eq!(reviews, vec!["01A", "01B", "01A"]);
+    }
+}
@@ -0,0 +1,391 @@
+use super::config::{load_config, save_config_section};
+use super::extraction::AttachmentMatch;
+use super::groups::CountryGroups;
+use super::journal::JournalEvent;
+use super::notes::{collect_note_files, parse_note, NoteSource};
+use super::search::NoteMatcher;
+use super::tags::tag_matches;
+use mapanote_lib::AppState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Saved searches are kept under this key of `.mapanote/config.json`
+const CONFIG_KEY: &str = "saved_searches";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchSort {
+    #[default]
+    Newest,
+    Oldest,
+    Title,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchCriteria {
+    #[serde(default)]
+    pub query: String, // Matched like quick search: place, topic, title, content, attachments
+    #[serde(default)]
+    pub tag: Option<String>, // Also matches hierarchical children
+    #[serde(default)]
+    pub country: Option<String>, // Slug or `group:<slug>`; topic notes match their targets
+    #[serde(default)]
+    pub topic_id: Option<String>,
+    #[serde(default)]
+    pub from: Option<String>, // YYYY-MM-DD, inclusive
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub sort: SearchSort,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    #[serde(flatten)]
+    pub criteria: SearchCriteria,
+    pub created_at: String,
+    #[serde(default)]
+    pub last_run_at: Option<String>,
+    #[serde(default)]
+    pub last_matches: Vec<String>, // Note IDs matched by the last run
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavedSearchMatch {
+    pub note_id: String,
+    pub title: String,
+    pub date: String,
+    pub tags: Vec<String>,
+    pub source_type: String, // "country" or "topic"
+    pub owner_id: String,
+    pub owner_name: String,
+    pub snippet: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment_match: Option<AttachmentMatch>, // Set when only an attachment's text matched
+    pub is_new: bool, // Not matched by the previous run
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavedSearchRun {
+    pub search: SavedSearch,
+    pub results: Vec<SavedSearchMatch>,
+    pub new_matches: usize,
+    pub previous_run_at: Option<String>,
+}
+
+pub fn load_saved_searches(vault_root: &Path) -> Result<Vec<SavedSearch>, String> {
+    let config = load_config(vault_root)?;
+
+    match config.get(CONFIG_KEY) {
+        Some(searches) => serde_json::from_value(searches.clone())
+            .map_err(|e| format!("Failed to parse saved searches: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Store the searches in the config, keeping its other settings
+fn save_saved_searches(vault_root: &Path, searches: &[SavedSearch]) -> Result<(), String> {
+    save_config_section(vault_root, CONFIG_KEY, &searches)
+}
+
+fn find_search(searches: &[SavedSearch], name: &str) -> Option<usize> {
+    searches
+        .iter()
+        .position(|s| s.name.to_lowercase() == name.trim().to_lowercase())
+}
+
+/// Notes in the vault matching `criteria`, sorted as it asks. `is_new` is
+/// left unset.
+pub fn find_matches(
+    vault_root: &Path,
+    criteria: &SearchCriteria,
+) -> Result<Vec<SavedSearchMatch>, String> {
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let groups = CountryGroups::load(Some(&vault_path));
+    let mut matcher = NoteMatcher::new(vault_root, &criteria.query)?;
+
+    let countries: Option<Vec<String>> = criteria
+        .country
+        .as_ref()
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| groups.expand_targets(&[c.trim().to_string()]));
+
+    let mut matches = Vec::new();
+
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+        let note = match parse_note(&content) {
+            Some(note) => note,
+            None => continue,
+        };
+
+        let (source_type, owner_id, note_countries) = match &file.source {
+            NoteSource::Country(slug) => ("country", slug.clone(), vec![slug.clone()]),
+            NoteSource::Topic(topic_id) => (
+                "topic",
+                topic_id.clone(),
+                groups.expand_targets(&note.country_targets),
+            ),
+        };
+
+        // Cheap filters first; the query may have to read attachment text
+        let filtered = criteria
+            .tag
+            .as_deref()
+            .is_none_or(|tag| note.tags.iter().any(|t| tag_matches(t, tag)))
+            && countries
+                .as_ref()
+                .is_none_or(|wanted| note_countries.iter().any(|c| wanted.contains(c)))
+            && criteria
+                .topic_id
+                .as_deref()
+                .is_none_or(|topic_id| source_type == "topic" && owner_id == topic_id)
+            && criteria
+                .from
+                .as_deref()
+                .is_none_or(|from| note.date.as_str() >= from)
+            && criteria
+                .to
+                .as_deref()
+                .is_none_or(|to| note.date.as_str() <= to);
+        if !filtered {
+            continue;
+        }
+
+        let found = match matcher.find(source_type, &owner_id, &note)? {
+            Some(found) => found,
+            None => continue,
+        };
+
+        matches.push(SavedSearchMatch {
+            snippet: found.snippet,
+            attachment_match: found.attachment_match,
+            note_id: note.id,
+            title: note.title,
+            date: note.date,
+            tags: note.tags,
+            source_type: source_type.to_string(),
+            owner_id,
+            owner_name: found.owner_name,
+            is_new: false,
+        });
+    }
+
+    match criteria.sort {
+        SearchSort::Newest => matches.sort_by(|a, b| b.date.cmp(&a.date)),
+        SearchSort::Oldest => matches.sort_by(|a, b| a.date.cmp(&b.date)),
+        SearchSort::Title => matches.sort_by_key(|m| m.title.to_lowercase()),
+    }
+
+    Ok(matches)
+}
+
+/// Run a saved search, flagging notes the previous run didn't match, and
+/// remember this run's matches
+pub fn run_search(vault_root: &Path, name: &str) -> Result<SavedSearchRun, String> {
+    let mut searches = load_saved_searches(vault_root)?;
+    let index = find_search(&searches, name).ok_or(format!("Saved search not found: {}", name))?;
+
+    let mut results = find_matches(vault_root, &searches[index].criteria)?;
+    let search = &mut searches[index];
+
+    // Everything counts as new on the first run
+    let previous: HashSet<&String> = search.last_matches.iter().collect();
+    for result in &mut results {
+        result.is_new = !previous.contains(&result.note_id);
+    }
+    let new_matches = results.iter().filter(|r| r.is_new).count();
+
+    let previous_run_at = search.last_run_at.take();
+    search.last_run_at = Some(chrono::Utc::now().to_rfc3339());
+    search.last_matches = results.iter().map(|r| r.note_id.clone()).collect();
+    let search = search.clone();
+
+    save_saved_searches(vault_root, &searches)?;
+
+    Ok(SavedSearchRun {
+        search,
+        results,
+        new_matches,
+        previous_run_at,
+    })
+}
+
+/// Save a named search, replacing one with the same name (which also
+/// resets what counts as new)
+#[tauri::command]
+pub fn save_search(
+    name: String,
+    criteria: SearchCriteria,
+    state: State<AppState>,
+) -> Result<SavedSearch, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+    let vault_root = PathBuf::from(vault_path);
+
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Saved search name cannot be empty".to_string());
+    }
+
+    let mut searches = load_saved_searches(&vault_root)?;
+    let search = SavedSearch {
+        name,
+        criteria,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        last_run_at: None,
+        last_matches: Vec::new(),
+    };
+
+    match find_search(&searches, &search.name) {
+        Some(index) => searches[index] = search.clone(),
+        None => searches.push(search.clone()),
+    }
+    save_saved_searches(&vault_root, &searches)?;
+
+    JournalEvent::new("search.saved")
+        .entity(&search.name)
+        .record(&vault_root);
+
+    Ok(search)
+}
+
+#[tauri::command]
+pub fn list_saved_searches(state: State<AppState>) -> Result<Vec<SavedSearch>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    load_saved_searches(&PathBuf::from(vault_path))
+}
+
+/// Run a saved search. Remembering the matches writes to the config, so
+/// this takes the writer lock.
+#[tauri::command]
+pub fn run_saved_search(name: String, state: State<AppState>) -> Result<SavedSearchRun, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    run_search(&PathBuf::from(vault_path), &name)
+}
+
+#[tauri::command]
+pub fn delete_saved_search(name: String, state: State<AppState>) -> Result<(), String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+    let vault_root = PathBuf::from(vault_path);
+
+    let mut searches = load_saved_searches(&vault_root)?;
+    let index = find_search(&searches, &name).ok_or(format!("Saved search not found: {}", name))?;
+    let removed = searches.remove(index);
+
+    save_saved_searches(&vault_root, &searches)?;
+
+    JournalEvent::new("search.deleted")
+        .entity(&removed.name)
+        .record(&vault_root);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::extraction::extract_attachment_text;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_run_saved_search_counts_new_matches() {
+        let vault = TestVault::new();
+        vault.write(".mapanote/config.json", r#"{"name": "Test"}"#);
+        vault.write(
+            "notes/fi/01A.md",
+            "---\nid: 01A\ntitle: Grid outage\ndate: 2025-01-10\ntags: [energy/grid]\n---\n\nOutage",
+        );
+        vault.write(
+            "notes/fi/01B.md",
+            "---\nid: 01B\ntitle: Budget\ndate: 2025-01-11\ntags: [economy]\n---\n\nBudget",
+        );
+
+        let mut searches = load_saved_searches(&vault).unwrap();
+        searches.push(SavedSearch {
+            name: "Nordic energy".to_string(),
+            criteria: SearchCriteria {
+                tag: Some("energy".to_string()),
+                country: Some("group:nordic".to_string()),
+                ..Default::default()
+            },
+            created_at: String::new(),
+            last_run_at: None,
+            last_matches: Vec::new(),
+        });
+        save_saved_searches(&vault, &searches).unwrap();
+
+        let first = run_search(&vault, "nordic energy").unwrap();
+        assert_eq!(first.results.len(), 1);
+        assert_eq!(first.new_matches, 1);
+
+        vault.write(
+            "topics/T1/01C.md",
+            "---\nid: 01C\ntitle: Interconnector\ndate: 2025-02-01\ntags: [energy]\ntopic_id: T1\ncountry_targets: [se]\n---\n\nCable",
+        );
+
+        let second = run_search(&vault, "Nordic energy").unwrap();
+        assert_eq!(second.results.len(), 2);
+        assert_eq!(second.new_matches, 1);
+        assert_eq!(second.results[0].note_id, "01C");
+        assert!(second.results[0].is_new);
+        assert!(second.previous_run_at.is_some());
+
+        // Other config settings survive
+        let config = load_config(&vault).unwrap();
+        assert_eq!(config["name"], "Test");
+    }
+
+    #[test]
+    fn test_query_matches_places_and_attachments() {
+        let vault = TestVault::new();
+        vault.write(
+            "notes/fi/01A.md",
+            "---\nid: 01A\ntitle: Budget\ndate: 2025-01-10\ntags: []\n---\n\nSpending",
+        );
+        vault.write(
+            "notes/se/01B.md",
+            "---\nid: 01B\ntitle: Report\ndate: 2025-01-11\ntags: []\nattachments: [{\"name\":\"memo.txt\",\"mime\":\"text/plain\",\"size\":0,\"added\":\"2025-01-11\"}]\n---\n\nSee memo",
+        );
+        let dir = vault.join("notes/se/attachments/01B");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("memo.txt"), "Talks on the Baltic interconnector").unwrap();
+        let note = parse_note(&fs::read_to_string(vault.join("notes/se/01B.md")).unwrap()).unwrap();
+        extract_attachment_text(&dir, &note.attachments[0]).unwrap();
+
+        let search = |query: &str| {
+            find_matches(
+                &vault,
+                &SearchCriteria {
+                    query: query.to_string(),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        // The place name lists all of its notes
+        let finland = search("finland");
+        assert_eq!(finland.len(), 1);
+        assert_eq!(finland[0].note_id, "01A");
+        assert_eq!(finland[0].owner_name, "Finland");
+
+        let baltic = search("interconnector");
+        assert_eq!(baltic.len(), 1);
+        assert_eq!(baltic[0].note_id, "01B");
+        assert_eq!(
+            baltic[0].attachment_match.as_ref().unwrap().name,
+            "memo.txt"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use super::assets::owner_dir;
+use super::attachments::attachments_dir;
+use super::entities::PlaceResolver;
+use super::extraction::{find_in_text, load_extracted_text, AttachmentMatch};
+use super::topics::load_topics_manifest;
+use mapanote_lib::models::{Note, TopicsManifest};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const SNIPPET_CHARS: usize = 150;
+
+/// Why a note matched a text query
+#[derive(Debug)]
+pub struct NoteMatch {
+    pub owner_name: String, // Country name, or topic title for topic notes
+    pub snippet: String,
+    pub attachment_match: Option<AttachmentMatch>, // Set when only an attachment's text matched
+}
+
+/// Matches notes against a text query the same way everywhere the vault is
+/// searched: a note matches when the query is the name of its place (or an
+/// alias of it) or its topic, appears in its title or content, or failing that in the
+/// extracted text of one of its attachments. An empty query matches all notes.
+pub struct NoteMatcher {
+    vault_root: PathBuf,
+    query: String, // Lowercase
+    resolver: PlaceResolver,
+    topics: TopicsManifest,
+    // Owner name, and whether the query names the place or topic, per owner
+    owners: HashMap<(String, String), (String, bool)>,
+}
+
+impl NoteMatcher {
+    pub fn new(vault_root: &Path, query: &str) -> Result<Self, String> {
+        let vault_path = vault_root.to_string_lossy();
+
+        Ok(Self {
+            vault_root: vault_root.to_path_buf(),
+            query: query.trim().to_lowercase(),
+            resolver: PlaceResolver::load(&vault_path)?,
+            topics: load_topics_manifest(&vault_path).unwrap_or_else(|_| TopicsManifest::new()),
+            owners: HashMap::new(),
+        })
+    }
+
+    fn owner(&mut self, source_type: &str, owner_id: &str) -> (String, bool) {
+        let query = &self.query;
+        let resolver = &self.resolver;
+        let topics = &self.topics;
+
+        self.owners
+            .entry((source_type.to_string(), owner_id.to_string()))
+            .or_insert_with(|| {
+                if source_type == "topic" {
+                    let title = topics
+                        .topics
+                        .iter()
+                        .find(|t| t.id == owner_id)
+                        .map(|t| t.title.clone())
+                        .unwrap_or_else(|| owner_id.to_string());
+                    let matches = query.is_empty() || names_match(&title, query);
+                    return (title, matches);
+                }
+
+                // Country name from metadata (falls back to slug)
+                let name = resolver.name(owner_id);
+
+                // Searching for a place's whole name or alias lists all of its
+                // notes; part of one ("land") only matches the notes' text
+                let matches = query.is_empty()
+                    || names_match(&name, query)
+                    || resolver
+                        .entity(owner_id)
+                        .is_some_and(|e| e.aliases.iter().any(|a| names_match(a, query)));
+
+                (name, matches)
+            })
+            .clone()
+    }
+
+    /// Match a note of `owner_id` ("country" or "topic" per `source_type`)
+    pub fn find(
+        &mut self,
+        source_type: &str,
+        owner_id: &str,
+        note: &Note,
+    ) -> Result<Option<NoteMatch>, String> {
+        let (owner_name, owner_matches) = self.owner(source_type, owner_id);
+
+        let note_matches = owner_matches
+            || note.title.to_lowercase().contains(&self.query)
+            || note.content.to_lowercase().contains(&self.query);
+
+        // Otherwise fall back to the text of the note's attachments
+        let attachment_match = if note_matches || note.attachments.is_empty() {
+            None
+        } else {
+            let dir = attachments_dir(
+                &owner_dir(&self.vault_root, source_type, owner_id)?,
+                &note.id,
+            );
+            note.attachments.iter().find_map(|attachment| {
+                load_extracted_text(&dir, attachment)
+                    .and_then(|text| find_in_text(&text, &self.query))
+            })
+        };
+
+        if !note_matches && attachment_match.is_none() {
+            return Ok(None);
+        }
+
+        let snippet = match &attachment_match {
+            Some(found) => found.snippet.clone(),
+            None => snippet(&note.content),
+        };
+
+        Ok(Some(NoteMatch {
+            owner_name,
+            snippet,
+            attachment_match,
+        }))
+    }
+}
+
+/// Whether a (lowercase) query is the whole of a place name, alias or
+/// topic title
+fn names_match(name: &str, query: &str) -> bool {
+    name.trim().to_lowercase() == query
+}
+
+/// The start of a note's content
+fn snippet(content: &str) -> String {
+    if content.chars().count() > SNIPPET_CHARS {
+        format!(
+            "{}...",
+            content.chars().take(SNIPPET_CHARS).collect::<String>()
+        )
+    } else {
+        content.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+
+    fn note(id: &str, title: &str, content: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            date: "2025-01-01".to_string(),
+            tags: Vec::new(),
+            topic_id: None,
+            country_targets: Vec::new(),
+            attachments: Vec::new(),
+            visibility: Default::default(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            review_by: None,
+            follow_ups: Vec::new(),
+            reviewed: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_place_names_match_whole() {
+        let vault = TestVault::new();
+        let budget = note("01A", "Budget", "Spending");
+        let farmland = note("01B", "Farms", "Farmland prices");
+
+        // The whole name lists every note of the place
+        let mut matcher = NoteMatcher::new(&vault, " Finland ").unwrap();
+        assert!(matcher.find("country", "fi", &budget).unwrap().is_some());
+        assert!(matcher.find("country", "pl", &budget).unwrap().is_none());
+
+        // Part of a name only matches notes that say it
+        let mut matcher = NoteMatcher::new(&vault, "land").unwrap();
+        assert!(matcher.find("country", "fi", &budget).unwrap().is_none());
+        assert!(matcher.find("country", "fi", &farmland).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_topic_titles_match_whole() {
+        let vault = TestVault::new();
+        vault.write(
+            "topics.json",
+            r#"{"version": "1.0", "topics": [
+                {"id": "T1", "title": "Energy security", "pinned": false, "created_at": "", "updated_at": ""}
+            ], "relations": []}"#,
+        );
+        let drills = note("01A", "Drills", "Navy exercise");
+
+        let mut matcher = NoteMatcher::new(&vault, "energy").unwrap();
+        assert!(matcher.find("topic", "T1", &drills).unwrap().is_none());
+
+        let mut matcher = NoteMatcher::new(&vault, "energy security").unwrap();
+        let found = matcher.find("topic", "T1", &drills).unwrap().unwrap();
+        assert_eq!(found.owner_name, "Energy security");
+    }
+}
@@ -0,0 +1,724 @@
+use super::assets::asset_references;
+use super::attachments::attachments_dir;
+use super::entities::{slugify, PlaceResolver};
+use super::gis_export::{country_stats, StatsFilter};
+use super::groups::CountryGroups;
+use super::notes::{collect_note_files, parse_note, NoteSource};
+use super::templates::{
+    country_context, note_context, note_countries, topic_context, ExportTemplates,
+};
+use super::topics::load_topics_manifest;
+use mapanote_lib::models::{CountryMetadata, Note, Visibility};
+use mapanote_lib::AppState;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+const STYLESHEET: &str = "body{font-family:system-ui,sans-serif;max-width:52rem;margin:0 auto;padding:1rem 1.5rem;color:#1f2937;line-height:1.6}\
+a{color:#2563eb;text-decoration:none}a:hover{text-decoration:underline}\
+nav{border-bottom:1px solid #e5e7eb;padding-bottom:.5rem;margin-bottom:1.5rem}\
+.meta{color:#6b7280;font-size:.875rem}.tag{background:#f3f4f6;border-radius:.25rem;padding:0 .4rem;margin-right:.25rem}\
+img{max-width:100%}ul.notes{list-style:none;padding:0}ul.notes li{margin-bottom:.75rem}\
+#results li{margin-bottom:.5rem}input[type=search]{width:100%;padding:.5rem;font-size:1rem}";
+
+/// Filters the search index (loaded from search-index.js, which also works
+/// over file://) as the reader types
+const SEARCH_SCRIPT: &str = r#"<script src="search-index.js"></script>
+<script>
+const input = document.getElementById("search");
+const results = document.getElementById("results");
+input.addEventListener("input", () => {
+  const q = input.value.trim().toLowerCase();
+  results.innerHTML = "";
+  if (q.length < 2) return;
+  for (const entry of SEARCH_INDEX) {
+    if (!(entry.title + " " + entry.place + " " + entry.tags.join(" ") + " " + entry.text).toLowerCase().includes(q)) continue;
+    const li = document.createElement("li");
+    const a = document.createElement("a");
+    a.href = entry.url;
+    a.textContent = entry.title;
+    li.append(a, " — " + entry.place + ", " + entry.date);
+    results.append(li);
+    if (results.children.length >= 50) break;
+  }
+});
+</script>"#;
+
+#[derive(Debug, Serialize)]
+pub struct SiteExportReport {
+    pub destination: String,
+    pub pages: usize,
+    pub notes: usize,
+    pub assets: usize,
+    pub attachments: usize,
+}
+
+#[derive(Serialize)]
+struct SearchEntry {
+    title: String,
+    place: String,
+    date: String,
+    tags: Vec<String>,
+    url: String,
+    text: String,
+}
+
+/// A note with the folder it lives in
+struct SiteNote {
+    note: Note,
+    owner: NoteSource,
+    owner_dir: PathBuf,
+}
+
+impl SiteNote {
+    /// Page folder relative to the site root, e.g. `countries/fi`
+    fn site_dir(&self) -> String {
+        match &self.owner {
+            NoteSource::Country(slug) => format!("countries/{}", slug),
+            NoteSource::Topic(topic_id) => format!("topics/{}", topic_id),
+        }
+    }
+
+    fn url(&self) -> String {
+        format!("{}/{}.html", self.site_dir(), self.note.id)
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Links and images that would run script when opened in a browser
+fn is_script_url(url: &str) -> bool {
+    let scheme: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .take_while(|c| *c != ':')
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    url.contains(':') && matches!(scheme.as_str(), "javascript" | "vbscript")
+}
+
+/// Render note Markdown to HTML for export. Raw HTML in the note is shown as
+/// text and script URLs are dropped, so the output is safe to insert as is.
+pub fn render_markdown(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_FOOTNOTES);
+
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if is_script_url(&dest_url) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: CowStr::Borrowed(""),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if is_script_url(&dest_url) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: CowStr::Borrowed(""),
+            title,
+            id,
+        }),
+        event => event,
+    });
+
+    let mut output = String::new();
+    html::push_html(&mut output, events);
+    output
+}
+
+/// Wrap page content in the shared layout. `root` is the relative path back
+/// to the site root ("" or "../../").
+fn layout(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n\
+         <nav><a href=\"{root}index.html\">Home</a></nav>\n<main>\n{body}\n</main>\n</body>\n</html>\n",
+        title = escape_html(title),
+        root = root,
+        body = body
+    )
+}
+
+fn tags_html(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| format!("<span class=\"tag\">#{}</span>", escape_html(tag)))
+        .collect()
+}
+
+fn write_page(destination: &Path, relative: &str, html: String) -> Result<(), String> {
+    let path = destination.join(relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+    fs::write(&path, html).map_err(|e| format!("Failed to write {}: {}", relative, e))
+}
+
+/// Notes to publish, newest first
+fn collect_site_notes(vault_root: &Path, publishable_only: bool) -> Result<Vec<SiteNote>, String> {
+    let mut notes = Vec::new();
+
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+
+        let note = match parse_note(&content) {
+            Some(note) => note,
+            None => continue,
+        };
+
+        if note.visibility == Visibility::Private
+            || (publishable_only && note.visibility != Visibility::Publishable)
+        {
+            continue;
+        }
+
+        notes.push(SiteNote {
+            owner_dir: file.path.parent().unwrap_or(vault_root).to_path_buf(),
+            note,
+            owner: file.source,
+        });
+    }
+
+    notes.sort_by(|a, b| b.note.date.cmp(&a.note.date));
+    Ok(notes)
+}
+
+/// Copy the assets and attachments a note uses next to its page, returning
+/// (assets, attachments) copied
+fn copy_note_files(
+    destination: &Path,
+    site_note: &SiteNote,
+    copied: &mut HashSet<PathBuf>,
+) -> Result<(usize, usize), String> {
+    let target_dir = destination.join(site_note.site_dir());
+    let mut assets = 0;
+
+    for name in asset_references(&site_note.note.content) {
+        let source = site_note.owner_dir.join("assets").join(&name);
+        let target = target_dir.join("assets").join(&name);
+
+        if name.contains("..") || !source.is_file() || !copied.insert(target.clone()) {
+            continue;
+        }
+
+        fs::create_dir_all(target_dir.join("assets"))
+            .map_err(|e| format!("Failed to create assets directory: {}", e))?;
+        fs::copy(&source, &target).map_err(|e| format!("Failed to copy asset: {}", e))?;
+        assets += 1;
+    }
+
+    let source_dir = attachments_dir(&site_note.owner_dir, &site_note.note.id);
+    let target_attachments = attachments_dir(&target_dir, &site_note.note.id);
+    let mut attachments = 0;
+
+    for attachment in &site_note.note.attachments {
+        let source = source_dir.join(&attachment.name);
+        if !source.is_file() {
+            continue;
+        }
+
+        fs::create_dir_all(&target_attachments)
+            .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
+        fs::copy(&source, target_attachments.join(&attachment.name))
+            .map_err(|e| format!("Failed to copy attachment: {}", e))?;
+        attachments += 1;
+    }
+
+    Ok((assets, attachments))
+}
+
+fn note_list_item(site_note: &SiteNote, root: &str, place: Option<&str>) -> String {
+    let place = place
+        .map(|p| format!(" · {}", escape_html(p)))
+        .unwrap_or_default();
+
+    format!(
+        "<li><a href=\"{}{}\">{}</a> <span class=\"meta\">{}{}</span> {}</li>",
+        root,
+        site_note.url(),
+        escape_html(&site_note.note.title),
+        escape_html(&site_note.note.date),
+        place,
+        tags_html(&site_note.note.tags)
+    )
+}
+
+fn export_site(
+    vault_root: &Path,
+    destination: &Path,
+    publishable_only: bool,
+    template: Option<&str>,
+) -> Result<SiteExportReport, String> {
+    if destination.starts_with(vault_root) {
+        return Err("Export destination must be outside the vault".to_string());
+    }
+    fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let resolver = PlaceResolver::load(&vault_path)?;
+    let groups = CountryGroups::load(Some(&vault_path));
+    let topics_manifest = load_topics_manifest(&vault_path)?;
+    let templates = ExportTemplates::load_selected(vault_root, template, &["html", "md"])?;
+    let stats: HashMap<String, _> = match &templates {
+        Some(_) => country_stats(vault_root, &StatsFilter::default())?
+            .into_iter()
+            .map(|row| (row.slug.clone(), row))
+            .collect(),
+        None => HashMap::new(),
+    };
+
+    let notes = collect_site_notes(vault_root, publishable_only)?;
+    let topic_title = |topic_id: &str| {
+        topics_manifest
+            .topics
+            .iter()
+            .find(|t| t.id == topic_id)
+            .map(|t| t.title.clone())
+            .unwrap_or_else(|| topic_id.to_string())
+    };
+    let find_topic = |topic_id: &str| topics_manifest.topics.iter().find(|t| t.id == topic_id);
+    let owner_name = |owner: &NoteSource| match owner {
+        NoteSource::Country(slug) => resolver.name(slug),
+        NoteSource::Topic(topic_id) => topic_title(topic_id),
+    };
+
+    let mut report = SiteExportReport {
+        destination: destination.to_string_lossy().to_string(),
+        pages: 0,
+        notes: notes.len(),
+        assets: 0,
+        attachments: 0,
+    };
+    let mut copied = HashSet::new();
+    let mut search_index = Vec::new();
+
+    // Country slug -> country notes and topic notes targeting it
+    let mut country_notes: BTreeMap<String, Vec<&SiteNote>> = BTreeMap::new();
+    let mut country_topic_notes: BTreeMap<String, Vec<&SiteNote>> = BTreeMap::new();
+    let mut topic_notes: BTreeMap<String, Vec<&SiteNote>> = BTreeMap::new();
+
+    for site_note in &notes {
+        match &site_note.owner {
+            NoteSource::Country(slug) => country_notes
+                .entry(slug.clone())
+                .or_default()
+                .push(site_note),
+            NoteSource::Topic(topic_id) => {
+                topic_notes
+                    .entry(topic_id.clone())
+                    .or_default()
+                    .push(site_note);
+                for slug in groups.expand_targets(&site_note.note.country_targets) {
+                    country_notes.entry(slug.clone()).or_default();
+                    country_topic_notes.entry(slug).or_default().push(site_note);
+                }
+            }
+        }
+    }
+
+    // Note pages
+    for site_note in &notes {
+        let (assets, attachments) = copy_note_files(destination, site_note, &mut copied)?;
+        report.assets += assets;
+        report.attachments += attachments;
+
+        let note = &site_note.note;
+        let owner_link = format!(
+            "<a href=\"index.html\">{}</a>",
+            escape_html(&owner_name(&site_note.owner))
+        );
+
+        let targets = if matches!(site_note.owner, NoteSource::Topic(_)) {
+            groups
+                .expand_targets(&note.country_targets)
+                .iter()
+                .map(|slug| {
+                    format!(
+                        "<a href=\"../../countries/{}/index.html\">{}</a>",
+                        slug,
+                        escape_html(&resolver.name(slug))
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        } else {
+            String::new()
+        };
+
+        let attachments_html = if note.attachments.is_empty() {
+            String::new()
+        } else {
+            let items: String = note
+                .attachments
+                .iter()
+                .map(|a| {
+                    format!(
+                        "<li><a href=\"attachments/{}/{}\">{}</a></li>",
+                        note.id,
+                        utf8_percent_encode(&a.name, NON_ALPHANUMERIC),
+                        escape_html(&a.name)
+                    )
+                })
+                .collect();
+            format!("<h2>Attachments</h2><ul>{}</ul>", items)
+        };
+
+        let templated = match &templates {
+            Some(templates) => {
+                let topic = match &site_note.owner {
+                    NoteSource::Topic(topic_id) => find_topic(topic_id),
+                    NoteSource::Country(_) => None,
+                };
+                let countries = note_countries(&site_note.owner, note, &groups, &resolver);
+                templates.render_html(
+                    "note",
+                    &note_context(note, &site_note.owner, topic, &countries),
+                )?
+            }
+            None => None,
+        };
+
+        let body = templated.unwrap_or_else(|| format!(
+            "<article>\n<h1>{}</h1>\n<p class=\"meta\">{} · {}{}</p>\n<p>{}</p>\n{}\n{}\n</article>",
+            escape_html(&note.title),
+            escape_html(&note.date),
+            owner_link,
+            if targets.is_empty() {
+                String::new()
+            } else {
+                format!(" · {}", targets)
+            },
+            tags_html(&note.tags),
+            render_markdown(&note.content),
+            attachments_html
+        ));
+
+        write_page(
+            destination,
+            &site_note.url(),
+            layout(&note.title, "../../", &body),
+        )?;
+        report.pages += 1;
+
+        search_index.push(SearchEntry {
+            title: note.title.clone(),
+            place: owner_name(&site_note.owner),
+            date: note.date.clone(),
+            tags: note.tags.clone(),
+            url: site_note.url(),
+            text: note.content.chars().take(2000).collect(),
+        });
+    }
+
+    // Country pages, grouped by region for the listings
+    let mut regions: BTreeMap<String, Vec<(String, String, usize)>> = BTreeMap::new();
+
+    for (slug, own_notes) in &country_notes {
+        let metadata = resolver.resolve(slug);
+        let name = resolver.name(slug);
+        let topic_notes_here = country_topic_notes.get(slug).cloned().unwrap_or_default();
+
+        // A template replaces the page header; the note lists below stay
+        // so the site remains navigable
+        let templated = match (&templates, &metadata) {
+            (Some(templates), Some(metadata)) => {
+                let notes: Vec<&Note> = own_notes.iter().map(|n| &n.note).collect();
+                let topic_notes: Vec<&Note> = topic_notes_here.iter().map(|n| &n.note).collect();
+                templates.render_html(
+                    "country",
+                    &country_context(metadata, &notes, &topic_notes, stats.get(slug)),
+                )?
+            }
+            _ => None,
+        };
+
+        let mut body = match templated {
+            Some(templated) => templated,
+            None => {
+                let mut header = format!("<h1>{}</h1>\n", escape_html(&name));
+                if let Some(metadata) = &metadata {
+                    header.push_str(&format!(
+                        "<p class=\"meta\">{} · {}</p>\n<p>{}</p>\n",
+                        escape_html(&metadata.region),
+                        escape_html(&metadata.subregion),
+                        escape_html(&metadata.summary)
+                    ));
+                }
+                header
+            }
+        };
+
+        if !own_notes.is_empty() {
+            let items: String = own_notes
+                .iter()
+                .map(|n| note_list_item(n, "../../", None))
+                .collect();
+            body.push_str(&format!(
+                "<h2>Notes</h2>\n<ul class=\"notes\">{}</ul>\n",
+                items
+            ));
+        }
+
+        if !topic_notes_here.is_empty() {
+            let items: String = topic_notes_here
+                .iter()
+                .map(|n| note_list_item(n, "../../", Some(&owner_name(&n.owner))))
+                .collect();
+            body.push_str(&format!(
+                "<h2>Topic notes</h2>\n<ul class=\"notes\">{}</ul>\n",
+                items
+            ));
+        }
+
+        write_page(
+            destination,
+            &format!("countries/{}/index.html", slug),
+            layout(&name, "../../", &body),
+        )?;
+        report.pages += 1;
+
+        let region = metadata
+            .map(|m| m.region)
+            .filter(|r| !r.is_empty())
+            .unwrap_or_else(|| "Other".to_string());
+        regions.entry(region).or_default().push((
+            slug.clone(),
+            name,
+            own_notes.len() + topic_notes_here.len(),
+        ));
+    }
+
+    // Topic pages
+    for (topic_id, notes_in_topic) in &topic_notes {
+        let topic = find_topic(topic_id);
+        let title = topic_title(topic_id);
+
+        let templated = match (&templates, topic) {
+            (Some(templates), Some(topic)) => {
+                let slugs: BTreeSet<String> = notes_in_topic
+                    .iter()
+                    .flat_map(|n| groups.expand_targets(&n.note.country_targets))
+                    .collect();
+                let countries: Vec<CountryMetadata> = slugs
+                    .iter()
+                    .filter_map(|slug| resolver.resolve(slug))
+                    .collect();
+                let notes: Vec<&Note> = notes_in_topic.iter().map(|n| &n.note).collect();
+                templates.render_html("topic", &topic_context(topic, &countries, &notes))?
+            }
+            _ => None,
+        };
+
+        let mut body = match templated {
+            Some(templated) => templated,
+            None => {
+                let mut header = format!("<h1>{}</h1>\n", escape_html(&title));
+                if let Some(summary) = topic.and_then(|t| t.summary.as_deref()) {
+                    header.push_str(&format!("<p>{}</p>\n", escape_html(summary)));
+                }
+                header
+            }
+        };
+
+        let items: String = notes_in_topic
+            .iter()
+            .map(|n| note_list_item(n, "../../", None))
+            .collect();
+        body.push_str(&format!("<ul class=\"notes\">{}</ul>\n", items));
+
+        write_page(
+            destination,
+            &format!("topics/{}/index.html", topic_id),
+            layout(&title, "../../", &body),
+        )?;
+        report.pages += 1;
+    }
+
+    // Region listings
+    for (region, countries) in &regions {
+        let mut countries = countries.clone();
+        countries.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let items: String = countries
+            .iter()
+            .map(|(slug, name, count)| {
+                format!(
+                    "<li><a href=\"../countries/{}/index.html\">{}</a> <span class=\"meta\">{} notes</span></li>",
+                    slug,
+                    escape_html(name),
+                    count
+                )
+            })
+            .collect();
+
+        write_page(
+            destination,
+            &format!("regions/{}.html", slugify(region)),
+            layout(
+                region,
+                "../",
+                &format!("<h1>{}</h1>\n<ul>{}</ul>", escape_html(region), items),
+            ),
+        )?;
+        report.pages += 1;
+    }
+
+    // Index
+    let region_items: String = regions
+        .iter()
+        .map(|(region, countries)| {
+            format!(
+                "<li><a href=\"regions/{}.html\">{}</a> <span class=\"meta\">{} countries</span></li>",
+                slugify(region),
+                escape_html(region),
+                countries.len()
+            )
+        })
+        .collect();
+    let topic_items: String = topic_notes
+        .iter()
+        .map(|(topic_id, notes_in_topic)| {
+            format!(
+                "<li><a href=\"topics/{}/index.html\">{}</a> <span class=\"meta\">{} notes</span></li>",
+                topic_id,
+                escape_html(&topic_title(topic_id)),
+                notes_in_topic.len()
+            )
+        })
+        .collect();
+    let recent_items: String = notes
+        .iter()
+        .take(20)
+        .map(|n| note_list_item(n, "", Some(&owner_name(&n.owner))))
+        .collect();
+
+    let vault_name = vault_root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Mapanote".to_string());
+
+    let index_body = format!(
+        "<h1>{}</h1>\n<input type=\"search\" id=\"search\" placeholder=\"Search notes\">\n<ul id=\"results\"></ul>\n\
+         <h2>Regions</h2>\n<ul>{}</ul>\n<h2>Topics</h2>\n<ul>{}</ul>\n<h2>Recent notes</h2>\n<ul class=\"notes\">{}</ul>\n{}",
+        escape_html(&vault_name),
+        region_items,
+        topic_items,
+        recent_items,
+        SEARCH_SCRIPT
+    );
+    write_page(
+        destination,
+        "index.html",
+        layout(&vault_name, "", &index_body),
+    )?;
+    report.pages += 1;
+
+    write_page(destination, "style.css", STYLESHEET.to_string())?;
+
+    let index_json = serde_json::to_string(&search_index)
+        .map_err(|e| format!("Failed to serialize search index: {}", e))?;
+    write_page(
+        destination,
+        "search-index.js",
+        format!("const SEARCH_INDEX = {};\n", index_json),
+    )?;
+
+    Ok(report)
+}
+
+/// Render the vault as a static, read-only HTML site. Private notes are never
+/// exported; with `publishable_only` only notes marked publishable are.
+/// `template` names an export template set rendering note pages and the
+/// headers of country and topic pages.
+#[tauri::command]
+pub fn export_static_site(
+    destination: String,
+    publishable_only: bool,
+    template: Option<String>,
+    state: State<AppState>,
+) -> Result<SiteExportReport, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    export_site(
+        &PathBuf::from(vault_path),
+        &PathBuf::from(destination),
+        publishable_only,
+        template.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_render_markdown_escapes_html() {
+        let html = render_markdown(
+            "Hi <script>alert(1)</script>\n\n<img src=x onerror=alert(1)>\n\n[a](JavaScript:alert(1)) [b](https://example.com)",
+        );
+
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(html.contains("<a href=\"\">a</a>"));
+        assert!(html.contains("<a href=\"https://example.com\">b</a>"));
+    }
+
+    #[test]
+    fn test_export_site_respects_visibility() {
+        let vault = TestVault::new();
+        let site = vault.scratch("site");
+
+        vault.write("notes/fi/assets/map.png", b"png");
+        vault.write(
+            "notes/fi/01A.md",
+            "---\nid: 01A\ntitle: Public <note>\ndate: 2025-10-07\ntags: [energy]\nvisibility: publishable\n---\n\n**Bold** ![Map](assets/map.png)",
+        );
+        vault.write(
+            "notes/fi/01B.md",
+            "---\nid: 01B\ntitle: Internal\ndate: 2025-10-06\ntags: []\n---\n\nInternal only",
+        );
+
+        let report = export_site(&vault, &site, true, None).unwrap();
+        assert_eq!(report.notes, 1);
+        assert_eq!(report.assets, 1);
+
+        let page = fs::read_to_string(site.join("countries/fi/01A.html")).unwrap();
+        assert!(page.contains("<h1>Public &lt;note&gt;</h1>"));
+        assert!(page.contains("<strong>Bold</strong>"));
+        assert!(site.join("countries/fi/assets/map.png").exists());
+        assert!(!site.join("countries/fi/01B.html").exists());
+        assert!(site.join("regions/europe.html").exists());
+
+        let index = fs::read_to_string(site.join("search-index.js")).unwrap();
+        assert!(index.contains("countries/fi/01A.html"));
+
+        assert!(export_site(&vault, &vault.join("site"), false, None).is_err());
+    }
+}
@@ -0,0 +1,359 @@
+use super::journal::JournalEvent;
+use super::notes::{
+    collect_note_files, format_list, parse_note, set_frontmatter_field, touch_note, NoteSource,
+};
+use mapanote_lib::models::{NoteWithSource, VaultManifest};
+use mapanote_lib::AppState;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Serialize)]
+pub struct TagUsage {
+    pub tag: String,
+    pub note_count: usize, // Notes carrying exactly this tag
+    pub country_note_count: usize,
+    pub topic_note_count: usize,
+    pub total_count: usize,    // Notes carrying this tag or any child tag
+    pub variants: Vec<String>, // Other tags that only differ in case
+}
+
+#[derive(Serialize)]
+pub struct TagChangeResult {
+    pub notes_updated: usize,
+    pub countries_updated: usize,
+}
+
+/// Check whether a tag matches a query, including hierarchical children
+/// (`energy` matches `energy/nuclear`)
+pub fn tag_matches(tag: &str, query: &str) -> bool {
+    let tag = tag.to_lowercase();
+    let query = query.trim().trim_end_matches('/').to_lowercase();
+
+    tag == query || tag.starts_with(&format!("{}/", query))
+}
+
+/// All hierarchical ancestors of a tag, including itself
+/// (`energy/nuclear/smr` -> `energy`, `energy/nuclear`, `energy/nuclear/smr`)
+fn tag_ancestors(tag: &str) -> Vec<String> {
+    let parts: Vec<&str> = tag.split('/').collect();
+    (1..=parts.len()).map(|i| parts[..i].join("/")).collect()
+}
+
+/// Rename a tag or one of its children, returning None if it is unaffected
+fn rename_tag_value(tag: &str, from: &str, to: &str) -> Option<String> {
+    if tag == from {
+        Some(to.to_string())
+    } else {
+        tag.strip_prefix(&format!("{}/", from))
+            .map(|rest| format!("{}/{}", to, rest))
+    }
+}
+
+fn validate_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim().trim_matches('/').to_string();
+
+    if tag.is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+
+    if tag.contains([',', '[', ']', '"', '\n']) {
+        return Err(format!("Invalid characters in tag: {}", tag));
+    }
+
+    Ok(tag)
+}
+
+/// Rewrite tags in every note file and in vault.json using the given mapping
+fn apply_tag_mapping<F>(vault_root: &Path, mapping: F) -> Result<TagChangeResult, String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let map_tags = |tags: &[String]| -> Option<Vec<String>> {
+        let mut changed = false;
+        let mut seen = HashSet::new();
+        let mut new_tags = Vec::new();
+
+        for tag in tags {
+            let new_tag = match mapping(tag) {
+                Some(renamed) => {
+                    changed = true;
+                    renamed
+                }
+                None => tag.clone(),
+            };
+
+            if seen.insert(new_tag.clone()) {
+                new_tags.push(new_tag);
+            }
+        }
+
+        if changed {
+            Some(new_tags)
+        } else {
+            None
+        }
+    };
+
+    let mut notes_updated = 0;
+
+    // 1. Rewrite note files
+    for file in collect_note_files(vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+
+        let note = match parse_note(&content) {
+            Some(note) => note,
+            None => continue,
+        };
+
+        if let Some(new_tags) = map_tags(&note.tags) {
+            let new_content = touch_note(&set_frontmatter_field(
+                &content,
+                "tags",
+                &format_list(&new_tags),
+            ));
+            fs::write(&file.path, new_content)
+                .map_err(|e| format!("Failed to write note: {}", e))?;
+            notes_updated += 1;
+        }
+    }
+
+    // 2. Rewrite tags in vault.json
+    let manifest_path = vault_root.join("vault.json");
+    let manifest_str = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("Failed to read manifest: {}", e))?;
+
+    let mut manifest: VaultManifest = serde_json::from_str(&manifest_str)
+        .map_err(|e| format!("Failed to parse manifest: {}", e))?;
+
+    let mut countries_updated = 0;
+
+    for stats in manifest.countries.values_mut() {
+        if let Some(mut new_tags) = map_tags(&stats.tags) {
+            new_tags.sort();
+            new_tags.dedup();
+            stats.tags = new_tags;
+            countries_updated += 1;
+        }
+    }
+
+    fs::write(
+        &manifest_path,
+        serde_json::to_string_pretty(&manifest)
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?,
+    )
+    .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    Ok(TagChangeResult {
+        notes_updated,
+        countries_updated,
+    })
+}
+
+/// List every tag in the vault with usage counts
+#[tauri::command]
+pub fn list_tags(state: State<AppState>) -> Result<Vec<TagUsage>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    let vault_root = PathBuf::from(vault_path);
+
+    // tag -> (exact count, country count, topic count, note ids including children)
+    let mut usage: BTreeMap<String, (usize, usize, usize, HashSet<String>)> = BTreeMap::new();
+
+    for file in collect_note_files(&vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+
+        let note = match parse_note(&content) {
+            Some(note) => note,
+            None => continue,
+        };
+
+        for tag in &note.tags {
+            let entry = usage.entry(tag.clone()).or_default();
+            entry.0 += 1;
+            match file.source {
+                NoteSource::Country(_) => entry.1 += 1,
+                NoteSource::Topic(_) => entry.2 += 1,
+            }
+
+            // Count the note towards the tag and all of its parents
+            for ancestor in tag_ancestors(tag) {
+                usage.entry(ancestor).or_default().3.insert(note.id.clone());
+            }
+        }
+    }
+
+    let all_tags: Vec<String> = usage.keys().cloned().collect();
+
+    let tags = usage
+        .into_iter()
+        .map(
+            |(tag, (note_count, country_note_count, topic_note_count, ids))| {
+                let variants = all_tags
+                    .iter()
+                    .filter(|other| *other != &tag && other.to_lowercase() == tag.to_lowercase())
+                    .cloned()
+                    .collect();
+
+                TagUsage {
+                    tag,
+                    note_count,
+                    country_note_count,
+                    topic_note_count,
+                    total_count: ids.len(),
+                    variants,
+                }
+            },
+        )
+        .collect();
+
+    Ok(tags)
+}
+
+/// Rename a tag (and its children) across all notes and manifests
+#[tauri::command]
+pub fn rename_tag(
+    from: String,
+    to: String,
+    state: State<AppState>,
+) -> Result<TagChangeResult, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let from = validate_tag(&from)?;
+    let to = validate_tag(&to)?;
+
+    if from == to {
+        return Ok(TagChangeResult {
+            notes_updated: 0,
+            countries_updated: 0,
+        });
+    }
+
+    let vault_root = PathBuf::from(vault_path);
+    let result = apply_tag_mapping(&vault_root, |tag| rename_tag_value(tag, &from, &to))?;
+
+    JournalEvent::new("tag.renamed")
+        .entities(&[&from, &to])
+        .record(&vault_root);
+
+    Ok(result)
+}
+
+/// Merge several tags into one target tag across all notes and manifests
+#[tauri::command]
+pub fn merge_tags(
+    sources: Vec<String>,
+    target: String,
+    state: State<AppState>,
+) -> Result<TagChangeResult, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    let target = validate_tag(&target)?;
+    let sources = sources
+        .iter()
+        .map(|s| validate_tag(s))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let vault_root = PathBuf::from(vault_path);
+    let result = apply_tag_mapping(&vault_root, |tag| {
+        sources
+            .iter()
+            .filter(|source| **source != target)
+            .find_map(|source| rename_tag_value(tag, source, &target))
+    })?;
+
+    JournalEvent::new("tag.merged")
+        .entities(&sources)
+        .entity(&target)
+        .record(&vault_root);
+
+    Ok(result)
+}
+
+/// Get all notes carrying a tag or any of its children
+#[tauri::command]
+pub fn get_notes_by_tag(
+    tag: String,
+    state: State<AppState>,
+) -> Result<Vec<NoteWithSource>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    let vault_root = PathBuf::from(vault_path);
+    let topics_manifest = crate::commands::topics::load_topics_manifest(vault_path)
+        .unwrap_or_else(|_| mapanote_lib::models::TopicsManifest::new());
+
+    let mut results = Vec::new();
+
+    for file in collect_note_files(&vault_root)? {
+        let content =
+            fs::read_to_string(&file.path).map_err(|e| format!("Failed to read note: {}", e))?;
+
+        let note = match parse_note(&content) {
+            Some(note) => note,
+            None => continue,
+        };
+
+        if !note.tags.iter().any(|t| tag_matches(t, &tag)) {
+            continue;
+        }
+
+        let (source_type, source_name, topic_color) = match &file.source {
+            NoteSource::Country(slug) => ("country", slug.clone(), None),
+            NoteSource::Topic(topic_id) => {
+                let topic = topics_manifest.topics.iter().find(|t| &t.id == topic_id);
+                (
+                    "topic",
+                    topic.map(|t| t.title.clone()).unwrap_or(topic_id.clone()),
+                    topic.and_then(|t| t.color.clone()),
+                )
+            }
+        };
+
+        results.push(NoteWithSource {
+            note,
+            source_type: source_type.to_string(),
+            source_name,
+            topic_color,
+        });
+    }
+
+    // Sort by date (newest first)
+    results.sort_by(|a, b| b.note.date.cmp(&a.note.date));
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_matches_hierarchy() {
+        assert!(tag_matches("energy/nuclear", "energy"));
+        assert!(tag_matches("Energy", "energy"));
+        assert!(!tag_matches("energy-policy", "energy"));
+        assert!(!tag_matches("energy", "energy/nuclear"));
+    }
+
+    #[test]
+    fn test_rename_tag_value() {
+        assert_eq!(
+            rename_tag_value("energy", "energy", "power"),
+            Some("power".to_string())
+        );
+        assert_eq!(
+            rename_tag_value("energy/nuclear", "energy", "power"),
+            Some("power/nuclear".to_string())
+        );
+        assert_eq!(rename_tag_value("Energy", "energy", "power"), None);
+        assert_eq!(tag_ancestors("a/b/c"), vec!["a", "a/b", "a/b/c"]);
+    }
+}
@@ -0,0 +1,577 @@
+use super::assets::validate_file_name;
+use super::entities::PlaceResolver;
+use super::gis_export::CountryStatsRow;
+use super::groups::CountryGroups;
+use super::import::{split_frontmatter, yaml_string, yaml_strings};
+use super::journal::JournalEvent;
+use super::notes::NoteSource;
+use super::static_site::render_markdown;
+use super::topics::{create_topic_note, load_topics_manifest, TopicNoteCreated};
+use super::vault::create_country_note;
+use mapanote_lib::models::{CountryMetadata, Note, Topic};
+use mapanote_lib::AppState;
+use minijinja::{path_loader, Environment, UndefinedBehavior, Value};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Export templates live in `<vault>/.mapanote/templates/export/<name>/`,
+/// one file per scope: `country.html`, `topic.md`, `note.html`, ...
+/// Files starting with `_` are partials for `{% include %}`.
+pub const EXPORT_TEMPLATES_DIR: &str = ".mapanote/templates/export";
+
+const SCOPES: [&str; 3] = ["country", "topic", "note"];
+
+/// Template file extension -> output format
+const FORMATS: [(&str, &str); 2] = [("md", "markdown"), ("html", "html")];
+
+/// Note templates: `<vault>/.mapanote/templates/notes/<id>.md`, with
+/// frontmatter defaults and a body skeleton. Title patterns starting with a
+/// placeholder must be quoted so they stay YAML strings:
+///
+/// ```text
+/// ---
+/// name: Election report
+/// title: "{{country.name}} election, {{date}}"
+/// tags: [elections, politics]
+/// country_targets: []
+/// ---
+///
+/// ## Result
+/// ```
+pub const NOTE_TEMPLATES_DIR: &str = ".mapanote/templates/notes";
+
+/// How many of the most used tags the stats carry
+const TOP_TAGS: usize = 5;
+
+#[derive(Debug, Serialize)]
+pub struct ExportTemplate {
+    pub name: String,
+    pub files: Vec<ExportTemplateFile>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportTemplateFile {
+    pub scope: String,  // "country", "topic" or "note"
+    pub format: String, // "markdown" or "html"
+    pub file_name: String,
+}
+
+/// The templates of one named set, ready to render
+pub struct ExportTemplates {
+    env: Environment<'static>,
+    files: HashMap<&'static str, String>, // Scope -> file name
+}
+
+impl ExportTemplates {
+    /// Load the template set `name`, picking for each scope the first file
+    /// whose extension appears in `extensions` (so callers state which
+    /// formats they can use, in order of preference)
+    pub fn load(vault_root: &Path, name: &str, extensions: &[&str]) -> Result<Self, String> {
+        validate_file_name(name)?;
+        let dir = vault_root.join(EXPORT_TEMPLATES_DIR).join(name);
+        if !dir.is_dir() {
+            return Err(format!("Export template not found: {}", name));
+        }
+
+        let mut files = HashMap::new();
+        for scope in SCOPES {
+            if let Some(extension) = extensions
+                .iter()
+                .find(|ext| dir.join(format!("{}.{}", scope, ext)).is_file())
+            {
+                files.insert(scope, format!("{}.{}", scope, extension));
+            }
+        }
+
+        // `.html` templates auto-escape; `markdown` turns note content into
+        // HTML with any raw HTML escaped, so it is safe to insert as is
+        let mut env = Environment::new();
+        env.set_loader(path_loader(dir));
+        env.add_filter("markdown", |text: String| {
+            Value::from_safe_string(render_markdown(&text))
+        });
+
+        Ok(Self { env, files })
+    }
+
+    /// Load `name` if a template was selected
+    pub fn load_selected(
+        vault_root: &Path,
+        name: Option<&str>,
+        extensions: &[&str],
+    ) -> Result<Option<Self>, String> {
+        name.filter(|name| !name.is_empty())
+            .map(|name| Self::load(vault_root, name, extensions))
+            .transpose()
+    }
+
+    /// Render `scope` with `context`, or `None` when the set has no template for it
+    pub fn render(
+        &self,
+        scope: &str,
+        context: &serde_json::Value,
+    ) -> Result<Option<String>, String> {
+        let file_name = match self.files.get(scope) {
+            Some(file_name) => file_name,
+            None => return Ok(None),
+        };
+
+        self.env
+            .get_template(file_name)
+            .and_then(|template| template.render(context))
+            .map(Some)
+            .map_err(|e| format!("Failed to render {}: {}", file_name, e))
+    }
+
+    /// Like `render`, converting Markdown templates to HTML
+    pub fn render_html(
+        &self,
+        scope: &str,
+        context: &serde_json::Value,
+    ) -> Result<Option<String>, String> {
+        let rendered = self.render(scope, context)?;
+        Ok(match self.files.get(scope) {
+            Some(file_name) if file_name.ends_with(".md") => {
+                rendered.map(|markdown| render_markdown(&markdown))
+            }
+            _ => rendered,
+        })
+    }
+}
+
+/// Metadata for the countries a note is about: its own country, or the
+/// expanded targets of a topic note
+pub fn note_countries(
+    owner: &NoteSource,
+    note: &Note,
+    groups: &CountryGroups,
+    resolver: &PlaceResolver,
+) -> Vec<CountryMetadata> {
+    let slugs = match owner {
+        NoteSource::Country(slug) => vec![slug.clone()],
+        NoteSource::Topic(_) => groups.expand_targets(&note.country_targets),
+    };
+
+    slugs
+        .iter()
+        .filter_map(|slug| resolver.resolve(slug))
+        .collect()
+}
+
+/// Context for `note` templates
+pub fn note_context(
+    note: &Note,
+    owner: &NoteSource,
+    topic: Option<&Topic>,
+    countries: &[CountryMetadata],
+) -> serde_json::Value {
+    let (owner_type, owner_id) = match owner {
+        NoteSource::Country(slug) => ("country", slug),
+        NoteSource::Topic(topic_id) => ("topic", topic_id),
+    };
+
+    json!({
+        "note": note,
+        "owner_type": owner_type,
+        "owner_id": owner_id,
+        "topic": topic,
+        "countries": countries,
+    })
+}
+
+/// Context for `country` templates
+pub fn country_context(
+    country: &CountryMetadata,
+    notes: &[&Note],
+    topic_notes: &[&Note],
+    stats: Option<&CountryStatsRow>,
+) -> serde_json::Value {
+    json!({
+        "country": country,
+        "notes": notes,
+        "topic_notes": topic_notes,
+        "stats": stats,
+    })
+}
+
+/// Context for `topic` templates
+pub fn topic_context(
+    topic: &Topic,
+    countries: &[CountryMetadata],
+    notes: &[&Note],
+) -> serde_json::Value {
+    let last_updated = notes.iter().map(|note| note.date.as_str()).max();
+
+    json!({
+        "topic": topic,
+        "countries": countries,
+        "notes": notes,
+        "stats": {
+            "note_count": notes.len(),
+            "country_count": countries.len(),
+            "last_updated": last_updated,
+            "top_tags": top_tags(notes),
+        },
+    })
+}
+
+/// Most used tags first
+fn top_tags(notes: &[&Note]) -> Vec<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for tag in notes.iter().flat_map(|note| &note.tags) {
+        *counts.entry(tag).or_default() += 1;
+    }
+
+    let mut tags: Vec<(&str, usize)> = counts.into_iter().collect();
+    tags.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    tags.into_iter()
+        .take(TOP_TAGS)
+        .map(|(tag, _)| tag.to_string())
+        .collect()
+}
+
+/// Every template set in the vault with the scopes it covers
+pub fn export_templates(vault_root: &Path) -> Result<Vec<ExportTemplate>, String> {
+    let dir = vault_root.join(EXPORT_TEMPLATES_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut templates = Vec::new();
+    for entry in fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read templates directory: {}", e))?
+        .flatten()
+    {
+        if !entry.path().is_dir() {
+            continue;
+        }
+
+        let mut files = Vec::new();
+        for scope in SCOPES {
+            for (extension, format) in FORMATS {
+                let file_name = format!("{}.{}", scope, extension);
+                if entry.path().join(&file_name).is_file() {
+                    files.push(ExportTemplateFile {
+                        scope: scope.to_string(),
+                        format: format.to_string(),
+                        file_name,
+                    });
+                }
+            }
+        }
+
+        if !files.is_empty() {
+            templates.push(ExportTemplate {
+                name: entry.file_name().to_string_lossy().to_string(),
+                files,
+            });
+        }
+    }
+
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteTemplate {
+    pub id: String,   // File stem, passed to `add_note_from_template`
+    pub name: String, // Defaults to the ID
+    pub title: String,
+    pub tags: Vec<String>,
+    pub country_targets: Vec<String>,
+    pub body: String,
+}
+
+fn parse_note_template(id: &str, content: &str) -> NoteTemplate {
+    let (doc, body) = split_frontmatter(content);
+    let doc = doc.as_ref();
+
+    NoteTemplate {
+        id: id.to_string(),
+        name: yaml_string(doc, "name").unwrap_or_else(|| id.to_string()),
+        title: yaml_string(doc, "title").unwrap_or_default(),
+        tags: yaml_strings(doc, "tags"),
+        country_targets: yaml_strings(doc, "country_targets"),
+        body: body.to_string(),
+    }
+}
+
+pub fn note_templates(vault_root: &Path) -> Result<Vec<NoteTemplate>, String> {
+    let dir = vault_root.join(NOTE_TEMPLATES_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut templates = Vec::new();
+    for entry in fs::read_dir(&dir)
+        .map_err(|e| format!("Failed to read templates directory: {}", e))?
+        .flatten()
+    {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let id = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(id) => id.to_string(),
+            None => continue,
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read template {}: {}", id, e))?;
+        templates.push(parse_note_template(&id, &content));
+    }
+
+    templates.sort_by_key(|t| t.name.to_lowercase());
+    Ok(templates)
+}
+
+pub fn load_note_template(vault_root: &Path, id: &str) -> Result<NoteTemplate, String> {
+    validate_file_name(id)?;
+    let path = vault_root
+        .join(NOTE_TEMPLATES_DIR)
+        .join(format!("{}.md", id));
+    let content =
+        fs::read_to_string(&path).map_err(|_| format!("Note template not found: {}", id))?;
+
+    Ok(parse_note_template(id, &content))
+}
+
+/// Fill in a template's title and body. Placeholders for values that don't
+/// apply (`{{topic.title}}` on a country note) render empty.
+pub fn fill_note_template(
+    template: &NoteTemplate,
+    context: &serde_json::Value,
+) -> Result<(String, String), String> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Chainable);
+
+    let render = |source: &str| {
+        env.render_str(source, context)
+            .map_err(|e| format!("Failed to fill template {}: {}", template.id, e))
+    };
+
+    Ok((render(&template.title)?, render(&template.body)?))
+}
+
+/// Create a note from a template through the regular note flows. Country
+/// notes go to `owner_id`, or the template's first default target; topic
+/// notes target `country_targets`, or the template's defaults.
+pub fn create_note_from_template(
+    vault_root: &Path,
+    template_id: &str,
+    owner_type: &str,
+    owner_id: Option<&str>,
+    country_targets: Option<Vec<String>>,
+) -> Result<TopicNoteCreated, String> {
+    let vault_path = vault_root.to_string_lossy().to_string();
+    let template = load_note_template(vault_root, template_id)?;
+    let resolver = PlaceResolver::load(&vault_path)?;
+    let groups = CountryGroups::load(Some(&vault_path));
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+
+    match owner_type {
+        "country" => {
+            let slug = owner_id
+                .map(str::to_string)
+                .or_else(|| template.country_targets.first().cloned())
+                .ok_or("No country given and the template has no default country")?;
+            let country = resolver.resolve(&slug);
+
+            let (title, body) = fill_note_template(
+                &template,
+                &json!({
+                    "date": date,
+                    "country": country,
+                    "countries": [country],
+                }),
+            )?;
+
+            let note =
+                create_country_note(vault_root, slug.clone(), title, body, template.tags.clone())?;
+            JournalEvent::new("note.created")
+                .entity(&note.id)
+                .country(&slug)
+                .record(vault_root);
+
+            Ok(TopicNoteCreated {
+                note,
+                suggested_targets: Vec::new(),
+            })
+        }
+        "topic" => {
+            let topic_id = owner_id.ok_or("Topic ID is required")?;
+            let manifest = load_topics_manifest(&vault_path)?;
+            let topic = manifest
+                .topics
+                .iter()
+                .find(|t| t.id == topic_id)
+                .ok_or(format!("Topic not found: {}", topic_id))?;
+
+            let targets = country_targets.unwrap_or_else(|| template.country_targets.clone());
+            let countries: Vec<CountryMetadata> = groups
+                .expand_targets(&targets)
+                .iter()
+                .filter_map(|slug| resolver.resolve(slug))
+                .collect();
+
+            let (title, body) = fill_note_template(
+                &template,
+                &json!({
+                    "date": date,
+                    "topic": topic,
+                    "country": countries.first(),
+                    "countries": countries,
+                }),
+            )?;
+
+            let created = create_topic_note(
+                &vault_path,
+                topic_id.to_string(),
+                title,
+                body,
+                template.tags.clone(),
+                targets,
+            )?;
+            JournalEvent::new("note.created")
+                .entity(&created.note.id)
+                .topic(topic_id)
+                .record(vault_root);
+
+            Ok(created)
+        }
+        other => Err(format!("Invalid owner type: {}", other)),
+    }
+}
+
+/// Note templates in `.mapanote/templates/notes/`
+#[tauri::command]
+pub fn list_note_templates(state: State<AppState>) -> Result<Vec<NoteTemplate>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    note_templates(&PathBuf::from(vault_path))
+}
+
+/// Add a country or topic note from a note template, with its default
+/// tags and targets and its placeholders filled in
+#[tauri::command]
+pub fn add_note_from_template(
+    template_id: String,
+    owner_type: String,
+    owner_id: Option<String>,
+    country_targets: Option<Vec<String>>,
+    state: State<AppState>,
+) -> Result<TopicNoteCreated, String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    create_note_from_template(
+        &PathBuf::from(vault_path),
+        &template_id,
+        &owner_type,
+        owner_id.as_deref(),
+        country_targets,
+    )
+}
+
+/// Template sets in `.mapanote/templates/export/` that export commands can
+/// be given as `template`
+#[tauri::command]
+pub fn list_export_templates(state: State<AppState>) -> Result<Vec<ExportTemplate>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+
+    export_templates(&PathBuf::from(vault_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+    use mapanote_lib::models::Visibility;
+
+    #[test]
+    fn test_export_templates() {
+        let vault = TestVault::new();
+        let dir = vault.join(EXPORT_TEMPLATES_DIR).join("customer");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("_tags.html"),
+            "{% for tag in note.tags %}#{{ tag }} {% endfor %}",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("note.html"),
+            "<h1>{{ note.title }}</h1>{% include '_tags.html' %}{{ note.content | markdown }}",
+        )
+        .unwrap();
+        fs::write(dir.join("note.md"), "# {{ note.title }}").unwrap();
+
+        let listed = export_templates(&vault).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].files.len(), 2);
+
+        let note = Note {
+            id: "01A".to_string(),
+            title: "A <b>".to_string(),
+            content: "**bold**".to_string(),
+            date: "2025-01-01".to_string(),
+            tags: vec!["energy".to_string()],
+            topic_id: None,
+            country_targets: Vec::new(),
+            attachments: Vec::new(),
+            visibility: Visibility::default(),
+            created_at: String::new(),
+            updated_at: String::new(),
+            review_by: None,
+            follow_ups: Vec::new(),
+            reviewed: Vec::new(),
+        };
+        let context = note_context(&note, &NoteSource::Country("fi".to_string()), None, &[]);
+
+        let templates = ExportTemplates::load(&vault, "customer", &["html", "md"]).unwrap();
+        let html = templates.render("note", &context).unwrap().unwrap();
+        assert_eq!(
+            html,
+            "<h1>A &lt;b&gt;</h1>#energy <p><strong>bold</strong></p>\n"
+        );
+        assert!(templates.render("country", &context).unwrap().is_none());
+
+        let templates = ExportTemplates::load(&vault, "customer", &["md"]).unwrap();
+        let html = templates.render_html("note", &context).unwrap().unwrap();
+        assert_eq!(html, "<h1>A &lt;b&gt;</h1>\n");
+
+        assert!(ExportTemplates::load(&vault, "missing", &["md"]).is_err());
+    }
+
+    #[test]
+    fn test_note_from_template() {
+        let vault = TestVault::new();
+        let dir = vault.join(NOTE_TEMPLATES_DIR);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("election.md"),
+            "---\nname: Election report\ntitle: \"{{country.name}} election {{date}}\"\ntags: [elections, politics]\ncountry_targets: [fi]\n---\n\n## {{ country.iso3 }}{{ topic.title }}\n\nTurnout:",
+        )
+        .unwrap();
+
+        let templates = note_templates(&vault).unwrap();
+        assert_eq!(templates[0].name, "Election report");
+        assert_eq!(templates[0].country_targets, vec!["fi"]);
+
+        let created = create_note_from_template(&vault, "election", "country", None, None).unwrap();
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        assert_eq!(created.note.title, format!("Finland election {}", today));
+        assert_eq!(created.note.tags, vec!["elections", "politics"]);
+        assert_eq!(created.note.content, "## FIN\n\nTurnout:");
+        assert!(vault
+            .join("notes/fi")
+            .join(format!("{}.md", created.note.id))
+            .exists());
+
+        assert!(create_note_from_template(&vault, "missing", "country", None, None).is_err());
+    }
+}
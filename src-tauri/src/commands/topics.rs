@@ -1,10 +1,20 @@
+use super::attachments::remove_note_attachments;
+use super::detection::{CountryDetector, CountrySuggestion};
+use super::groups::{CountryGroups, GROUP_PREFIX};
+use super::journal::JournalEvent;
+use super::note_index::{load_note_index, paginate, read_indexed_note, NoteSort, Page};
+use super::notes::{
+    format_list, parse_note, set_frontmatter_field, set_note_body, timestamp_now, touch_note,
+};
 use chrono::Utc;
 use mapanote_lib::models::{
     Note, NoteWithSource, Topic, TopicCountryRelation, TopicWithCountries, TopicsManifest,
+    Visibility,
 };
 use mapanote_lib::AppState;
+use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::State;
 use ulid::Ulid;
 
@@ -32,7 +42,7 @@ pub fn load_topics_manifest(vault_path: &str) -> Result<TopicsManifest, String>
 }
 
 /// Save topics manifest
-fn save_topics_manifest(vault_path: &str, manifest: &TopicsManifest) -> Result<(), String> {
+pub fn save_topics_manifest(vault_path: &str, manifest: &TopicsManifest) -> Result<(), String> {
     let topics_path = get_topics_path(vault_path);
     let json = serde_json::to_string_pretty(manifest)
         .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
@@ -144,8 +154,9 @@ pub fn create_topic(
 
     manifest.topics.push(topic.clone());
 
-    // Create relations for each country
-    for country_slug in country_slugs {
+    // Create relations for each country (groups add all of their members)
+    let groups = CountryGroups::load(Some(vault_path));
+    for country_slug in groups.expand_targets(&country_slugs) {
         manifest.relations.push(TopicCountryRelation {
             topic_id: id.clone(),
             country_slug,
@@ -156,6 +167,11 @@ pub fn create_topic(
 
     save_topics_manifest(vault_path, &manifest)?;
 
+    JournalEvent::new("topic.created")
+        .entity(&id)
+        .topic(&id)
+        .record(Path::new(vault_path));
+
     Ok(topic)
 }
 
@@ -187,6 +203,11 @@ pub fn update_topic(
 
     save_topics_manifest(vault_path, &manifest)?;
 
+    JournalEvent::new("topic.updated")
+        .entity(&topic_id)
+        .topic(&topic_id)
+        .record(Path::new(vault_path));
+
     Ok(())
 }
 
@@ -205,6 +226,11 @@ pub fn delete_topic(topic_id: String, state: State<AppState>) -> Result<(), Stri
 
     save_topics_manifest(vault_path, &manifest)?;
 
+    JournalEvent::new("topic.deleted")
+        .entity(&topic_id)
+        .topic(&topic_id)
+        .record(Path::new(vault_path));
+
     Ok(())
 }
 
@@ -219,6 +245,39 @@ pub fn add_country_to_topic(
 
     let mut manifest = load_topics_manifest(vault_path)?;
 
+    // A group adds each of its members that isn't already in the topic
+    let groups = CountryGroups::load(Some(vault_path));
+    if let Some(group) = country_slug
+        .strip_prefix(GROUP_PREFIX)
+        .and_then(|slug| groups.get(slug))
+    {
+        for member in &group.members {
+            let exists = manifest
+                .relations
+                .iter()
+                .any(|r| r.topic_id == topic_id && &r.country_slug == member);
+
+            if !exists {
+                manifest.relations.push(TopicCountryRelation {
+                    topic_id: topic_id.clone(),
+                    country_slug: member.clone(),
+                    note_count: 0,
+                    last_updated: None,
+                });
+            }
+        }
+
+        save_topics_manifest(vault_path, &manifest)?;
+
+        JournalEvent::new("topic.country_added")
+            .entities(&group.members)
+            .country(&country_slug)
+            .topic(&topic_id)
+            .record(Path::new(vault_path));
+
+        return Ok(());
+    }
+
     // Check if relation already exists
     let exists = manifest
         .relations
@@ -230,14 +289,20 @@ pub fn add_country_to_topic(
     }
 
     manifest.relations.push(TopicCountryRelation {
-        topic_id,
-        country_slug,
+        topic_id: topic_id.clone(),
+        country_slug: country_slug.clone(),
         note_count: 0,
         last_updated: None,
     });
 
     save_topics_manifest(vault_path, &manifest)?;
 
+    JournalEvent::new("topic.country_added")
+        .entity(&country_slug)
+        .country(&country_slug)
+        .topic(&topic_id)
+        .record(Path::new(vault_path));
+
     Ok(())
 }
 
@@ -258,6 +323,12 @@ pub fn remove_country_from_topic(
 
     save_topics_manifest(vault_path, &manifest)?;
 
+    JournalEvent::new("topic.country_removed")
+        .entity(&country_slug)
+        .country(&country_slug)
+        .topic(&topic_id)
+        .record(Path::new(vault_path));
+
     Ok(())
 }
 
@@ -289,6 +360,15 @@ pub fn get_topics_for_country(
     Ok(topics)
 }
 
+/// A newly created topic note, with places detected in its text that
+/// the note doesn't target (suggestions only, nothing is applied)
+#[derive(Serialize)]
+pub struct TopicNoteCreated {
+    #[serde(flatten)]
+    pub note: Note,
+    pub suggested_targets: Vec<CountrySuggestion>,
+}
+
 /// Add a note to a topic
 #[tauri::command]
 pub fn add_topic_note(
@@ -298,10 +378,36 @@ pub fn add_topic_note(
     tags: Vec<String>,
     country_targets: Vec<String>, // Which countries this note is about
     state: State<AppState>,
-) -> Result<Note, String> {
+) -> Result<TopicNoteCreated, String> {
     let writer = state.vault_writer.lock().unwrap();
     let vault_path = writer.as_ref().ok_or("No vault opened")?;
 
+    let created = create_topic_note(
+        vault_path,
+        topic_id.clone(),
+        title,
+        content,
+        tags,
+        country_targets,
+    )?;
+
+    JournalEvent::new("note.created")
+        .entity(&created.note.id)
+        .topic(&topic_id)
+        .record(Path::new(vault_path));
+
+    Ok(created)
+}
+
+/// Write a new topic note, suggesting places it mentions but doesn't target
+pub fn create_topic_note(
+    vault_path: &str,
+    topic_id: String,
+    title: String,
+    content: String,
+    tags: Vec<String>,
+    country_targets: Vec<String>,
+) -> Result<TopicNoteCreated, String> {
     let vault_root = PathBuf::from(vault_path);
     let topic_notes_dir = vault_root.join("topics").join(&topic_id);
 
@@ -315,8 +421,8 @@ pub fn add_topic_note(
     let note_path = topic_notes_dir.join(&filename);
 
     // Get current date
-    let now = Utc::now();
-    let date = now.format("%Y-%m-%d").to_string();
+    let date = Utc::now().format("%Y-%m-%d").to_string();
+    let timestamp = timestamp_now();
 
     // Create note
     let note = Note {
@@ -327,61 +433,74 @@ pub fn add_topic_note(
         tags: tags.clone(),
         topic_id: Some(topic_id.clone()),
         country_targets: country_targets.clone(),
+        attachments: Vec::new(),
+        visibility: Visibility::default(),
+        created_at: timestamp.clone(),
+        updated_at: timestamp.clone(),
+        review_by: None,
+        follow_ups: Vec::new(),
+        reviewed: Vec::new(),
     };
 
     // Format note content with country_targets
     let note_content = format!(
-        "---\nid: {}\ntitle: {}\ndate: {}\ntags: [{}]\ntopic_id: {}\ncountry_targets: [{}]\n---\n\n{}",
+        "---\nid: {}\ntitle: {}\ndate: {}\ntags: [{}]\ntopic_id: {}\ncountry_targets: [{}]\ncreated_at: {}\nupdated_at: {}\n---\n\n{}",
         note_id,
         title,
         date,
         tags.join(", "),
         topic_id,
         country_targets.join(", "),
+        timestamp,
+        timestamp,
         content
     );
 
+    // Loaded first, so a broken entities.json fails before anything is written
+    let detector = CountryDetector::load(Some(vault_path))?;
+
     // Write note to file
     fs::write(&note_path, note_content).map_err(|e| format!("Failed to write note: {}", e))?;
 
-    Ok(note)
+    // Suggest places mentioned in the note that aren't targeted yet
+    let targeted = CountryGroups::load(Some(vault_path)).expand_targets(&country_targets);
+    let suggested_targets = detector
+        .detect(&title, &content)
+        .into_iter()
+        .filter(|s| !targeted.contains(&s.slug))
+        .collect();
+
+    Ok(TopicNoteCreated {
+        note,
+        suggested_targets,
+    })
 }
 
-/// Get all notes for a topic (regardless of country targets)
+/// Get a page of a topic's notes (regardless of country targets)
 #[tauri::command]
-pub fn get_topic_notes(topic_id: String, state: State<AppState>) -> Result<Vec<Note>, String> {
+pub fn get_topic_notes(
+    topic_id: String,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    sort: Option<NoteSort>,
+    state: State<AppState>,
+) -> Result<Page<Note>, String> {
     let reader = state.vault_reader.lock().unwrap();
     let vault_path = reader.as_ref().ok_or("No vault opened")?;
 
     let vault_root = PathBuf::from(vault_path);
-    let topic_notes_dir = vault_root.join("topics").join(&topic_id);
-
-    if !topic_notes_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut notes = Vec::new();
-
-    for entry in fs::read_dir(&topic_notes_dir)
-        .map_err(|e| format!("Failed to read topic notes directory: {}", e))?
-    {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) == Some("md") {
-            let content =
-                fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
-
-            if let Some(note) = parse_note(&content) {
-                notes.push(note);
-            }
-        }
-    }
-
-    // Sort by date (newest first)
-    notes.sort_by(|a, b| b.date.cmp(&a.date));
+    let entries = load_note_index(&vault_root)?
+        .into_iter()
+        .filter(|entry| entry.source_type == "topic" && entry.owner_id == topic_id)
+        .collect();
 
-    Ok(notes)
+    paginate(
+        entries,
+        sort.unwrap_or_default(),
+        cursor.as_deref(),
+        limit,
+        |entry| read_indexed_note(&vault_root, entry),
+    )
 }
 
 /// Update a topic note
@@ -412,23 +531,26 @@ pub fn update_topic_note(
     let existing_content =
         fs::read_to_string(&note_path).map_err(|e| format!("Failed to read note: {}", e))?;
 
-    let existing_note = parse_note(&existing_content).ok_or("Failed to parse existing note")?;
+    parse_note(&existing_content).ok_or("Failed to parse existing note")?;
 
-    // Format updated note content
-    let note_content = format!(
-        "---\nid: {}\ntitle: {}\ndate: {}\ntags: [{}]\ntopic_id: {}\ncountry_targets: [{}]\n---\n\n{}",
-        note_id,
-        title,
-        existing_note.date,
-        tags.join(", "),
-        topic_id,
-        country_targets.join(", "),
-        content
+    // Update the edited fields, keeping the rest of the frontmatter (date, attachments...)
+    let note_content = set_frontmatter_field(&existing_content, "title", &title);
+    let note_content = set_frontmatter_field(&note_content, "tags", &format_list(&tags));
+    let note_content = set_frontmatter_field(
+        &note_content,
+        "country_targets",
+        &format_list(&country_targets),
     );
+    let note_content = touch_note(&set_note_body(&note_content, &content));
 
     // Write updated note
     fs::write(&note_path, note_content).map_err(|e| format!("Failed to write note: {}", e))?;
 
+    JournalEvent::new("note.updated")
+        .entity(&note_id)
+        .topic(&topic_id)
+        .record(&vault_root);
+
     Ok(())
 }
 
@@ -452,156 +574,81 @@ pub fn delete_topic_note(
         fs::remove_file(&note_path).map_err(|e| format!("Failed to delete note: {}", e))?;
     }
 
+    remove_note_attachments(&vault_root.join("topics").join(&topic_id), &note_id)?;
+
+    JournalEvent::new("note.deleted")
+        .entity(&note_id)
+        .topic(&topic_id)
+        .record(&vault_root);
+
     Ok(())
 }
 
-/// Get all notes for a country (country notes + topic notes that target this country)
+/// Get a page of a country's notes: its own notes plus topic notes that
+/// target it
 #[tauri::command]
 pub fn get_country_notes_with_topics(
     slug: String,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    sort: Option<NoteSort>,
     state: State<AppState>,
-) -> Result<Vec<NoteWithSource>, String> {
+) -> Result<Page<NoteWithSource>, String> {
     let reader = state.vault_reader.lock().unwrap();
     let vault_path = reader.as_ref().ok_or("No vault opened")?;
 
-    let mut all_notes = Vec::new();
-
-    // 1. Get regular country notes
     let vault_root = PathBuf::from(vault_path);
-    let country_notes_dir = vault_root.join("notes").join(&slug);
-
-    if country_notes_dir.exists() {
-        for entry in fs::read_dir(&country_notes_dir)
-            .map_err(|e| format!("Failed to read notes directory: {}", e))?
-        {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let path = entry.path();
-
-            if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                let content =
-                    fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
-
-                if let Some(note) = parse_note(&content) {
-                    all_notes.push(NoteWithSource {
-                        note,
-                        source_type: "country".to_string(),
-                        source_name: slug.clone(),
-                        topic_color: None,
-                    });
-                }
-            }
-        }
-    }
-
-    // 2. Get topic notes that TARGET this country
     let manifest = load_topics_manifest(vault_path)?;
-    let topic_ids: Vec<String> = manifest
+    let groups = CountryGroups::load(Some(vault_path));
+    let topic_ids: Vec<&str> = manifest
         .relations
         .iter()
         .filter(|r| r.country_slug == slug)
-        .map(|r| r.topic_id.clone())
+        .map(|r| r.topic_id.as_str())
         .collect();
 
-    for topic_id in topic_ids {
-        let topic_notes_dir = vault_root.join("topics").join(&topic_id);
-
-        if !topic_notes_dir.exists() {
-            continue;
-        }
-
-        // Find topic details
-        let topic = manifest.topics.iter().find(|t| t.id == topic_id);
-        let topic_name = topic
-            .as_ref()
-            .map(|t| t.title.clone())
-            .unwrap_or(topic_id.clone());
-        let topic_color = topic.as_ref().and_then(|t| t.color.clone());
-
-        for entry in fs::read_dir(&topic_notes_dir)
-            .map_err(|e| format!("Failed to read topic notes directory: {}", e))?
-        {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let path = entry.path();
-
-            if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                let content =
-                    fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
-
-                if let Some(note) = parse_note(&content) {
-                    // CRITICAL: Only include if this country is in country_targets
-                    if note.country_targets.contains(&slug) {
-                        all_notes.push(NoteWithSource {
-                            note,
-                            source_type: "topic".to_string(),
-                            source_name: topic_name.clone(),
-                            topic_color: topic_color.clone(),
-                        });
-                    }
-                }
+    // Topic notes only count when this country is a target (directly or via a group)
+    let entries = load_note_index(&vault_root)?
+        .into_iter()
+        .filter(|entry| match entry.source_type.as_str() {
+            "country" => entry.owner_id == slug,
+            _ => {
+                topic_ids.contains(&entry.owner_id.as_str())
+                    && groups
+                        .expand_targets(&entry.country_targets)
+                        .contains(&slug)
             }
-        }
-    }
-
-    // Sort by date (newest first)
-    all_notes.sort_by(|a, b| b.note.date.cmp(&a.note.date));
-
-    Ok(all_notes)
-}
-
-// Helper function to parse note with country_targets
-fn parse_note(content: &str) -> Option<Note> {
-    let mut lines = content.lines();
-
-    if lines.next()? != "---" {
-        return None;
-    }
-
-    let mut id = String::new();
-    let mut title = String::new();
-    let mut date = String::new();
-    let mut tags = Vec::new();
-    let mut topic_id = None;
-    let mut country_targets = Vec::new();
-
-    for line in lines.by_ref() {
-        if line == "---" {
-            break;
-        }
-
-        if let Some(value) = line.strip_prefix("id: ") {
-            id = value.to_string();
-        } else if let Some(value) = line.strip_prefix("title: ") {
-            title = value.to_string();
-        } else if let Some(value) = line.strip_prefix("date: ") {
-            date = value.to_string();
-        } else if let Some(value) = line.strip_prefix("tags: ") {
-            let tags_str = value.trim_matches(|c| c == '[' || c == ']');
-            tags = tags_str
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-        } else if let Some(value) = line.strip_prefix("topic_id: ") {
-            topic_id = Some(value.to_string());
-        } else if let Some(value) = line.strip_prefix("country_targets: ") {
-            let targets_str = value.trim_matches(|c| c == '[' || c == ']');
-            country_targets = targets_str
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-        }
-    }
+        })
+        .collect();
 
-    let content: String = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+    paginate(
+        entries,
+        sort.unwrap_or_default(),
+        cursor.as_deref(),
+        limit,
+        |entry| {
+            let Some(note) = read_indexed_note(&vault_root, entry)? else {
+                return Ok(None);
+            };
+
+            if entry.source_type == "country" {
+                return Ok(Some(NoteWithSource {
+                    note,
+                    source_type: "country".to_string(),
+                    source_name: slug.clone(),
+                    topic_color: None,
+                }));
+            }
 
-    Some(Note {
-        id,
-        title,
-        content,
-        date,
-        tags,
-        topic_id,
-        country_targets,
-    })
+            let topic = manifest.topics.iter().find(|t| t.id == entry.owner_id);
+            Ok(Some(NoteWithSource {
+                note,
+                source_type: "topic".to_string(),
+                source_name: topic
+                    .map(|t| t.title.clone())
+                    .unwrap_or(entry.owner_id.clone()),
+                topic_color: topic.and_then(|t| t.color.clone()),
+            }))
+        },
+    )
 }
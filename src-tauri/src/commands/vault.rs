@@ -1,9 +1,20 @@
+use super::assets::{owner_dir, remove_asset, validate_file_name};
+use super::attachments::remove_note_attachments;
+use super::config::write_config;
+use super::entities::PlaceResolver;
+use super::extraction::AttachmentMatch;
+use super::groups::{split_group_filters, CountryGroups, GROUP_PREFIX};
+use super::images::{load_image_settings, store_image};
+use super::journal::JournalEvent;
+use super::note_index::{load_note_index, paginate, read_indexed_note, IndexEntry, NoteSort, Page};
+use super::notes::{parse_note, set_frontmatter_field, set_note_body, timestamp_now, touch_note};
+use super::search::NoteMatcher;
 use base64::{engine::general_purpose, Engine as _};
-use mapanote_lib::models::{CountryStats, Note, VaultManifest};
+use mapanote_lib::models::{CountryStats, Note, VaultManifest, Visibility};
 use mapanote_lib::AppState;
 use serde::Serialize;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::State;
 
 #[tauri::command]
@@ -39,12 +50,7 @@ pub fn create_minimal_vault(destination: String, vault_name: String) -> Result<S
         "created": chrono::Utc::now().to_rfc3339(),
     });
 
-    fs::write(
-        dest_path.join(".mapanote/config.json"),
-        serde_json::to_string_pretty(&config)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?,
-    )
-    .map_err(|e| format!("Failed to write config: {}", e))?;
+    write_config(&dest_path, &config)?;
 
     // Create README
     let readme = format!(
@@ -102,100 +108,31 @@ pub fn get_vault_manifest(state: State<AppState>) -> Result<VaultManifest, Strin
     serde_json::from_str(&manifest_str).map_err(|e| format!("Failed to parse manifest: {}", e))
 }
 
+/// A page of a country's own notes (newest first unless `sort` says otherwise)
 #[tauri::command]
-pub fn get_country_notes(slug: String, state: State<AppState>) -> Result<Vec<Note>, String> {
+pub fn get_country_notes(
+    slug: String,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    sort: Option<NoteSort>,
+    state: State<AppState>,
+) -> Result<Page<Note>, String> {
     let reader = state.vault_reader.lock().unwrap();
     let vault_path = reader.as_ref().ok_or("No vault opened")?;
 
-    let country_dir = PathBuf::from(vault_path).join("notes").join(&slug);
-
-    // If folder doesn't exist, return empty array (not an error)
-    if !country_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut notes = Vec::new();
-
-    for entry in fs::read_dir(&country_dir)
-        .map_err(|e| format!("Failed to read country directory: {}", e))?
-    {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) == Some("md") {
-            let content =
-                fs::read_to_string(&path).map_err(|e| format!("Failed to read note: {}", e))?;
-
-            // Parse frontmatter and content
-            if let Some(note) = parse_note(&content) {
-                notes.push(note);
-            }
-        }
-    }
-
-    // Sort by date descending
-    notes.sort_by(|a, b| b.date.cmp(&a.date));
-
-    Ok(notes)
-}
-
-fn parse_note(content: &str) -> Option<Note> {
-    let mut lines = content.lines();
-
-    if lines.next()? != "---" {
-        return None;
-    }
-
-    let mut id = String::new();
-    let mut title = String::new();
-    let mut date = String::new();
-    let mut tags = Vec::new();
-    let mut topic_id = None; // ← ADD
-    let mut country_targets = Vec::new(); // ← ADD
-
-    for line in lines.by_ref() {
-        if line == "---" {
-            break;
-        }
-
-        if let Some(value) = line.strip_prefix("id: ") {
-            id = value.to_string();
-        } else if let Some(value) = line.strip_prefix("title: ") {
-            title = value.to_string();
-        } else if let Some(value) = line.strip_prefix("date: ") {
-            date = value.to_string();
-        } else if let Some(value) = line.strip_prefix("tags: ") {
-            let tags_str = value.trim_matches(|c| c == '[' || c == ']');
-            tags = tags_str
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-        } else if let Some(value) = line.strip_prefix("topic_id: ") {
-            // ← ADD
-            topic_id = Some(value.to_string());
-        } else if let Some(value) = line.strip_prefix("country_targets: ") {
-            // ← ADD
-            let targets_str = value.trim_matches(|c| c == '[' || c == ']');
-            country_targets = targets_str
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-        }
-    }
+    let vault_root = PathBuf::from(vault_path);
+    let entries = load_note_index(&vault_root)?
+        .into_iter()
+        .filter(|entry| entry.source_type == "country" && entry.owner_id == slug)
+        .collect();
 
-    let content: String = lines.collect::<Vec<_>>().join("\n").trim().to_string();
-
-    Some(Note {
-        id,
-        title,
-        content,
-        date,
-        tags,
-        topic_id,        // ← ADD
-        country_targets, // ← ADD
-    })
+    paginate(
+        entries,
+        sort.unwrap_or_default(),
+        cursor.as_deref(),
+        limit,
+        |entry| read_indexed_note(&vault_root, entry),
+    )
 }
 
 #[tauri::command]
@@ -210,10 +147,28 @@ pub fn add_note(
     let vault_path = writer.as_ref().ok_or("No vault opened")?;
 
     let vault_root = PathBuf::from(vault_path);
+    let note = create_country_note(&vault_root, country_slug.clone(), title, content, tags)?;
+
+    JournalEvent::new("note.created")
+        .entity(&note.id)
+        .country(&country_slug)
+        .record(&vault_root);
+
+    Ok(note)
+}
 
+/// Write a new country note and count it in the manifest
+pub fn create_country_note(
+    vault_root: &Path,
+    country_slug: String,
+    title: String,
+    content: String,
+    tags: Vec<String>,
+) -> Result<Note, String> {
     // Generate note ID and date
     let id = ulid::Ulid::new().to_string();
     let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let now = timestamp_now();
 
     let note = Note {
         id: id.clone(), // ← FIXED: use 'id' not 'note_id'
@@ -223,6 +178,13 @@ pub fn add_note(
         tags: tags.clone(),
         topic_id: None,
         country_targets: vec![country_slug.clone()], // ← FIXED: use 'country_slug' not 'slug'
+        attachments: Vec::new(),
+        visibility: Visibility::default(),
+        created_at: now.clone(),
+        updated_at: now.clone(),
+        review_by: None,
+        follow_ups: Vec::new(),
+        reviewed: Vec::new(),
     };
 
     // Lazy-create country folder
@@ -235,8 +197,8 @@ pub fn add_note(
     let note_path = country_dir.join(&note_filename);
 
     let note_content = format!(
-        "---\nid: {}\ndate: {}\ntitle: {}\ntags: {:?}\ntopic_id: \ncountry_targets: [{}]\n---\n\n{}",
-        id, date, title, tags, country_slug, content
+        "---\nid: {}\ndate: {}\ntitle: {}\ntags: {:?}\ntopic_id: \ncountry_targets: [{}]\ncreated_at: {}\nupdated_at: {}\n---\n\n{}",
+        id, date, title, tags, country_slug, now, now, content
     );
 
     fs::write(&note_path, note_content).map_err(|e| format!("Failed to write note: {}", e))?;
@@ -349,22 +311,28 @@ pub fn update_note(
         return Err(format!("Note {} not found", note_id));
     }
 
-    // Read existing note to get the date
+    // Read existing note to keep its other frontmatter (date, attachments...)
     let existing_content =
         fs::read_to_string(&note_path).map_err(|e| format!("Failed to read note: {}", e))?;
 
-    let existing_date = if let Some(note) = parse_note(&existing_content) {
-        note.date
+    let note_content = if parse_note(&existing_content).is_some() {
+        let updated = set_frontmatter_field(&existing_content, "title", &title);
+        let updated = set_frontmatter_field(&updated, "tags", &format!("{:?}", tags));
+        touch_note(&set_note_body(&updated, &content))
     } else {
-        chrono::Utc::now().format("%Y-%m-%d").to_string()
+        let now = timestamp_now();
+        format!(
+            "---\nid: {}\ndate: {}\ntitle: {}\ntags: {:?}\ncreated_at: {}\nupdated_at: {}\n---\n\n{}",
+            note_id,
+            chrono::Utc::now().format("%Y-%m-%d"),
+            title,
+            tags,
+            now,
+            now,
+            content
+        )
     };
 
-    // Write updated note
-    let note_content = format!(
-        "---\nid: {}\ndate: {}\ntitle: {}\ntags: {:?}\n---\n\n{}",
-        note_id, existing_date, title, tags, content
-    );
-
     fs::write(&note_path, note_content).map_err(|e| format!("Failed to write note: {}", e))?;
 
     // Update manifest tags
@@ -389,6 +357,49 @@ pub fn update_note(
     )
     .map_err(|e| format!("Failed to write manifest: {}", e))?;
 
+    JournalEvent::new("note.updated")
+        .entity(&note_id)
+        .country(&country_slug)
+        .record(&vault_root);
+
+    Ok(())
+}
+
+/// Set who a country or topic note may be shown to (used by exports)
+#[tauri::command]
+pub fn set_note_visibility(
+    owner_type: String,
+    owner_id: String,
+    note_id: String,
+    visibility: Visibility,
+    state: State<AppState>,
+) -> Result<(), String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
+
+    validate_file_name(&note_id)?;
+    let note_path = owner_dir(&PathBuf::from(vault_path), &owner_type, &owner_id)?
+        .join(format!("{}.md", note_id));
+
+    if !note_path.exists() {
+        return Err(format!("Note {} not found", note_id));
+    }
+
+    let content =
+        fs::read_to_string(&note_path).map_err(|e| format!("Failed to read note: {}", e))?;
+    let updated = touch_note(&set_frontmatter_field(
+        &content,
+        "visibility",
+        visibility.as_str(),
+    ));
+
+    fs::write(&note_path, updated).map_err(|e| format!("Failed to write note: {}", e))?;
+
+    JournalEvent::new("note.visibility_changed")
+        .entity(&note_id)
+        .owner(&owner_type, &owner_id)
+        .record(Path::new(vault_path));
+
     Ok(())
 }
 
@@ -414,6 +425,8 @@ pub fn delete_note(
         return Err(format!("Note {} not found", note_id));
     }
 
+    remove_note_attachments(&vault_root.join("notes").join(&country_slug), &note_id)?;
+
     // Update manifest
     let manifest_path = vault_root.join("vault.json");
     let manifest_str = fs::read_to_string(&manifest_path)
@@ -440,18 +453,26 @@ pub fn delete_note(
     )
     .map_err(|e| format!("Failed to write manifest: {}", e))?;
 
+    JournalEvent::new("note.deleted")
+        .entity(&note_id)
+        .country(&country_slug)
+        .record(&vault_root);
+
     Ok(())
 }
 
 #[derive(Serialize)]
 pub struct SearchResult {
-    pub country_slug: String,
-    pub country_name: String,
+    pub source_type: String,  // "country" or "topic"
+    pub country_slug: String, // Topic ID for topic notes
+    pub country_name: String, // Topic title for topic notes
     pub note_id: String,
     pub note_title: String,
     pub note_date: String,
     pub snippet: String,
     pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment_match: Option<AttachmentMatch>, // Set when only an attachment's text matched
 }
 
 #[derive(Serialize)]
@@ -464,88 +485,121 @@ pub struct RecentActivity {
     pub source_type: String, // "country" or "topic"
     pub topic_name: Option<String>,
     pub topic_color: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub activity: String, // "created" or "edited"
 }
 
-#[tauri::command]
-pub fn search_notes(query: String, state: State<AppState>) -> Result<Vec<SearchResult>, String> {
-    let reader = state.vault_reader.lock().unwrap();
-    let vault_path = reader.as_ref().ok_or("No vault opened")?;
-
-    let vault_root = PathBuf::from(vault_path);
-    let notes_dir = vault_root.join("notes");
+impl RecentActivity {
+    fn new(entry: &IndexEntry, country_slug: String, country_name: String) -> Self {
+        let activity = if parse_timestamp(&entry.updated_at) > parse_timestamp(&entry.created_at) {
+            "edited"
+        } else {
+            "created"
+        };
 
-    if !notes_dir.exists() {
-        return Ok(Vec::new());
+        RecentActivity {
+            note_id: entry.id.clone(),
+            note_title: entry.title.clone(),
+            note_date: entry.date.clone(),
+            country_slug,
+            country_name,
+            source_type: entry.source_type.clone(),
+            topic_name: None,
+            topic_color: None,
+            created_at: entry.created_at.clone(),
+            updated_at: entry.updated_at.clone(),
+            activity: activity.to_string(),
+        }
     }
+}
 
-    let query_lower = query.to_lowercase();
-    let mut results = Vec::new();
-
-    // Iterate through country folders
-    for country_entry in
-        fs::read_dir(&notes_dir).map_err(|e| format!("Failed to read notes directory: {}", e))?
-    {
-        let country_entry = country_entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let country_path = country_entry.path();
-
-        if !country_path.is_dir() {
-            continue;
-        }
+/// Parse an RFC3339 frontmatter timestamp, whatever offset it was written with
+fn parse_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&chrono::Utc))
+}
 
-        let country_slug = country_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
+/// Search country and topic notes by title, body, place name (or topic
+/// title) or attachment text, a page at a time
+#[tauri::command]
+pub fn search_notes(
+    query: String,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    sort: Option<NoteSort>,
+    state: State<AppState>,
+) -> Result<Page<SearchResult>, String> {
+    let reader = state.vault_reader.lock().unwrap();
+    let vault_path = reader.as_ref().ok_or("No vault opened")?;
 
-        // Get country name from metadata
-        let country_name = country_slug.clone(); // Fallback to slug
+    let vault_root = PathBuf::from(vault_path);
 
-        // Iterate through notes in this country
-        for note_entry in fs::read_dir(&country_path)
-            .map_err(|e| format!("Failed to read country directory: {}", e))?
-        {
-            let note_entry = note_entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let note_path = note_entry.path();
+    // `group:<slug>` terms restrict results to the group's member countries
+    let (group_filters, query) = split_group_filters(&query);
+    let groups = CountryGroups::load(Some(vault_path));
+    let group_members: Option<Vec<String>> = if group_filters.is_empty() {
+        None
+    } else {
+        Some(
+            groups.expand_targets(
+                &group_filters
+                    .iter()
+                    .map(|slug| format!("{}{}", GROUP_PREFIX, slug))
+                    .collect::<Vec<_>>(),
+            ),
+        )
+    };
 
-            if note_path.extension().and_then(|s| s.to_str()) != Some("md") {
-                continue;
-            }
+    let mut matcher = NoteMatcher::new(&vault_root, &query)?;
 
-            let content = fs::read_to_string(&note_path)
-                .map_err(|e| format!("Failed to read note: {}", e))?;
-
-            if let Some(note) = parse_note(&content) {
-                // Check if query matches title or content
-                let title_lower = note.title.to_lowercase();
-                let content_lower = note.content.to_lowercase();
-
-                if title_lower.contains(&query_lower) || content_lower.contains(&query_lower) {
-                    // Create snippet (first 150 chars of content)
-                    let snippet = if note.content.len() > 150 {
-                        format!("{}...", &note.content[..150])
-                    } else {
-                        note.content.clone()
-                    };
-
-                    results.push(SearchResult {
-                        country_slug: country_slug.clone(),
-                        country_name: country_name.clone(),
-                        note_id: note.id,
-                        note_title: note.title,
-                        note_date: note.date,
-                        snippet,
-                        tags: note.tags,
-                    });
+    let entries = load_note_index(&vault_root)?
+        .into_iter()
+        .filter(|entry| {
+            group_members.as_ref().is_none_or(|members| {
+                if entry.source_type == "country" {
+                    members.contains(&entry.owner_id)
+                } else {
+                    groups
+                        .expand_targets(&entry.country_targets)
+                        .iter()
+                        .any(|slug| members.contains(slug))
                 }
-            }
-        }
-    }
+            })
+        })
+        .collect();
 
-    // Sort by date (newest first)
-    results.sort_by(|a, b| b.note_date.cmp(&a.note_date));
+    paginate(
+        entries,
+        sort.unwrap_or_default(),
+        cursor.as_deref(),
+        limit,
+        |entry| {
+            let note = match read_indexed_note(&vault_root, entry)? {
+                Some(note) => note,
+                None => return Ok(None),
+            };
 
-    Ok(results)
+            // A bare group filter lists every note of its members
+            let found = match matcher.find(&entry.source_type, &entry.owner_id, &note)? {
+                Some(found) => found,
+                None => return Ok(None),
+            };
+
+            Ok(Some(SearchResult {
+                source_type: entry.source_type.clone(),
+                country_slug: entry.owner_id.clone(),
+                country_name: found.owner_name,
+                note_id: note.id,
+                note_title: note.title,
+                note_date: note.date,
+                snippet: found.snippet,
+                tags: note.tags,
+                attachment_match: found.attachment_match,
+            }))
+        },
+    )
 }
 
 #[tauri::command]
@@ -568,6 +622,12 @@ pub fn get_all_countries_with_combined_counts(
     let topics_manifest = crate::commands::topics::load_topics_manifest(vault_path)
         .unwrap_or_else(|_| mapanote_lib::models::TopicsManifest::new());
 
+    // Group targets count towards each member country
+    let groups = CountryGroups::load(Some(vault_path));
+
+    // Countries gone quiet for longer than the staleness threshold
+    let stale = super::coverage::stale_country_slugs(&vault_root);
+
     // Build a set of ALL country slugs that have either:
     // 1. Country notes (in vault.json)
     // 2. Topic notes targeting them
@@ -590,7 +650,7 @@ pub fn get_all_countries_with_combined_counts(
                         if let Ok(content) = fs::read_to_string(&path) {
                             if let Some(note) = parse_note(&content) {
                                 // Add all country targets from this note
-                                for target in note.country_targets {
+                                for target in groups.expand_targets(&note.country_targets) {
                                     all_country_slugs.insert(target);
                                 }
                             }
@@ -603,13 +663,17 @@ pub fn get_all_countries_with_combined_counts(
 
     let mut countries: Vec<mapanote_lib::models::CountryWithStats> = Vec::new();
 
+    // Resolve slugs against embedded countries and vault-defined entities
+    let resolver = PlaceResolver::load(vault_path)?;
+
     // Process each country slug
     for slug in all_country_slugs {
         // Get base metadata
-        let metadata = match crate::get_country_metadata(slug.clone()) {
-            Ok(meta) => meta,
-            Err(_) => continue, // Skip if metadata not found
+        let metadata = match resolver.resolve(&slug) {
+            Some(meta) => meta,
+            None => continue, // Skip if metadata not found
         };
+        let entity = resolver.entity(&slug);
 
         // Get country notes count (from vault.json)
         let country_note_count = manifest
@@ -639,7 +703,8 @@ pub fn get_all_countries_with_combined_counts(
                             if let Ok(content) = fs::read_to_string(&path) {
                                 if let Some(note) = parse_note(&content) {
                                     // Only count if this country is in the targets
-                                    if note.country_targets.contains(&slug) {
+                                    if groups.expand_targets(&note.country_targets).contains(&slug)
+                                    {
                                         topic_note_count += 1;
                                     }
                                 }
@@ -663,9 +728,12 @@ pub fn get_all_countries_with_combined_counts(
                 summary: metadata.summary,
                 region: metadata.region,
                 subregion: metadata.subregion,
+                kind: entity.map(|e| e.kind.as_str().to_string()),
+                map_ref: entity.and_then(|e| e.map_ref.clone()),
                 note_count: total_note_count,
                 last_updated,
                 tags,
+                stale: stale.contains(&slug),
             });
         }
     }
@@ -675,133 +743,63 @@ pub fn get_all_countries_with_combined_counts(
     Ok(countries)
 }
 
-// Add this command at the end of vault.rs
+/// Recently created or edited notes, served from the note index without
+/// reading any note bodies
 #[tauri::command]
 pub fn get_recent_activity(
-    // ← Make sure it has 'pub'
-    limit: usize,
+    limit: Option<usize>,
+    cursor: Option<String>,
+    sort: Option<NoteSort>,
     state: State<AppState>,
-) -> Result<Vec<RecentActivity>, String> {
+) -> Result<Page<RecentActivity>, String> {
     let reader = state.vault_reader.lock().unwrap();
     let vault_path = reader.as_ref().ok_or("No vault opened")?;
 
     let vault_root = PathBuf::from(vault_path);
-    let mut activities: Vec<RecentActivity> = Vec::new();
-
-    // 1. Collect all country notes
-    let notes_dir = vault_root.join("notes");
-    if notes_dir.exists() {
-        for country_entry in fs::read_dir(&notes_dir)
-            .map_err(|e| format!("Failed to read notes directory: {}", e))?
-        {
-            let country_entry =
-                country_entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let country_path = country_entry.path();
-
-            if !country_path.is_dir() {
-                continue;
-            }
-
-            let country_slug = country_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            // Get country metadata
-            let country_metadata = match crate::get_country_metadata(country_slug.clone()) {
-                Ok(meta) => meta,
-                Err(_) => continue,
-            };
-
-            // Read notes in this country
-            for note_entry in fs::read_dir(&country_path)
-                .map_err(|e| format!("Failed to read country directory: {}", e))?
-            {
-                let note_entry = note_entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-                let note_path = note_entry.path();
-
-                if note_path.extension().and_then(|s| s.to_str()) != Some("md") {
-                    continue;
-                }
-
-                let content = fs::read_to_string(&note_path)
-                    .map_err(|e| format!("Failed to read note: {}", e))?;
-
-                if let Some(note) = parse_note(&content) {
-                    activities.push(RecentActivity {
-                        note_id: note.id,
-                        note_title: note.title,
-                        note_date: note.date,
-                        country_slug: country_slug.clone(),
-                        country_name: country_metadata.name.clone(),
-                        source_type: "country".to_string(),
-                        topic_name: None,
-                        topic_color: None,
-                    });
-                }
-            }
-        }
-    }
-
-    // 2. Collect all topic notes
+    let resolver = PlaceResolver::load(vault_path)?;
     let topics_manifest = crate::commands::topics::load_topics_manifest(vault_path)
         .unwrap_or_else(|_| mapanote_lib::models::TopicsManifest::new());
-
-    for topic in &topics_manifest.topics {
-        let topic_notes_dir = vault_root.join("topics").join(&topic.id);
-
-        if !topic_notes_dir.exists() {
-            continue;
-        }
-
-        for note_entry in fs::read_dir(&topic_notes_dir)
-            .map_err(|e| format!("Failed to read topic notes directory: {}", e))?
-        {
-            let note_entry = note_entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let note_path = note_entry.path();
-
-            if note_path.extension().and_then(|s| s.to_str()) != Some("md") {
-                continue;
-            }
-
-            let content = fs::read_to_string(&note_path)
-                .map_err(|e| format!("Failed to read note: {}", e))?;
-
-            if let Some(note) = parse_note(&content) {
-                // Get first country target as the primary country
-                let primary_country_slug = note
-                    .country_targets
-                    .first()
-                    .cloned()
-                    .unwrap_or_else(|| "unknown".to_string());
-
-                let country_name = match crate::get_country_metadata(primary_country_slug.clone()) {
-                    Ok(meta) => meta.name,
-                    Err(_) => primary_country_slug.clone(),
-                };
-
-                activities.push(RecentActivity {
-                    note_id: note.id,
-                    note_title: note.title,
-                    note_date: note.date,
-                    country_slug: primary_country_slug,
-                    country_name,
-                    source_type: "topic".to_string(),
-                    topic_name: Some(topic.title.clone()),
-                    topic_color: topic.color.clone(),
-                });
+    let groups = CountryGroups::load(Some(vault_path));
+
+    paginate(
+        load_note_index(&vault_root)?,
+        sort.unwrap_or(NoteSort::Updated),
+        cursor.as_deref(),
+        limit,
+        |entry| {
+            if entry.source_type == "country" {
+                // Skip folders that aren't a known place
+                return Ok(resolver.resolve(&entry.owner_id).map(|meta| {
+                    RecentActivity::new(entry, entry.owner_id.clone(), meta.name.clone())
+                }));
             }
-        }
-    }
 
-    // Sort by date (newest first)
-    activities.sort_by(|a, b| b.note_date.cmp(&a.note_date));
-
-    // Limit results
-    activities.truncate(limit);
+            let Some(topic) = topics_manifest
+                .topics
+                .iter()
+                .find(|t| t.id == entry.owner_id)
+            else {
+                return Ok(None);
+            };
 
-    Ok(activities)
+            // Get first country target as the primary country
+            let primary_country_slug = entry
+                .country_targets
+                .first()
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string());
+
+            let country_name = groups
+                .target_name(&primary_country_slug)
+                .unwrap_or_else(|| resolver.name(&primary_country_slug));
+
+            Ok(Some(RecentActivity {
+                topic_name: Some(topic.title.clone()),
+                topic_color: topic.color.clone(),
+                ..RecentActivity::new(entry, primary_country_slug, country_name)
+            }))
+        },
+    )
 }
 
 /// Save an image to the vault's assets folder
@@ -809,7 +807,6 @@ pub fn get_recent_activity(
 pub fn save_note_image(
     country_slug: String,
     image_data: String, // Base64 encoded image
-    filename: String,
     state: State<AppState>,
 ) -> Result<String, String> {
     let writer = state.vault_writer.lock().unwrap();
@@ -818,29 +815,22 @@ pub fn save_note_image(
     let vault_root = PathBuf::from(vault_path);
     let assets_dir = vault_root.join("notes").join(&country_slug).join("assets");
 
-    // Create assets directory if it doesn't exist
-    fs::create_dir_all(&assets_dir)
-        .map_err(|e| format!("Failed to create assets directory: {}", e))?;
-
     // Decode base64 image data
     let image_bytes = general_purpose::STANDARD
         .decode(image_data)
         .map_err(|e| format!("Failed to decode image data: {}", e))?;
 
-    // Generate unique filename if needed
-    let final_filename = if filename.is_empty() {
-        format!("image_{}.png", ulid::Ulid::new())
-    } else {
-        filename
-    };
+    // Strip metadata, downscale and store under a content hash
+    let settings = load_image_settings(vault_path);
+    let filename = store_image(&assets_dir, &image_bytes, &settings)?;
 
-    // Save image file
-    let image_path = assets_dir.join(&final_filename);
-    fs::write(&image_path, image_bytes)
-        .map_err(|e| format!("Failed to write image file: {}", e))?;
+    JournalEvent::new("image.added")
+        .entity(&filename)
+        .country(&country_slug)
+        .record(&vault_root);
 
     // Return relative path for markdown
-    Ok(format!("assets/{}", final_filename))
+    Ok(format!("assets/{}", filename))
 }
 
 /// Save an image to a topic note's assets folder
@@ -848,7 +838,6 @@ pub fn save_note_image(
 pub fn save_topic_image(
     topic_id: String,
     image_data: String, // Base64 encoded image
-    filename: String,
     state: State<AppState>,
 ) -> Result<String, String> {
     let writer = state.vault_writer.lock().unwrap();
@@ -857,32 +846,25 @@ pub fn save_topic_image(
     let vault_root = PathBuf::from(vault_path);
     let assets_dir = vault_root.join("topics").join(&topic_id).join("assets");
 
-    // Create assets directory if it doesn't exist
-    fs::create_dir_all(&assets_dir)
-        .map_err(|e| format!("Failed to create assets directory: {}", e))?;
-
     // Decode base64 image data
     let image_bytes = general_purpose::STANDARD
         .decode(image_data)
         .map_err(|e| format!("Failed to decode image data: {}", e))?;
 
-    // Generate unique filename if needed
-    let final_filename = if filename.is_empty() {
-        format!("image_{}.png", ulid::Ulid::new())
-    } else {
-        filename
-    };
+    // Strip metadata, downscale and store under a content hash
+    let settings = load_image_settings(vault_path);
+    let filename = store_image(&assets_dir, &image_bytes, &settings)?;
 
-    // Save image file
-    let image_path = assets_dir.join(&final_filename);
-    fs::write(&image_path, image_bytes)
-        .map_err(|e| format!("Failed to write image file: {}", e))?;
+    JournalEvent::new("image.added")
+        .entity(&filename)
+        .topic(&topic_id)
+        .record(&vault_root);
 
     // Return relative path for markdown
-    Ok(format!("assets/{}", final_filename))
+    Ok(format!("assets/{}", filename))
 }
 
-/// Delete an image from assets
+/// Delete an image (and its thumbnail) from a country's assets
 #[tauri::command]
 pub fn delete_note_image(
     country_slug: String,
@@ -893,73 +875,37 @@ pub fn delete_note_image(
     let vault_path = writer.as_ref().ok_or("No vault opened")?;
 
     let vault_root = PathBuf::from(vault_path);
-    let image_path = vault_root
-        .join("notes")
-        .join(&country_slug)
-        .join("assets")
-        .join(&image_filename);
-
-    if image_path.exists() {
-        fs::remove_file(&image_path).map_err(|e| format!("Failed to delete image: {}", e))?;
-    }
-
-    Ok(())
-}
-
-/// Get image as base64 for display
-#[tauri::command]
-pub fn get_note_image(
-    country_slug: String,
-    image_filename: String,
-    state: State<AppState>,
-) -> Result<String, String> {
-    let reader = state.vault_reader.lock().unwrap();
-    let vault_path = reader.as_ref().ok_or("No vault opened")?;
-
-    let vault_root = PathBuf::from(vault_path);
-    let image_path = vault_root
-        .join("notes")
-        .join(&country_slug)
-        .join("assets")
-        .join(&image_filename);
-
-    if !image_path.exists() {
-        return Err(format!("Image not found: {}", image_filename));
-    }
+    let assets_dir = vault_root.join("notes").join(&country_slug).join("assets");
 
-    let image_bytes = fs::read(&image_path).map_err(|e| format!("Failed to read image: {}", e))?;
+    remove_asset(&assets_dir, &image_filename)?;
 
-    // Encode as base64
-    let base64_data = general_purpose::STANDARD.encode(image_bytes);
+    JournalEvent::new("image.deleted")
+        .entity(&image_filename)
+        .country(&country_slug)
+        .record(&vault_root);
 
-    Ok(base64_data)
+    Ok(())
 }
 
-/// Get topic image as base64 for display
+/// Delete an image (and its thumbnail) from a topic's assets
 #[tauri::command]
-pub fn get_topic_image(
+pub fn delete_topic_image(
     topic_id: String,
     image_filename: String,
     state: State<AppState>,
-) -> Result<String, String> {
-    let reader = state.vault_reader.lock().unwrap();
-    let vault_path = reader.as_ref().ok_or("No vault opened")?;
+) -> Result<(), String> {
+    let writer = state.vault_writer.lock().unwrap();
+    let vault_path = writer.as_ref().ok_or("No vault opened")?;
 
     let vault_root = PathBuf::from(vault_path);
-    let image_path = vault_root
-        .join("topics")
-        .join(&topic_id)
-        .join("assets")
-        .join(&image_filename);
-
-    if !image_path.exists() {
-        return Err(format!("Image not found: {}", image_filename));
-    }
+    let assets_dir = vault_root.join("topics").join(&topic_id).join("assets");
 
-    let image_bytes = fs::read(&image_path).map_err(|e| format!("Failed to read image: {}", e))?;
+    remove_asset(&assets_dir, &image_filename)?;
 
-    // Encode as base64
-    let base64_data = general_purpose::STANDARD.encode(image_bytes);
+    JournalEvent::new("image.deleted")
+        .entity(&image_filename)
+        .topic(&topic_id)
+        .record(&vault_root);
 
-    Ok(base64_data)
+    Ok(())
 }
@@ -1,16 +1,29 @@
 mod commands;
+mod protocol;
+#[cfg(test)]
+mod test_support;
 
 use commands::{
-    add_country_to_topic, add_note, add_topic_note, create_minimal_vault, create_topic,
-    delete_note, delete_note_image, delete_topic, delete_topic_note, get_all_countries_metadata,
+    add_attachment, add_country_to_topic, add_note, add_note_from_template, add_topic_note,
+    collect_garbage, compact_journal, create_entity, create_minimal_vault, create_topic,
+    delete_country_group, delete_entity, delete_note, delete_note_image, delete_saved_search,
+    delete_topic, delete_topic_image, delete_topic_note, export_country_stats, export_static_site,
+    export_vault_data, generate_briefing, get_all_countries_metadata,
     get_all_countries_with_combined_counts, get_all_country_stats, get_all_topics,
-    get_country_metadata, get_country_notes, get_country_notes_with_topics, get_note_image,
-    get_recent_activity, get_topic, get_topic_image, get_topic_notes, get_topics_for_country,
-    get_vault_manifest, list_countries, open_vault, remove_country_from_topic, save_note_image,
-    save_topic_image, search_notes, update_note, update_topic, update_topic_note,
+    get_attachment_path, get_country_groups, get_country_metadata, get_country_metadata_by_code,
+    get_country_notes, get_country_notes_with_topics, get_due_reviews, get_entities, get_journal,
+    get_notes_by_tag, get_recent_activity, get_stale_coverage, get_topic, get_topic_notes,
+    get_topics_for_country, get_vault_analytics, get_vault_manifest, import_events,
+    import_markdown_folder, import_vault_data, list_assets, list_attachments, list_countries,
+    list_export_templates, list_note_templates, list_saved_searches, list_tags, mark_reviewed,
+    merge_tags, open_vault, remove_attachment, remove_country_from_topic, rename_asset, rename_tag,
+    run_saved_search, save_country_group, save_note_image, save_search, save_topic_image,
+    search_notes, set_note_review, set_note_visibility, suggest_countries_for_text, update_entity,
+    update_note, update_topic, update_topic_note,
 };
 use mapanote_lib::AppState;
 use std::sync::Mutex;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 fn main() {
@@ -21,6 +34,22 @@ fn main() {
             vault_reader: Mutex::new(None),
             vault_writer: Mutex::new(None),
         })
+        .register_asynchronous_uri_scheme_protocol(
+            protocol::ASSET_SCHEME,
+            |ctx, request, responder| {
+                let app = ctx.app_handle().clone();
+
+                // Read files off the main thread, holding the vault lock only
+                // long enough to copy the path, so large media never blocks commands
+                std::thread::spawn(move || {
+                    let vault_path = app.state::<AppState>().vault_reader.lock().unwrap().clone();
+                    responder.respond(protocol::handle_asset_request(
+                        vault_path.as_deref(),
+                        &request,
+                    ));
+                });
+            },
+        )
         .invoke_handler(tauri::generate_handler![
             open_vault,
             create_minimal_vault,
@@ -30,10 +59,13 @@ fn main() {
             get_all_country_stats,
             get_all_countries_metadata,
             get_country_metadata,
+            get_country_metadata_by_code,
             add_note,
             update_note,
+            set_note_visibility,
             delete_note,
             search_notes,
+            suggest_countries_for_text,
             get_all_topics,
             get_topic,
             create_topic,
@@ -52,8 +84,46 @@ fn main() {
             save_note_image,
             save_topic_image,
             delete_note_image,
-            get_note_image,
-            get_topic_image,
+            delete_topic_image,
+            list_assets,
+            rename_asset,
+            collect_garbage,
+            add_attachment,
+            list_attachments,
+            get_attachment_path,
+            remove_attachment,
+            import_markdown_folder,
+            import_events,
+            export_static_site,
+            export_vault_data,
+            import_vault_data,
+            export_country_stats,
+            generate_briefing,
+            list_export_templates,
+            list_note_templates,
+            add_note_from_template,
+            save_search,
+            list_saved_searches,
+            run_saved_search,
+            delete_saved_search,
+            get_journal,
+            compact_journal,
+            get_vault_analytics,
+            get_stale_coverage,
+            get_due_reviews,
+            set_note_review,
+            mark_reviewed,
+            list_tags,
+            rename_tag,
+            merge_tags,
+            get_notes_by_tag,
+            get_entities,
+            create_entity,
+            update_entity,
+            delete_entity,
+            get_country_groups,
+            save_country_group,
+            delete_country_group,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
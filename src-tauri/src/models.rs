@@ -33,9 +33,168 @@ pub struct CountryMetadata {
     pub name: String,
     pub iso2: String,
     pub iso3: String,
+    #[serde(default)]
+    pub iso_numeric: String, // Zero-padded ISO 3166-1 numeric code, e.g. "004"
     pub summary: String,
     pub region: String,
     pub subregion: String,
+    #[serde(default)]
+    pub aliases: Vec<String>, // Official and local names ("Suomi", "Republic of Finland")
+    #[serde(default)]
+    pub demonyms: Vec<String>, // "Finnish", "Finn"
+    #[serde(default)]
+    pub capital: Option<String>,
+    #[serde(default)]
+    pub centroid: Option<Coordinates>,
+    #[serde(default)]
+    pub bbox: Option<BoundingBox>,
+    #[serde(default)]
+    pub currencies: Vec<Currency>,
+    #[serde(default)]
+    pub languages: Vec<Language>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Coordinates {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Geographic extent in degrees. For countries crossing the antimeridian
+/// (e.g. Russia, Fiji) `min_lon` is greater than `max_lon`, as in GeoJSON.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Currency {
+    pub code: String, // ISO 4217
+    pub name: String,
+    pub symbol: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Language {
+    pub code: String, // ISO 639-3
+    pub name: String,
+}
+
+/// A vault-defined place that is not in the embedded country list
+/// (disputed territories, regions, organisations, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeoEntity {
+    pub slug: String,
+    pub name: String,
+    pub kind: EntityKind,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>, // Slug of a country or another entity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map_ref: Option<String>, // Map shape to highlight (embedded country slug)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub centroid: Option<Coordinates>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    Country,
+    Territory,
+    Region,
+    Organization,
+    Other,
+}
+
+impl EntityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityKind::Country => "country",
+            EntityKind::Territory => "territory",
+            EntityKind::Region => "region",
+            EntityKind::Organization => "organization",
+            EntityKind::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitiesManifest {
+    pub version: String,
+    pub entities: Vec<GeoEntity>,
+}
+
+impl EntitiesManifest {
+    pub fn new() -> Self {
+        Self {
+            version: "1.0".to_string(),
+            entities: Vec::new(),
+        }
+    }
+}
+
+impl Default for EntitiesManifest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A named set of countries (bloc, alliance, region) that can be used
+/// as `group:<slug>` in note targets and search filters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryGroup {
+    pub slug: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub members: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupsManifest {
+    pub version: String,
+    pub groups: Vec<CountryGroup>,
+}
+
+impl GroupsManifest {
+    pub fn new() -> Self {
+        Self {
+            version: "1.0".to_string(),
+            groups: Vec::new(),
+        }
+    }
+}
+
+impl Default for GroupsManifest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Image ingestion settings, read from the `images` section of
+/// `.mapanote/config.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageSettings {
+    pub max_dimension: u32, // Longest side in pixels; larger images are downscaled
+    pub thumbnail_size: u32, // Longest side of generated thumbnails (0 disables them)
+    pub jpeg_quality: u8,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            max_dimension: 2560,
+            thumbnail_size: 320,
+            jpeg_quality: 85,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,10 +206,16 @@ pub struct CountryWithStats {
     pub summary: String,
     pub region: String,
     pub subregion: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>, // Set for vault-defined entities
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub map_ref: Option<String>,
     // Stats fields
     pub note_count: usize,
     pub last_updated: Option<String>,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub stale: bool, // Latest note older than the staleness threshold
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +229,66 @@ pub struct Note {
     pub topic_id: Option<String>, // Which topic this belongs to (if any)
     #[serde(default)]
     pub country_targets: Vec<String>, // Which countries this note is about
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>, // Files stored in attachments/<note_id>/
+    #[serde(default)]
+    pub visibility: Visibility,
+    #[serde(default)]
+    pub created_at: String, // RFC3339; legacy notes fall back to the ULID timestamp
+    #[serde(default)]
+    pub updated_at: String, // RFC3339, bumped by every write
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_by: Option<String>, // YYYY-MM-DD the assessment should be revisited by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub follow_ups: Vec<FollowUp>, // Checklist of things to check at review
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviewed: Vec<String>, // RFC3339 time of every review, oldest first
+}
+
+/// Who a note may be shown to outside the app (`visibility:` in frontmatter)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Private,
+    #[default]
+    Internal,
+    Publishable,
+}
+
+impl Visibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Private => "private",
+            Visibility::Internal => "internal",
+            Visibility::Publishable => "publishable",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "private" => Some(Visibility::Private),
+            "internal" => Some(Visibility::Internal),
+            "publishable" => Some(Visibility::Publishable),
+            _ => None,
+        }
+    }
+}
+
+/// A file kept next to a note, recorded in its frontmatter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub name: String, // File name inside the note's attachments folder
+    pub mime: String,
+    pub size: u64,
+    pub added: String, // YYYY-MM-DD
+}
+
+/// One item of a note's follow-up checklist, recorded in its frontmatter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FollowUp {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
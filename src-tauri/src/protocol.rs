@@ -0,0 +1,371 @@
+use percent_encoding::percent_decode_str;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tauri::http::{header, Request, Response, StatusCode};
+
+/// URI scheme serving files from the open vault's asset and attachment folders:
+/// `mapanote-asset://country/fi/assets/map.png`,
+/// `mapanote-asset://topic/<topic_id>/assets/map.png` or
+/// `mapanote-asset://country/fi/attachments/<note_id>/report.pdf`
+pub const ASSET_SCHEME: &str = "mapanote-asset";
+
+/// Largest body of a single response; players and PDF viewers ask for the
+/// next range as they go, so a large file is never read into memory whole
+pub const MAX_RANGE_LEN: u64 = 4 * 1024 * 1024;
+
+/// Map a request path (`country/fi/assets/x.png`) to a file inside the vault,
+/// rejecting anything that could escape the asset and attachment folders
+pub fn resolve_asset_path(vault_root: &Path, request_path: &str) -> Option<PathBuf> {
+    let decoded = percent_decode_str(request_path).decode_utf8().ok()?;
+    let segments: Vec<&str> = decoded.trim_matches('/').split('/').collect();
+
+    if segments.len() < 4 || !matches!(segments[2], "assets" | "attachments") {
+        return None;
+    }
+
+    let safe = segments
+        .iter()
+        .all(|s| !s.is_empty() && *s != "." && *s != ".." && !s.contains(['\\', ':', '\0']));
+    if !safe {
+        return None;
+    }
+
+    let base = match segments[0] {
+        "country" => "notes",
+        "topic" => "topics",
+        _ => return None,
+    };
+
+    let mut path = vault_root.join(base);
+    for segment in &segments[1..] {
+        path.push(segment);
+    }
+
+    // Symlinks must not lead outside the vault either
+    let canonical_root = vault_root.canonicalize().ok()?;
+    let canonical = path.canonicalize().ok()?;
+
+    if canonical.starts_with(&canonical_root) && canonical.is_file() {
+        Some(canonical)
+    } else {
+        None
+    }
+}
+
+/// MIME type for the asset formats notes can embed
+pub fn mime_type_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "pdf" => "application/pdf",
+        "csv" => "text/csv",
+        "txt" => "text/plain",
+        "md" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "geojson" => "application/geo+json",
+        "kml" => "application/vnd.google-earth.kml+xml",
+        "zip" => "application/zip",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Attachment types a webview would run as a document (scripts included);
+/// they are only ever offered as downloads
+fn is_active_content(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        "text/html" | "image/svg+xml" | "application/xml" | "text/xml"
+    )
+}
+
+/// Headers that keep a served file from acting as part of the app: it is
+/// sandboxed (no scripts, no IPC) if opened as a document, and attachments
+/// the webview would run are sent as downloads of opaque bytes
+fn with_safety_headers(
+    builder: tauri::http::response::Builder,
+    mime_type: &'static str,
+    is_attachment: bool,
+) -> tauri::http::response::Builder {
+    let builder = builder
+        .header(header::CONTENT_SECURITY_POLICY, "sandbox")
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff");
+
+    if is_attachment && is_active_content(mime_type) {
+        builder
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .header(header::CONTENT_DISPOSITION, "attachment")
+    } else {
+        builder.header(header::CONTENT_TYPE, mime_type)
+    }
+}
+
+/// Parse a single `Range: bytes=...` header into an inclusive byte range.
+/// Returns Err(()) when the range cannot be satisfied for a file of `len` bytes.
+pub fn parse_range(value: &str, len: u64) -> Result<(u64, u64), ()> {
+    let spec = value.trim().strip_prefix("bytes=").ok_or(())?;
+
+    // Multiple ranges are not supported; serve the first one
+    let spec = spec.split(',').next().ok_or(())?.trim();
+    let (start, end) = spec.split_once('-').ok_or(())?;
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            // Last N bytes
+            let suffix: u64 = suffix.parse().map_err(|_| ())?;
+            if suffix == 0 {
+                return Err(());
+            }
+            (len.saturating_sub(suffix), len.checked_sub(1).ok_or(())?)
+        }
+        (start, "") => (
+            start.parse().map_err(|_| ())?,
+            len.checked_sub(1).ok_or(())?,
+        ),
+        (start, end) => {
+            let start: u64 = start.parse().map_err(|_| ())?;
+            let end: u64 = end.parse().map_err(|_| ())?;
+            (start, end.min(len.saturating_sub(1)))
+        }
+    };
+
+    if start > end || start >= len {
+        return Err(());
+    }
+
+    Ok((start, end))
+}
+
+/// Read bytes `start..=end` of a file
+fn read_slice(file: &mut File, start: u64, end: u64) -> std::io::Result<Vec<u8>> {
+    let mut buffer = vec![0; (end - start + 1) as usize];
+    file.seek(SeekFrom::Start(start))?;
+    file.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(message.as_bytes().to_vec())
+        .unwrap()
+}
+
+/// Serve a `mapanote-asset://` request from the vault at `vault_path`
+pub fn handle_asset_request(
+    vault_path: Option<&str>,
+    request: &Request<Vec<u8>>,
+) -> Response<Vec<u8>> {
+    let vault_path = match vault_path {
+        Some(path) => path,
+        None => return error_response(StatusCode::SERVICE_UNAVAILABLE, "No vault opened"),
+    };
+
+    // Depending on the platform the first segment arrives as the host
+    // (`mapanote-asset://country/...`) or in the path (`http://mapanote-asset.localhost/country/...`)
+    let uri = request.uri();
+    let request_path = match uri.host() {
+        Some(host) if !host.ends_with("localhost") => format!("{}{}", host, uri.path()),
+        _ => uri.path().to_string(),
+    };
+
+    let path = match resolve_asset_path(Path::new(vault_path), &request_path) {
+        Some(path) => path,
+        None => return error_response(StatusCode::NOT_FOUND, "Asset not found"),
+    };
+
+    let mut file = match File::open(&path) {
+        Ok(file) => file,
+        Err(_) => return error_response(StatusCode::NOT_FOUND, "Asset not found"),
+    };
+
+    let len = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let mime_type = mime_type_for(&path);
+    let is_attachment = path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::file_name)
+        .is_some_and(|name| name == "attachments");
+    let response = |status| {
+        with_safety_headers(Response::builder().status(status), mime_type, is_attachment)
+            .header(header::ACCEPT_RANGES, "bytes")
+    };
+
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok());
+
+    match range {
+        Some(range) => {
+            let (start, end) = match parse_range(range, len) {
+                Ok(range) => range,
+                Err(()) => {
+                    return Response::builder()
+                        .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                        .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                        .body(Vec::new())
+                        .unwrap()
+                }
+            };
+
+            // Only read the requested slice of the file, at most one chunk
+            let end = end.min(start + MAX_RANGE_LEN - 1);
+            let buffer = match read_slice(&mut file, start, end) {
+                Ok(buffer) => buffer,
+                Err(e) => {
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        &format!("Failed to read asset: {}", e),
+                    )
+                }
+            };
+
+            response(StatusCode::PARTIAL_CONTENT)
+                .header(
+                    header::CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", start, end, len),
+                )
+                .header(header::CONTENT_LENGTH, buffer.len())
+                .body(buffer)
+                .unwrap()
+        }
+        None if len == 0 => response(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, 0)
+            .body(Vec::new())
+            .unwrap(),
+        None => {
+            // Without a range, send the first chunk; `Accept-Ranges` tells
+            // the viewer to fetch the rest of a larger file by range
+            let end = len.min(MAX_RANGE_LEN) - 1;
+            let buffer = match read_slice(&mut file, 0, end) {
+                Ok(buffer) => buffer,
+                Err(e) => {
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        &format!("Failed to read asset: {}", e),
+                    )
+                }
+            };
+
+            response(StatusCode::OK)
+                .header(header::CONTENT_LENGTH, buffer.len())
+                .body(buffer)
+                .unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestVault;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Ok((0, 99)));
+        assert_eq!(parse_range("bytes=900-", 1000), Ok((900, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Ok((900, 999)));
+        assert_eq!(parse_range("bytes=500-5000", 1000), Ok((500, 999)));
+        assert_eq!(parse_range("bytes=1000-", 1000), Err(()));
+        assert_eq!(parse_range("items=0-1", 1000), Err(()));
+    }
+
+    #[test]
+    fn test_resolve_asset_path_rejects_traversal() {
+        let vault = TestVault::new();
+        vault.write("notes/fi/assets/map one.png", b"png");
+
+        assert!(resolve_asset_path(&vault, "country/fi/assets/map%20one.png").is_some());
+        assert!(resolve_asset_path(&vault, "country%2Ffi%2Fassets%2Fmap%20one.png").is_some());
+        assert!(resolve_asset_path(&vault, "country/fi/assets/../../../vault.json").is_none());
+        assert!(resolve_asset_path(&vault, "country/fi/assets/%2E%2E/x.png").is_none());
+        assert!(resolve_asset_path(&vault, "notes/fi/assets/map%20one.png").is_none());
+        assert!(resolve_asset_path(&vault, "country/fi/other/map%20one.png").is_none());
+    }
+
+    #[test]
+    fn test_active_attachments_are_downloads() {
+        let vault = TestVault::new();
+        vault.write("notes/fi/attachments/01A/page.html", "<script>1</script>");
+        vault.write("notes/fi/attachments/01A/report.pdf", "%PDF");
+        vault.write("notes/fi/assets/map.svg", "<svg/>");
+
+        let get = |uri: &str| {
+            let request = Request::builder().uri(uri).body(Vec::new()).unwrap();
+            handle_asset_request(vault.to_str(), &request)
+        };
+
+        let page = get("mapanote-asset://country/fi/attachments/01A/page.html");
+        assert_eq!(
+            page.headers()[header::CONTENT_TYPE],
+            "application/octet-stream"
+        );
+        assert_eq!(page.headers()[header::CONTENT_DISPOSITION], "attachment");
+        assert_eq!(page.headers()[header::CONTENT_SECURITY_POLICY], "sandbox");
+
+        let report = get("mapanote-asset://country/fi/attachments/01A/report.pdf");
+        assert_eq!(report.headers()[header::CONTENT_TYPE], "application/pdf");
+        assert!(report.headers().get(header::CONTENT_DISPOSITION).is_none());
+
+        // Note images still render, but never run as a document
+        let map = get("mapanote-asset://country/fi/assets/map.svg");
+        assert_eq!(map.headers()[header::CONTENT_TYPE], "image/svg+xml");
+        assert_eq!(map.headers()[header::CONTENT_SECURITY_POLICY], "sandbox");
+    }
+
+    #[test]
+    fn test_range_responses_are_capped() {
+        let vault = TestVault::new();
+        let len = MAX_RANGE_LEN + 10;
+        vault.write("notes/fi/assets/clip.mp4", vec![7u8; len as usize]);
+
+        let request = Request::builder()
+            .uri("mapanote-asset://country/fi/assets/clip.mp4")
+            .header(header::RANGE, "bytes=0-")
+            .body(Vec::new())
+            .unwrap();
+        let response = handle_asset_request(vault.to_str(), &request);
+
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.body().len() as u64, MAX_RANGE_LEN);
+        assert_eq!(
+            response.headers()[header::CONTENT_RANGE],
+            format!("bytes 0-{}/{}", MAX_RANGE_LEN - 1, len).as_str()
+        );
+
+        // A plain request gets the first chunk, not the whole file
+        let request = Request::builder()
+            .uri("mapanote-asset://country/fi/assets/clip.mp4")
+            .body(Vec::new())
+            .unwrap();
+        let response = handle_asset_request(vault.to_str(), &request);
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body().len() as u64, MAX_RANGE_LEN);
+        assert_eq!(response.headers()[header::ACCEPT_RANGES], "bytes");
+    }
+}
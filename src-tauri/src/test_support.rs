@@ -0,0 +1,70 @@
+//! Fixtures shared by the command tests
+
+use mapanote_lib::models::VaultManifest;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// A vault in a temporary directory, removed when dropped (also when an
+/// assertion fails). The vault lives in `<tmp>/vault`, so tests can keep
+/// exports and import sources next to it with `scratch`. Derefs to the
+/// vault root.
+pub struct TestVault {
+    dir: TempDir,
+    root: PathBuf,
+}
+
+impl TestVault {
+    /// An empty vault with a fresh `vault.json`
+    pub fn new() -> Self {
+        let dir = tempfile::Builder::new()
+            .prefix("mapanote-test-")
+            .tempdir()
+            .expect("Failed to create test directory");
+        let root = dir.path().join("vault");
+        fs::create_dir_all(&root).unwrap();
+
+        let vault = TestVault { dir, root };
+        vault.write_manifest(&VaultManifest::new());
+        vault
+    }
+
+    /// Write a file relative to the vault root, creating its folders
+    pub fn write(&self, relative: &str, content: impl AsRef<[u8]>) -> PathBuf {
+        write_file(&self.root.join(relative), content)
+    }
+
+    pub fn write_manifest(&self, manifest: &VaultManifest) {
+        self.write("vault.json", serde_json::to_string(manifest).unwrap());
+    }
+
+    pub fn manifest(&self) -> VaultManifest {
+        serde_json::from_str(&fs::read_to_string(self.join("vault.json")).unwrap()).unwrap()
+    }
+
+    /// A folder next to the vault (export targets, import sources), removed
+    /// together with it
+    pub fn scratch(&self, name: &str) -> PathBuf {
+        let path = self.dir.path().join(name);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+}
+
+impl Deref for TestVault {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.root
+    }
+}
+
+/// Write a file, creating its folders
+pub fn write_file(path: &Path, content: impl AsRef<[u8]>) -> PathBuf {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, content).unwrap();
+    path.to_path_buf()
+}
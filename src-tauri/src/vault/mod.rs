@@ -30,7 +30,5 @@ pub fn get_country_metadata(slug: &str) -> Option<&'static CountryMetadata> {
 mod frontmatter;
 mod notes;
 mod reader;
-mod writer; // ← NEW
 
 pub use reader::VaultReader;
-pub use writer::VaultWriter; // ← NEW
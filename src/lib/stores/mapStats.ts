@@ -7,6 +7,7 @@ export interface CountryStats {
   noteCount: number;
   lastUpdated: string | null;
   tags: string[];
+  stale: boolean;             // Latest note older than the staleness threshold
 }
 
 export const mapStats = writable<Map<string, CountryStats>>(new Map());
@@ -28,6 +29,7 @@ export async function loadMapStats(): Promise<void> {
         noteCount: country.note_count,
         lastUpdated: country.last_updated ?? null,
         tags: country.tags,
+        stale: country.stale ?? false,
       });
     });
     
@@ -0,0 +1,65 @@
+import { writable } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+import type { DueReview, DueReviews, FollowUp } from '$lib/types';
+
+// Notes overdue for review and due within the next two weeks
+export const dueReviews = writable<DueReviews>({ overdue: [], upcoming: [] });
+
+/**
+ * Load notes due for review
+ */
+export async function loadDueReviews(): Promise<void> {
+  try {
+    dueReviews.set(await invoke<DueReviews>('get_due_reviews'));
+  } catch (error) {
+    console.error('Failed to load due reviews:', error);
+    dueReviews.set({ overdue: [], upcoming: [] });
+  }
+}
+
+/**
+ * Set (or clear) a note's review date and follow-up checklist
+ */
+export async function setNoteReview(
+  ownerType: string,
+  ownerId: string,
+  noteId: string,
+  reviewBy: string | null,
+  followUps: FollowUp[]
+): Promise<void> {
+  await invoke('set_note_review', {
+    ownerType,
+    ownerId,
+    noteId,
+    reviewBy,
+    followUps,
+  });
+  await loadDueReviews();
+}
+
+/**
+ * Whether the review date or checklist differs from what the note has
+ */
+export function reviewChanged(
+  note: { review_by?: string; follow_ups?: FollowUp[] },
+  reviewBy: string,
+  followUps: FollowUp[]
+): boolean {
+  return (
+    (note.review_by ?? '') !== reviewBy ||
+    JSON.stringify(note.follow_ups ?? []) !== JSON.stringify(followUps)
+  );
+}
+
+/**
+ * Mark a note reviewed, optionally scheduling the next review
+ */
+export async function markReviewed(review: DueReview, nextReviewBy?: string): Promise<void> {
+  await invoke('mark_reviewed', {
+    ownerType: review.source_type,
+    ownerId: review.owner_id,
+    noteId: review.note_id,
+    nextReviewBy: nextReviewBy ?? null,
+  });
+  await loadDueReviews();
+}
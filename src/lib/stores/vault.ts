@@ -1,8 +1,9 @@
-import { writable } from 'svelte/store';
+import { get, writable } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
 import { loadMapStats } from './mapStats';
 import { loadTopics } from './topics';
-import type { Country, Note, NoteWithSource } from "$lib/types"; 
+import { loadDueReviews } from './reviews';
+import type { Country, Note, NoteWithSource, Page } from "$lib/types"; 
 
 // Country metadata from embedded data
 export interface CountryMetadata {
@@ -10,9 +11,17 @@ export interface CountryMetadata {
   name: string;
   iso2: string;
   iso3: string;
+  iso_numeric: string;
   summary: string;
   region: string;
   subregion: string;
+  aliases: string[];
+  demonyms: string[];
+  capital?: string;
+  centroid?: { lat: number; lon: number };
+  bbox?: { min_lon: number; min_lat: number; max_lon: number; max_lat: number };
+  currencies: { code: string; name: string; symbol: string }[];
+  languages: { code: string; name: string }[];
 }
 
 // Vault state
@@ -22,6 +31,8 @@ export const countries = writable<Country[]>([]);
 export const currentCountry = writable<CountryMetadata | null>(null);
 export const currentNotes = writable<Note[]>([]);
 export const currentNotesWithSource = writable<NoteWithSource[]>([]); 
+// Cursor of the next page of the current country's notes, if any
+export const currentNotesCursor = writable<string | null>(null);
 
 // UI state
 export const isLoading = writable<boolean>(false);
@@ -50,6 +61,8 @@ export async function openVault(path: string): Promise<void> {
     console.log('Loading topics...');
     await loadTopics();
     console.log('Topics loaded');
+
+    await loadDueReviews();
     
   } catch (error) {
     console.error('Failed to open vault:', error);
@@ -70,34 +83,53 @@ export async function loadCountry(slug: string): Promise<void> {
     currentCountry.set(metadata);
     
     // Use the new command that includes topic notes
-    const notesWithSource = await invoke<NoteWithSource[]>('get_country_notes_with_topics', { slug });
+    const page = await invoke<Page<NoteWithSource>>('get_country_notes_with_topics', { slug });
     
     // Store the full data
-    currentNotesWithSource.set(notesWithSource);
-    
-    // Extract just the notes for backward compatibility
-    const notes = notesWithSource.map(nws => ({
-      id: nws.id,
-      title: nws.title,
-      content: nws.content,
-      date: nws.date,
-      tags: nws.tags,
-      topic_id: nws.topic_id,
-      country_targets: nws.country_targets,
-    }));
-    currentNotes.set(notes);
+    currentNotesWithSource.set(page.items);
+    currentNotes.set(page.items.map(toNote));
+    currentNotesCursor.set(page.next_cursor);
     
   } catch (error) {
     console.error('Failed to load country:', error);
     currentCountry.set(null);
     currentNotes.set([]);
     currentNotesWithSource.set([]);
+    currentNotesCursor.set(null);
     throw error;
   } finally {
     isLoading.set(false);
   }
 }
 
+/**
+ * Append the next page of the current country's notes
+ */
+export async function loadMoreCountryNotes(slug: string): Promise<void> {
+  const cursor = get(currentNotesCursor);
+  if (!cursor) return;
+
+  const page = await invoke<Page<NoteWithSource>>('get_country_notes_with_topics', { slug, cursor });
+  currentNotesWithSource.update(notes => [...notes, ...page.items]);
+  currentNotes.update(notes => [...notes, ...page.items.map(toNote)]);
+  currentNotesCursor.set(page.next_cursor);
+}
+
+// Extract just the notes for backward compatibility
+function toNote(nws: NoteWithSource): Note {
+  return {
+    id: nws.id,
+    title: nws.title,
+    content: nws.content,
+    date: nws.date,
+    tags: nws.tags,
+    topic_id: nws.topic_id,
+    country_targets: nws.country_targets,
+    created_at: nws.created_at,
+    updated_at: nws.updated_at,
+  };
+}
+
 export async function loadCountries(): Promise<void> {
   isLoading.set(true);
   
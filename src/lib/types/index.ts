@@ -6,9 +6,12 @@ export interface Country {
   summary: string;
   region: string;
   subregion: string;
+  kind?: string;             // Set for vault-defined entities
+  map_ref?: string;
   note_count: number;        
   last_updated?: string;     
   tags: string[];            
+  stale?: boolean;           // Latest note older than the staleness threshold
 }
 export interface CountryMetadata {
   slug: string;
@@ -23,6 +26,30 @@ export interface Note {
   tags: string[];
   topic_id?: string;           // ← ADD
   country_targets: string[];   // ← ADD
+  attachments?: Attachment[];
+  visibility?: Visibility;
+  created_at: string; // RFC3339
+  updated_at: string; // RFC3339
+  review_by?: string;       // YYYY-MM-DD the assessment should be revisited by
+  follow_ups?: FollowUp[];
+  reviewed?: string[];      // RFC3339 time of every review, oldest first
+}
+
+// Item of a note's follow-up checklist (see `set_note_review`)
+export interface FollowUp {
+  text: string;
+  done: boolean;
+}
+
+// Who a note may be shown to outside the app (see `set_note_visibility`)
+export type Visibility = "private" | "internal" | "publishable";
+
+// File kept in the note's attachments/<note_id>/ folder
+export interface Attachment {
+  name: string;
+  mime: string;
+  size: number;
+  added: string;
 }
 
 export interface NoteWithSource {
@@ -33,6 +60,11 @@ export interface NoteWithSource {
   tags: string[];
   topic_id?: string;
   country_targets: string[];
+  created_at: string;
+  updated_at: string;
+  review_by?: string;
+  follow_ups?: FollowUp[];
+  reviewed?: string[];
   source_type: string;         
   source_name: string;         
   topic_color?: string;        
@@ -99,4 +131,332 @@ export interface RecentActivity {
   source_type: string; // "country" or "topic"
   topic_name?: string;
   topic_color?: string;
-}
\ No newline at end of file
+  created_at: string;
+  updated_at: string;
+  activity: "created" | "edited";
+}
+
+// Named set of countries (EU, NATO...), referenced in targets as `group:<slug>`
+export interface CountryGroup {
+  slug: string;
+  name: string;
+  description?: string;
+  members: string[];
+  builtin: boolean;
+}
+
+// Place detected in note text (see `suggest_countries_for_text`)
+export interface CountrySuggestion {
+  slug: string;
+  name: string;
+  matches: {
+    field: string; // "title" or "content"
+    text: string;
+    start: number; // Offsets into the field's string
+    end: number;
+  }[];
+}
+
+// Returned by `add_topic_note`
+export interface TopicNoteCreated extends Note {
+  suggested_targets: CountrySuggestion[];
+}
+
+// File in a country's or topic's assets folder (see `list_assets`)
+export interface AssetInfo {
+  owner_type: string; // "country" or "topic"
+  owner_id: string;
+  path: string; // e.g. "assets/3f2a….png"
+  size: number;
+  thumbnail?: string;
+  referenced_by: { note_id: string; note_title: string }[];
+}
+
+// Result of `import_markdown_folder` (also returned for dry runs)
+export interface MarkdownImportReport {
+  dry_run: boolean;
+  imported: {
+    source: string; // Path relative to the imported folder
+    slug: string;
+    matched_by: string; // e.g. "frontmatter:country", "tag:finland", "folder:Finland"
+    title: string;
+    note_id: string | null;
+    images: number;
+  }[];
+  unmapped: { source: string; title: string; candidates: string[] }[];
+  already_imported: string[];
+  missing_images: string[];
+  failed_images: { source: string; error: string }[]; // source is "<file>: <image>"
+  failed: { source: string; error: string }[];
+}
+
+// Column mapping for `import_events` (CSV headers or JSON keys)
+export interface EventColumnMapping {
+  date: string;
+  country: string; // ISO2, ISO3 or name; several separated by ";" or "|"
+  title?: string;
+  description?: string;
+  actor?: string;
+  tags?: string;
+  source_key?: string; // Stable row ID used to skip rows imported before
+}
+
+export interface EventImportOptions {
+  topic_id?: string; // Create topic notes instead of country notes
+  tags?: string[];
+  date_format?: string; // chrono format, e.g. "%m/%d/%Y"
+  dataset?: string; // Namespace for row keys; defaults to the file's path
+  dry_run?: boolean;
+}
+
+export interface EventImportSummary {
+  dry_run: boolean;
+  created: { row: number; source_key: string; targets: string[]; note_ids: string[] }[];
+  skipped: { row: number; source_key: string }[];
+  failed: { row: number; error: string }[];
+}
+
+// Result of `export_static_site`
+export interface SiteExportReport {
+  destination: string;
+  pages: number;
+  notes: number;
+  assets: number;
+  attachments: number;
+}
+
+// Results of `export_vault_data` / `import_vault_data` (notes.jsonl + CSVs)
+export interface DataExportReport {
+  destination: string;
+  notes: number;
+  topics: number;
+  relations: number;
+  files: number; // Assets and attachments copied
+}
+
+export interface DataImportReport {
+  notes_imported: number;
+  notes_skipped: number;
+  topics_imported: number;
+  relations_imported: number;
+  files: number;
+}
+
+// Filter for `export_country_stats`
+export interface StatsFilter {
+  from?: string; // YYYY-MM-DD, inclusive
+  to?: string;
+  tag?: string; // Also matches hierarchical children
+}
+
+export interface GisExportReport {
+  geojson_path: string;
+  csv_path: string;
+  countries: number;
+}
+
+// Result of `generate_briefing` (PDF for a country or topic)
+export interface BriefingReport {
+  path: string;
+  pages: number;
+  notes: number;
+  images: number;
+}
+
+// Export template sets in `.mapanote/templates/export/<name>/`, passed as
+// `template` to the export commands
+export interface ExportTemplateFile {
+  scope: 'country' | 'topic' | 'note';
+  format: 'markdown' | 'html';
+  file_name: string;
+}
+
+export interface ExportTemplate {
+  name: string;
+  files: ExportTemplateFile[];
+}
+
+// Note template in `.mapanote/templates/notes/<id>.md`. `add_note_from_template`
+// returns a TopicNoteCreated (suggestions are empty for country notes).
+export interface NoteTemplate {
+  id: string;
+  name: string;
+  title: string; // Pattern with placeholders such as {{country.name}}
+  tags: string[];
+  country_targets: string[];
+  body: string;
+}
+
+// Saved searches, stored in `.mapanote/config.json`
+export type SearchSort = 'newest' | 'oldest' | 'title';
+
+export interface SearchCriteria {
+  query?: string;
+  tag?: string;
+  country?: string; // Slug or `group:<slug>`
+  topic_id?: string;
+  from?: string; // YYYY-MM-DD, inclusive
+  to?: string;
+  sort?: SearchSort;
+}
+
+export interface SavedSearch {
+  name: string;
+  query: string;
+  tag: string | null;
+  country: string | null;
+  topic_id: string | null;
+  from: string | null;
+  to: string | null;
+  sort: SearchSort;
+  created_at: string;
+  last_run_at: string | null;
+  last_matches: string[]; // Note IDs matched by the last run
+}
+
+export interface SavedSearchMatch {
+  note_id: string;
+  title: string;
+  date: string;
+  tags: string[];
+  source_type: 'country' | 'topic';
+  owner_id: string;
+  owner_name: string;
+  snippet: string;
+  attachment_match?: {
+    name: string;
+    page: number | null; // 1-based, for PDFs
+    snippet: string;
+  }; // Set when only an attachment's text matched
+  is_new: boolean;
+}
+
+export interface SavedSearchRun {
+  search: SavedSearch;
+  results: SavedSearchMatch[];
+  new_matches: number;
+  previous_run_at: string | null;
+}
+
+// Order of a paginated note listing
+export type NoteSort = "date" | "created" | "updated" | "title";
+
+// One page of a listing; pass `next_cursor` back as `cursor` for the next one
+export interface Page<T> {
+  items: T[];
+  next_cursor: string | null;
+}
+
+// A change to the vault, from `.mapanote/journal.jsonl` (see `get_journal`)
+export interface JournalEvent {
+  timestamp: string; // RFC3339
+  operation: string; // "<entity>.<action>", e.g. "note.created"
+  entity_ids?: string[];
+  country?: string;
+  topic_id?: string;
+  author?: string;
+}
+
+export interface JournalFilter {
+  operation?: string; // "note" matches every "note.*" operation
+  entity_id?: string;
+  country?: string;
+  topic_id?: string;
+  author?: string;
+  from?: string; // Inclusive date or RFC3339 timestamp
+  to?: string;
+  limit?: number;
+}
+
+export interface CompactionReport {
+  removed: number;
+  remaining: number;
+}
+
+// Period length of the created/edited series in `get_vault_analytics`
+export type AnalyticsPeriod = "month" | "quarter" | "year";
+
+export interface RegionCount {
+  region: string;
+  notes: number;
+  countries_covered: number;
+  countries_total: number;
+}
+
+export interface SubregionCount extends RegionCount {
+  subregion: string;
+}
+
+export interface PeriodActivity {
+  period: string; // "2025-03", "2025-Q1" or "2025"
+  created: number;
+  edited: number;
+}
+
+// Chart-ready note counts; periods are contiguous (empty ones are zero)
+export interface VaultAnalytics {
+  total_notes: number;
+  regions: RegionCount[];
+  subregions: SubregionCount[];
+  tags: { tag: string; notes: number }[];
+  topics: { topic_id: string; title: string; color?: string; notes: number }[];
+  months: { month: string; notes: number }[];
+  activity: PeriodActivity[];
+  coverage: {
+    countries_covered: number;
+    countries_total: number;
+    share: number; // 0 to 1
+  };
+}
+
+export type WatchPriority = 'high' | 'normal' | 'low';
+
+export interface StaleCountry {
+  slug: string;
+  name: string;
+  region: string;
+  subregion: string;
+  last_note: string;
+  days_since: number;
+  threshold_days: number;
+  priority: WatchPriority;
+}
+
+export interface StaleTopic {
+  topic_id: string;
+  title: string;
+  last_note: string | null; // null when the topic has no notes
+  days_since: number | null;
+  threshold_days: number;
+  priority: WatchPriority;
+}
+
+export interface UncoveredCountry {
+  slug: string;
+  name: string;
+  region: string;
+  subregion: string;
+}
+
+export interface StaleCoverageReport {
+  threshold_days: number;
+  countries: StaleCountry[];
+  topics: StaleTopic[];
+  uncovered: UncoveredCountry[];
+}
+
+export interface DueReview {
+  note_id: string;
+  title: string;
+  source_type: 'country' | 'topic';
+  owner_id: string;
+  owner_name: string;
+  review_by: string;
+  days_until: number; // Negative once overdue
+  open_follow_ups: string[];
+}
+
+export interface DueReviews {
+  overdue: DueReview[];
+  upcoming: DueReview[];
+}